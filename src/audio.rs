@@ -0,0 +1,158 @@
+use crate::util::{get_config_dir, get_process_names, get_state_dir};
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SoundServer {
+    PipeWire,
+    PulseAudio,
+    // No sound server, programs talk to the ALSA devices directly
+    Alsa,
+}
+
+impl SoundServer {
+    pub fn name(self) -> &'static str {
+        match self {
+            SoundServer::PipeWire => "PipeWire",
+            SoundServer::PulseAudio => "PulseAudio",
+            SoundServer::Alsa => "ALSA",
+        }
+    }
+}
+
+/// A sound card, as listed in /proc/asound/cards
+struct Card {
+    index: usize,
+    name: String,
+}
+
+fn runtime_dir() -> PathBuf {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(format!("/run/user/{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Each card takes two lines, e.g.
+/// ```text
+///  0 [PCH            ]: HDA-Intel - HDA Intel PCH
+///                       HDA Intel PCH at 0xf7f10000 irq 32
+/// ```
+fn get_cards() -> Vec<Card> {
+    let cards = fs::read_to_string("/proc/asound/cards").unwrap_or_default();
+
+    cards
+        .lines()
+        .filter_map(|line| {
+            let (index, description) = line.split_once('[')?;
+            let index = index.trim().parse().ok()?;
+            let (_, name) = description.split_once(" - ")?;
+
+            Some(Card { index, name: name.trim().to_string() })
+        })
+        .collect()
+}
+
+/// Guesses the sound server in use from its sockets and processes
+pub fn get_sound_server() -> Option<SoundServer> {
+    let runtime_dir = runtime_dir();
+    let processes = get_process_names();
+    let is_running = |name: &str| processes.iter().any(|process| process == name);
+
+    // pipewire-pulse also creates pulse/native, so PipeWire has to be checked first
+    if runtime_dir.join("pipewire-0").exists() || is_running("pipewire") {
+        Some(SoundServer::PipeWire)
+    } else if runtime_dir.join("pulse").join("native").exists() || is_running("pulseaudio") {
+        Some(SoundServer::PulseAudio)
+    } else if !get_cards().is_empty() {
+        Some(SoundServer::Alsa)
+    } else {
+        None
+    }
+}
+
+/// Name of the default sink chosen by the user, e.g. "alsa_output.pci-0000_00_1f.3.analog-stereo"
+fn configured_sink(server: SoundServer) -> Option<String> {
+    let config_dir = get_config_dir()?;
+    let state_dir = get_state_dir()?;
+
+    let text = match server {
+        // WirePlumber, or pipewire-media-session on older setups
+        SoundServer::PipeWire => [
+            state_dir.join("wireplumber").join("default-nodes"),
+            config_dir.join("pipewire").join("media-session.d").join("default-nodes"),
+        ]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())?,
+        // e.g. ~/.config/pulse/<machine-id>-default-sink
+        SoundServer::PulseAudio => fs::read_dir(config_dir.join("pulse"))
+            .ok()?
+            .flatten()
+            .find(|entry| entry.file_name().to_string_lossy().ends_with("-default-sink"))
+            .and_then(|entry| fs::read_to_string(entry.path()).ok())?,
+        SoundServer::Alsa => return None,
+    };
+
+    let start = text.find("alsa_output.")?;
+    let sink = text[start..]
+        .split(|ch: char| ch == '"' || ch.is_whitespace())
+        .next()?;
+
+    Some(sink.to_string())
+}
+
+/// Finds the card of a sink named after its PCI address,
+/// e.g. "alsa_output.pci-0000_00_1f.3.analog-stereo"
+fn card_of_sink(sink: &str, cards: &[Card]) -> Option<usize> {
+    let address = sink.strip_prefix("alsa_output.pci-")?;
+    // The address itself contains a dot, e.g. "0000_00_1f.3"
+    let (bus, rest) = address.split_once('.')?;
+    let function = rest.split('.').next()?;
+    let address = format!("{}.{}", bus.replace('_', ":"), function);
+
+    cards.iter().map(|card| card.index).find(|index| {
+        let device = Path::new("/sys/class/sound/").join(format!("card{}", index)).join("device");
+        fs::canonicalize(device).is_ok_and(|device| device.ends_with(&address))
+    })
+}
+
+/// Whether any playback stream of the card is running
+fn is_playing(index: usize) -> bool {
+    let card_dir = Path::new("/proc/asound/").join(format!("card{}", index));
+    let entries = match fs::read_dir(card_dir) {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+
+    entries
+        .flatten()
+        // Playback devices end with 'p', e.g. "pcm0p"
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("pcm") && name.ends_with('p')
+        })
+        .any(|entry| {
+            let status = fs::read_to_string(entry.path().join("sub0").join("status"));
+            status.is_ok_and(|status| status.contains("RUNNING"))
+        })
+}
+
+/// Name of the card behind the default sink, e.g. "HDA Intel PCH".
+/// Falls back to the card which is playing, and then to the first one.
+pub fn get_default_card(server: SoundServer) -> Option<String> {
+    let cards = get_cards();
+
+    let index = configured_sink(server)
+        .and_then(|sink| card_of_sink(&sink, &cards))
+        .or_else(|| cards.iter().map(|card| card.index).find(|&index| is_playing(index)))
+        .or_else(|| cards.first().map(|card| card.index))?;
+
+    cards
+        .into_iter()
+        .find(|card| card.index == index)
+        .map(|card| card.name)
+}
@@ -0,0 +1,34 @@
+use crate::util::read_u64;
+
+use std::{fs, path::Path};
+
+/// Prefer the interfaces which drive the panel through the firmware or the
+/// platform driver over raw GPU registers, as e.g. systemd-backlight does
+fn type_priority(backlight: &Path) -> u8 {
+    match fs::read_to_string(backlight.join("type")).as_deref().map(str::trim) {
+        Ok("firmware") => 0,
+        Ok("platform") => 1,
+        _ => 2,
+    }
+}
+
+/// Brightness of the backlight as a percentage of its maximum
+pub fn get_brightness() -> Option<f64> {
+    let mut backlights: Vec<_> = fs::read_dir("/sys/class/backlight/")
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    backlights.sort_by_key(|backlight| type_priority(backlight));
+
+    backlights.iter().find_map(|backlight| {
+        let brightness = read_u64(&backlight.join("brightness"))?;
+        let max_brightness = read_u64(&backlight.join("max_brightness"))?;
+
+        if max_brightness == 0 {
+            None
+        } else {
+            Some(brightness as f64 / max_brightness as f64 * 100.0)
+        }
+    })
+}
@@ -0,0 +1,102 @@
+use crate::util::{read_trimmed, read_u64};
+
+use std::{fs, path::Path};
+
+#[derive(Debug)]
+pub struct Battery {
+    // Name of the power supply, e.g. "BAT0"
+    pub name:     String,
+    // Charge percentage
+    pub capacity: u8,
+    // "Charging", "Discharging", "Full", "Not charging" or "Unknown"
+    pub status:   String,
+    // Full charge capacity relative to the design capacity, in percent
+    pub health:   Option<f64>,
+    // Charge cycles, when reported by the firmware
+    pub cycles:   Option<u64>,
+    // Charge or discharge rate, in watts
+    pub rate:     Option<f64>,
+}
+
+/// Batteries report either energy (µWh) or charge (µAh) values
+fn get_health(battery_dir: &Path) -> Option<f64> {
+    let (full, full_design) = ["energy", "charge"].iter().find_map(|kind| {
+        Some((
+            read_u64(&battery_dir.join(format!("{}_full", kind)))?,
+            read_u64(&battery_dir.join(format!("{}_full_design", kind)))?,
+        ))
+    })?;
+
+    if full_design == 0 {
+        return None;
+    }
+
+    Some(full as f64 / full_design as f64 * 100.0)
+}
+
+fn read_f64(path: &Path) -> Option<f64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+/// Batteries report either power (µW) or current (µA) and voltage (µV).
+/// Some of them report negative values while discharging.
+fn get_rate(battery_dir: &Path) -> Option<f64> {
+    let microwatts = read_f64(&battery_dir.join("power_now")).or_else(|| {
+        let current = read_f64(&battery_dir.join("current_now"))?;
+        let voltage = read_f64(&battery_dir.join("voltage_now"))?;
+        Some(current * voltage / 1_000_000.0)
+    })?;
+
+    Some(microwatts.abs() / 1_000_000.0).filter(|&watts| watts > 0.0)
+}
+
+/// Checks whether an AC adapter is connected.
+/// Returns None if the system has no AC adapter, e.g. desktops.
+pub fn ac_online() -> Option<bool> {
+    fs::read_dir("/sys/class/power_supply/")
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| read_trimmed(&entry.path().join("type")).as_deref() == Some("Mains"))
+        .filter_map(|entry| read_trimmed(&entry.path().join("online")))
+        .fold(None, |online, adapter| {
+            Some(online.unwrap_or(false) || adapter == "1")
+        })
+}
+
+/// Lists the batteries powering the system. Desktops usually have none.
+pub fn get_batteries() -> Vec<Battery> {
+    let entries = match fs::read_dir("/sys/class/power_supply/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut batteries: Vec<Battery> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.path();
+
+            if read_trimmed(&path.join("type"))? != "Battery" {
+                return None;
+            }
+
+            // Skip the batteries of peripherals, such as wireless mice
+            if read_trimmed(&path.join("scope")).as_deref() == Some("Device") {
+                return None;
+            }
+
+            Some(Battery {
+                name:     entry.file_name().to_string_lossy().into_owned(),
+                capacity: read_trimmed(&path.join("capacity"))?.parse().ok()?,
+                status:   read_trimmed(&path.join("status"))
+                    .unwrap_or_else(|| "Unknown".to_string()),
+                health:   get_health(&path),
+                // Some firmwares report 0 when they don't keep track of cycles
+                cycles:   read_u64(&path.join("cycle_count")).filter(|&cycles| cycles > 0),
+                rate:     get_rate(&path),
+            })
+        })
+        .collect();
+
+    batteries.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    batteries
+}
@@ -0,0 +1,78 @@
+use crate::{disk::Mount, util::read_u64};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub struct BtrfsUsage {
+    // Data and metadata stored in the filesystem, in bytes
+    pub used:         u64,
+    // Size of the devices taking the data profile into account (e.g. half of it for RAID1)
+    pub total:        u64,
+    // Block group profile of the data, e.g. "single", "dup" or "raid1"
+    pub data_profile: Option<String>,
+}
+
+/// Finds /sys/fs/btrfs/{UUID} for the filesystem stored in the given device, e.g. "nvme0n1p2"
+fn find_fs_dir(device_name: &str) -> Option<PathBuf> {
+    fs::read_dir("/sys/fs/btrfs/")
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|fs_dir| fs_dir.join("devices").join(device_name).exists())
+}
+
+/// The profile of a block group type is the name of its only subdirectory,
+/// e.g. /sys/fs/btrfs/{UUID}/allocation/data/raid1
+fn read_profile(block_group_dir: &Path) -> Option<String> {
+    fs::read_dir(block_group_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+}
+
+/// The free space reported by statvfs is misleading on btrfs, since space is
+/// allocated in chunks and may be replicated, so we read the allocation
+/// information from sysfs instead.
+pub fn get_usage(mount: &Mount) -> Option<BtrfsUsage> {
+    if mount.fs_type != "btrfs" {
+        return None;
+    }
+
+    let fs_dir = find_fs_dir(&mount.device_name()?)?;
+    let allocation_dir = fs_dir.join("allocation");
+
+    let used = ["data", "metadata", "system"]
+        .iter()
+        .filter_map(|block_group| read_u64(&allocation_dir.join(block_group).join("bytes_used")))
+        .sum();
+
+    // Sizes of the devices are given in 512-byte sectors
+    let raw_total: u64 = fs::read_dir(fs_dir.join("devices"))
+        .ok()?
+        .filter_map(Result::ok)
+        .filter_map(|device| read_u64(&device.path().join("size")))
+        .map(|sectors| sectors * 512)
+        .sum();
+
+    // How many bytes of the devices are needed to store a byte of data
+    let data_dir = allocation_dir.join("data");
+    let replication_ratio = match (
+        read_u64(&data_dir.join("disk_total")),
+        read_u64(&data_dir.join("total_bytes")),
+    ) {
+        (Some(disk_total), Some(total_bytes)) if total_bytes > 0 => {
+            disk_total as f64 / total_bytes as f64
+        },
+        _ => 1.0,
+    };
+
+    Some(BtrfsUsage {
+        used,
+        total: (raw_total as f64 / replication_ratio) as u64,
+        data_profile: read_profile(&data_dir),
+    })
+}
@@ -0,0 +1,34 @@
+use std::fs;
+
+/// uvcvideo names its devices "<product>: <product>", truncated to 31 characters,
+/// e.g. "Integrated Camera: Integrated C"
+fn clean_name(name: &str) -> &str {
+    match name.split_once(": ") {
+        Some((product, repeated)) if product.starts_with(repeated) => product,
+        _ => name,
+    }
+}
+
+/// Names of the video capture devices, e.g. ["Integrated Camera"]
+pub fn get_cameras() -> Vec<String> {
+    let entries = match fs::read_dir("/sys/class/video4linux/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut cameras: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        // Cameras may expose more nodes, e.g. for metadata, but the first one captures the video
+        .filter(|device| {
+            fs::read_to_string(device.join("index")).map_or(true, |index| index.trim() == "0")
+        })
+        .filter_map(|device| fs::read_to_string(device.join("name")).ok())
+        .map(|name| clean_name(name.trim()).to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    cameras.sort();
+    cameras.dedup();
+    cameras
+}
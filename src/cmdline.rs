@@ -0,0 +1,118 @@
+use std::fs;
+
+/// Parameters worth showing off, by name or by the prefix of their module, e.g. "amdgpu."
+const INTERESTING_PARAMETERS: &[&str] = &[
+    // CPU vulnerabilities
+    "mitigations",
+    "nosmt",
+    "pti",
+    "spectre_v2",
+    "spec_store_bypass_disable",
+    "retbleed",
+    "split_lock_detect",
+    // IOMMU and passthrough
+    "iommu",
+    "intel_iommu",
+    "amd_iommu",
+    "vfio-pci.",
+    "vfio_pci.",
+    // Scheduling and CPU isolation
+    "preempt",
+    "threadirqs",
+    "isolcpus",
+    "nohz",
+    "nohz_full",
+    "rcu_nocbs",
+    "irqaffinity",
+    // Power management
+    "amd_pstate",
+    "intel_pstate",
+    "intel_idle.",
+    "processor.max_cstate",
+    "pcie_aspm",
+    "acpi",
+    // Memory
+    "hugepages",
+    "hugepagesz",
+    "default_hugepagesz",
+    "transparent_hugepage",
+    "zswap.",
+    // Graphics
+    "nvidia-drm.",
+    "nvidia_drm.",
+    "i915.",
+    "amdgpu.",
+    "nouveau.",
+    "nomodeset",
+    // Security modules and lockdown
+    "lsm",
+    "security",
+    "selinux",
+    "apparmor",
+    "lockdown",
+    "module.sig_enforce",
+    // Init and cgroups
+    "init",
+    "systemd.unit",
+    "systemd.unified_cgroup_hierarchy",
+    "cgroup_no_v1",
+    "psi",
+];
+
+/// Splits the command line into its parameters, whose values may be quoted,
+/// e.g. `dyndbg="file drm* +p"`
+fn split_parameters(cmdline: &str) -> Vec<String> {
+    let mut parameters = vec![];
+    let mut parameter = String::new();
+    let mut in_quotes = false;
+
+    for ch in cmdline.trim().chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ch if ch.is_whitespace() && !in_quotes => {
+                if !parameter.is_empty() {
+                    parameters.push(std::mem::take(&mut parameter));
+                }
+                continue;
+            },
+            _ => {},
+        }
+        parameter.push(ch);
+    }
+    if !parameter.is_empty() {
+        parameters.push(parameter);
+    }
+
+    parameters
+}
+
+fn is_interesting(parameter: &str) -> bool {
+    let name = parameter.split('=').next().unwrap_or_default();
+
+    INTERESTING_PARAMETERS.iter().any(|interesting| {
+        if interesting.ends_with('.') {
+            name.starts_with(interesting)
+        } else {
+            name == *interesting
+        }
+    })
+}
+
+/// The parameters the kernel was booted with, either all of them or only the ones
+/// which tune it, e.g. "mitigations=off amd_pstate=active"
+pub fn get_cmdline(full: bool) -> Option<String> {
+    let cmdline = fs::read_to_string("/proc/cmdline").ok()?;
+
+    // What follows "--" is passed on to init
+    let parameters: Vec<String> = split_parameters(&cmdline)
+        .into_iter()
+        .take_while(|parameter| parameter != "--")
+        .filter(|parameter| full || is_interesting(parameter))
+        .collect();
+
+    if parameters.is_empty() {
+        None
+    } else {
+        Some(parameters.join(" "))
+    }
+}
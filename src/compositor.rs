@@ -0,0 +1,104 @@
+use crate::util::get_process_names;
+
+use std::{
+    env, fs, mem,
+    os::unix::{io::AsRawFd, net::UnixStream},
+    path::PathBuf,
+};
+
+/// Wayland compositors, by the name of their process
+const WAYLAND_COMPOSITORS: &[(&str, &str)] = &[
+    ("Hyprland", "Hyprland"),
+    ("sway", "Sway"),
+    ("river", "river"),
+    ("wayfire", "Wayfire"),
+    ("labwc", "labwc"),
+    ("niri", "niri"),
+    ("dwl", "dwl"),
+    ("hikari", "hikari"),
+    ("cage", "Cage"),
+    ("gamescope", "gamescope"),
+    ("weston", "Weston"),
+    ("kwin_wayland", "KWin"),
+    ("gnome-shell", "Mutter"),
+    ("cosmic-comp", "COSMIC"),
+];
+
+/// Standalone compositors for X11 window managers
+const X11_COMPOSITORS: &[(&str, &str)] = &[
+    ("picom", "picom"),
+    ("compton", "compton"),
+    ("xcompmgr", "xcompmgr"),
+    ("compiz", "Compiz"),
+];
+
+fn find_by_process(process: &str, compositors: &[(&str, &'static str)]) -> Option<&'static str> {
+    compositors
+        .iter()
+        .find(|(name, _)| *name == process)
+        .map(|(_, pretty_name)| *pretty_name)
+}
+
+/// Some compositors advertise themselves to the clients they start
+fn compositor_from_env() -> Option<&'static str> {
+    if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        Some("Hyprland")
+    } else if env::var_os("SWAYSOCK").is_some() {
+        Some("Sway")
+    } else if env::var_os("NIRI_SOCKET").is_some() {
+        Some("niri")
+    } else {
+        None
+    }
+}
+
+/// Asks the kernel which process is listening on the Wayland socket
+fn compositor_from_socket() -> Option<&'static str> {
+    let display = PathBuf::from(env::var_os("WAYLAND_DISPLAY")?);
+    let socket = if display.is_absolute() {
+        display
+    } else {
+        PathBuf::from(env::var_os("XDG_RUNTIME_DIR")?).join(display)
+    };
+
+    let stream = UnixStream::connect(socket).ok()?;
+
+    let mut credentials: libc::ucred = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let ret_val = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if ret_val != 0 || credentials.pid <= 0 {
+        return None;
+    }
+
+    let process = fs::read_to_string(format!("/proc/{}/comm", credentials.pid)).ok()?;
+    find_by_process(process.trim_end(), WAYLAND_COMPOSITORS)
+}
+
+/// Name of the Wayland compositor of the session, e.g. "Hyprland"
+pub fn get_wayland_compositor() -> Option<&'static str> {
+    env::var_os("WAYLAND_DISPLAY")?;
+
+    compositor_from_env().or_else(compositor_from_socket).or_else(|| {
+        get_process_names()
+            .iter()
+            .find_map(|process| find_by_process(process, WAYLAND_COMPOSITORS))
+    })
+}
+
+/// Name of the compositor of the session, e.g. "Hyprland" or "picom"
+pub fn get_compositor() -> Option<&'static str> {
+    get_wayland_compositor().or_else(|| {
+        env::var_os("DISPLAY")?;
+        get_process_names()
+            .iter()
+            .find_map(|process| find_by_process(process, X11_COMPOSITORS))
+    })
+}
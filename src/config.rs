@@ -0,0 +1,335 @@
+use crate::util::get_config_dir;
+
+use std::{env, fs, path::PathBuf};
+
+/// Which frequency the CPU line shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CpuFreq {
+    // The highest frequency the CPU may be scaled to
+    Max,
+    // The current frequency, averaged across the CPUs
+    Current,
+    // The lowest and highest frequencies the CPU supports
+    Range,
+}
+
+/// Which kernel parameters the cmdline line shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cmdline {
+    Hidden,
+    // Only the parameters which tune the kernel, e.g. mitigations=off
+    Interesting,
+    Full,
+}
+
+/// How much of the IPv6 addresses the ipv6 line shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ipv6 {
+    Hidden,
+    // Only the network the address is in, e.g. 2001:db8:1:2::/64
+    Prefix,
+    Full,
+}
+
+/// How much of the MAC addresses the mac line shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mac {
+    Hidden,
+    // Only the part which names the vendor, e.g. a4:83:e7:xx:xx:xx
+    Oui,
+    Full,
+}
+
+/// Colors of the information, which must stay readable on the terminal's background
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    // Follow the background color reported by the terminal
+    Auto,
+    Dark,
+    Light,
+}
+
+/// User preferences, read from `$XDG_CONFIG_HOME/pulga/pulga.toml` and then
+/// overridden by command-line flags.
+///
+/// The configuration file holds one `key = value` pair per line, e.g.
+/// ```toml
+/// # Show the CPU package power draw
+/// power = true
+/// sample_interval = 500
+/// sensors = ["k10temp/Tctl", "nvme/*"]
+/// ```
+/// and every key can also be given as a flag, such as `--power`,
+/// `--no-power`, `--sample-interval=500` or `--sensors=k10temp/Tctl,nvme/*`.
+#[derive(Debug)]
+pub struct Config {
+    // Show the logo of a random distro
+    pub random_distro:   bool,
+    // Show the CPU package power draw, read from RAPL
+    pub power:           bool,
+    // Show the CPU utilization, sampled from /proc/stat
+    pub cpu_usage:       bool,
+    // Append the sizes of the L2 and L3 caches to the CPU line
+    pub cpu_cache:       bool,
+    // Frequency shown in the CPU line
+    pub cpu_freq:        CpuFreq,
+    // Show the I/O throughput of the root device
+    pub disk_io:         bool,
+    // Show the usage of every mounted filesystem, not only of `/`
+    pub mounts:          bool,
+    // Show the pressure stall information of the CPU, memory and I/O
+    pub pressure:        bool,
+    // Show the size, type and speed of the RAM modules, read from SMBIOS
+    pub memory_modules:  bool,
+    // Interval between the samples of fields which need two, in milliseconds.
+    // If 0, such fields report totals or averages since boot instead.
+    pub sample_interval: u64,
+    // Count the page cache and other reclaimable memory as used, as older versions did
+    pub cache_as_used:   bool,
+    // hwmon sensors to show, e.g. "k10temp/Tctl", "nvme/*" or "*" for all of them
+    pub sensors:         Vec<String>,
+    // List the connected USB devices, other than hubs
+    pub usb:             bool,
+    // List the webcams and other video capture devices
+    pub camera:          bool,
+    // Count the packages with pending updates, from the metadata last synced
+    pub updates:         bool,
+    // Show the supplementary groups of the user
+    pub groups:          bool,
+    // Show the versions of the compilers and interpreters in $PATH
+    pub toolchains:      bool,
+    // Keep track of the longest uptime, and show it next to the current one
+    pub uptime_record:   bool,
+    // Colors of the information, for dark or light backgrounds
+    pub theme:           Theme,
+    // Kernel parameters to show, from /proc/cmdline
+    pub cmdline:         Cmdline,
+    // How much of the global IPv6 addresses to show
+    pub ipv6:            Ipv6,
+    // How much of the MAC address of each interface to show
+    pub mac:             Mac,
+    // Show the throughput of the active network interfaces
+    pub net_io:          bool,
+    // Show the fully qualified domain name instead of the hostname, if it resolves to one
+    pub fqdn:            bool,
+    // Show the public address, as seen by the HTTPS endpoint at `public_ip_url`
+    pub public_ip:       bool,
+    // Endpoint which replies with the address of whoever connects to it, in plain text
+    pub public_ip_url:   String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            random_distro:   false,
+            power:           false,
+            cpu_usage:       false,
+            cpu_cache:       false,
+            cpu_freq:        CpuFreq::Max,
+            disk_io:         false,
+            mounts:          false,
+            pressure:        false,
+            memory_modules:  false,
+            sample_interval: 200,
+            cache_as_used:   false,
+            sensors:         vec![],
+            usb:             false,
+            camera:          false,
+            updates:         false,
+            groups:          false,
+            toolchains:      false,
+            uptime_record:   true,
+            theme:           Theme::Auto,
+            cmdline:         Cmdline::Hidden,
+            ipv6:            Ipv6::Full,
+            mac:             Mac::Hidden,
+            net_io:          false,
+            fqdn:            false,
+            public_ip:       false,
+            public_ip_url:   "https://api.ipify.org".to_string(),
+        }
+    }
+}
+
+fn config_path() -> Option<PathBuf> {
+    Some(get_config_dir()?.join("pulga").join("pulga.toml"))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, got '{}'", value)),
+    }
+}
+
+fn parse_u64(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a positive number, got '{}'", value))
+}
+
+fn parse_cpu_freq(value: &str) -> Result<CpuFreq, String> {
+    match value {
+        "max" => Ok(CpuFreq::Max),
+        "current" => Ok(CpuFreq::Current),
+        "range" => Ok(CpuFreq::Range),
+        _ => Err(format!("expected max, current or range, got '{}'", value)),
+    }
+}
+
+fn parse_theme(value: &str) -> Result<Theme, String> {
+    match value {
+        "auto" => Ok(Theme::Auto),
+        "dark" => Ok(Theme::Dark),
+        "light" => Ok(Theme::Light),
+        _ => Err(format!("expected auto, dark or light, got '{}'", value)),
+    }
+}
+
+fn parse_cmdline(value: &str) -> Result<Cmdline, String> {
+    match value {
+        "false" => Ok(Cmdline::Hidden),
+        "true" => Ok(Cmdline::Interesting),
+        "full" => Ok(Cmdline::Full),
+        _ => Err(format!("expected true, false or full, got '{}'", value)),
+    }
+}
+
+fn parse_ipv6(value: &str) -> Result<Ipv6, String> {
+    match value {
+        "false" => Ok(Ipv6::Hidden),
+        "prefix" => Ok(Ipv6::Prefix),
+        "true" => Ok(Ipv6::Full),
+        _ => Err(format!("expected true, false or prefix, got '{}'", value)),
+    }
+}
+
+/// Parses either a TOML array of strings, e.g. `["a", "b"]`, or comma-separated values
+fn parse_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .unwrap_or(value);
+
+    value
+        .split(',')
+        .map(|item| item.trim().trim_matches('"').to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// Removes quotes and trailing comments from a raw value
+fn unquote(raw_value: &str) -> &str {
+    let raw_value = raw_value.trim();
+
+    match raw_value.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default(),
+        None => raw_value.split('#').next().unwrap_or_default().trim(),
+    }
+}
+
+fn parse_mac(value: &str) -> Result<Mac, String> {
+    match value {
+        "false" => Ok(Mac::Hidden),
+        "oui" => Ok(Mac::Oui),
+        "true" => Ok(Mac::Full),
+        _ => Err(format!("expected true, false or oui, got '{}'", value)),
+    }
+}
+
+fn parse_https_url(value: &str) -> Result<String, String> {
+    match value.strip_prefix("https://") {
+        Some(rest) if !rest.is_empty() => Ok(value.to_string()),
+        _ => Err(format!("expected an https:// URL, got '{}'", value)),
+    }
+}
+
+impl Config {
+    pub fn load() -> Config {
+        let mut config = Config::default();
+
+        if let Some(text) = config_path().and_then(|path| fs::read_to_string(path).ok()) {
+            config.apply_file(&text);
+        }
+
+        config.apply_args(env::args().skip(1));
+
+        config
+    }
+
+    fn apply_file(&mut self, text: &str) {
+        let lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in lines {
+            let result = match line.split_once('=') {
+                Some((key, value)) => self.set(key.trim(), unquote(value)),
+                None => Err("expected 'key = value'".to_string()),
+            };
+
+            if let Err(err) = result {
+                eprintln!("pulga: pulga.toml: '{}': {}", line, err);
+            }
+        }
+    }
+
+    fn apply_args(&mut self, args: impl Iterator<Item = String>) {
+        for arg in args {
+            let result = match arg.as_str() {
+                "-r" => self.set("random", "true"),
+                _ => match arg.strip_prefix("--") {
+                    Some(flag) => match flag.split_once('=') {
+                        Some((key, value)) => self.set(&key.replace('-', "_"), value),
+                        None => match flag.strip_prefix("no-") {
+                            Some(key) => self.set(&key.replace('-', "_"), "false"),
+                            None => self.set(&flag.replace('-', "_"), "true"),
+                        },
+                    },
+                    None => Err("unexpected argument".to_string()),
+                },
+            };
+
+            if let Err(err) = result {
+                eprintln!("pulga: '{}': {}", arg, err);
+            }
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "random" => self.random_distro = parse_bool(value)?,
+            "power" => self.power = parse_bool(value)?,
+            "cpu_usage" => self.cpu_usage = parse_bool(value)?,
+            "cpu_cache" => self.cpu_cache = parse_bool(value)?,
+            "cpu_freq" => self.cpu_freq = parse_cpu_freq(value)?,
+            "disk_io" => self.disk_io = parse_bool(value)?,
+            "mounts" => self.mounts = parse_bool(value)?,
+            "pressure" => self.pressure = parse_bool(value)?,
+            "memory_modules" => self.memory_modules = parse_bool(value)?,
+            "sample_interval" => self.sample_interval = parse_u64(value)?,
+            "cache_as_used" => self.cache_as_used = parse_bool(value)?,
+            "sensors" => self.sensors = parse_list(value),
+            "usb" => self.usb = parse_bool(value)?,
+            "camera" => self.camera = parse_bool(value)?,
+            "updates" => self.updates = parse_bool(value)?,
+            "groups" => self.groups = parse_bool(value)?,
+            "toolchains" => self.toolchains = parse_bool(value)?,
+            "uptime_record" => self.uptime_record = parse_bool(value)?,
+            "theme" => self.theme = parse_theme(value)?,
+            "cmdline" => self.cmdline = parse_cmdline(value)?,
+            "ipv6" => self.ipv6 = parse_ipv6(value)?,
+            "mac" => self.mac = parse_mac(value)?,
+            "net_io" => self.net_io = parse_bool(value)?,
+            "fqdn" => self.fqdn = parse_bool(value)?,
+            "public_ip" => self.public_ip = parse_bool(value)?,
+            "public_ip_url" => self.public_ip_url = parse_https_url(value)?,
+            _ => return Err("unknown option".to_string()),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use std::{fs, path::Path};
+
+/// Guesses the container manager from the control groups of PID 1
+fn container_from_cgroup() -> Option<&'static str> {
+    let cgroup = fs::read_to_string("/proc/1/cgroup").ok()?;
+
+    let name = if cgroup.contains("/kubepods") {
+        "kubernetes"
+    } else if cgroup.contains("/libpod-") {
+        "podman"
+    } else if cgroup.contains("/docker") {
+        "docker"
+    } else if cgroup.contains("/lxc") {
+        "lxc"
+    } else {
+        return None;
+    };
+
+    Some(name)
+}
+
+/// Name of the container manager the system runs under, e.g. "podman", if any
+pub fn get_container() -> Option<String> {
+    // systemd records the `container` variable it was started with, e.g. "systemd-nspawn"
+    if let Ok(container) = fs::read_to_string("/run/systemd/container") {
+        let container = container.trim();
+        if !container.is_empty() {
+            return Some(container.to_string());
+        }
+    }
+
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+
+    // The `container` variable of PID 1, though its environment is usually only readable by root
+    let environ = fs::read("/proc/1/environ").unwrap_or_default();
+    let container = environ
+        .split(|&byte| byte == 0)
+        .find_map(|variable| variable.strip_prefix(b"container="))
+        .map(|container| String::from_utf8_lossy(container).into_owned());
+
+    container.or_else(|| container_from_cgroup().map(ToString::to_string))
+}
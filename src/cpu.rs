@@ -0,0 +1,460 @@
+use crate::util::read_trimmed;
+
+use std::{
+    cmp::Ordering,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Time spent by all CPUs since boot, from the "cpu" line of /proc/stat, in clock ticks
+#[derive(Debug, Clone, Copy)]
+pub struct CpuTimes {
+    pub busy:  u64,
+    pub total: u64,
+}
+
+/// The line looks like "cpu  user nice system idle iowait irq softirq steal guest guest_nice".
+/// Guest time is already accounted for in user time, so it's left out.
+pub fn get_cpu_times() -> Option<CpuTimes> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let line = stat.lines().find(|line| line.starts_with("cpu "))?;
+
+    let times: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .take(8)
+        .map(|time| time.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let total: u64 = times.iter().sum();
+    // Both idle and iowait
+    let idle: u64 = times.iter().skip(3).take(2).sum();
+
+    Some(CpuTimes {
+        busy: total - idle,
+        total,
+    })
+}
+
+/// Share of time in which the CPUs were busy between two samples, in percent
+pub fn get_usage(before: CpuTimes, after: CpuTimes) -> Option<f64> {
+    let total = after.total.checked_sub(before.total)?;
+    let busy = after.busy.checked_sub(before.busy)?;
+
+    if total == 0 {
+        return None;
+    }
+
+    Some(busy as f64 * 100.0 / total as f64)
+}
+
+/// Lists /sys/devices/system/cpu/cpu{N} for every CPU
+fn get_cpu_dirs() -> Vec<PathBuf> {
+    let entries = match fs::read_dir("/sys/devices/system/cpu/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_name().to_str().is_some_and(|name| {
+                name.strip_prefix("cpu")
+                    .is_some_and(|id| !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit()))
+            })
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Counts the distinct (package, core) pairs of the online CPUs, from sysfs or else /proc/cpuinfo
+pub fn get_physical_cores() -> Option<usize> {
+    let mut cores: Vec<(String, String)> = vec![];
+
+    for cpu_dir in get_cpu_dirs() {
+        // Offline CPUs have no topology
+        let topology_dir = cpu_dir.join("topology");
+        let core = read_trimmed(&topology_dir.join("physical_package_id"))
+            .zip(read_trimmed(&topology_dir.join("core_id")));
+
+        if let Some(core) = core {
+            if !cores.contains(&core) {
+                cores.push(core);
+            }
+        }
+    }
+
+    if cores.is_empty() {
+        let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+        let value = |line: &str| line.split_once(':').map(|(_, value)| value.trim().to_string());
+
+        // Each processor has a "physical id" line followed by a "core id" one
+        let mut physical_id = None;
+        for line in cpuinfo.lines() {
+            if line.starts_with("physical id") {
+                physical_id = value(line);
+            } else if line.starts_with("core id") {
+                if let Some(core) = physical_id.clone().zip(value(line)) {
+                    if !cores.contains(&core) {
+                        cores.push(core);
+                    }
+                }
+            }
+        }
+    }
+
+    if cores.is_empty() {
+        None
+    } else {
+        Some(cores.len())
+    }
+}
+
+/// Total size of each data cache level, e.g. [(1, 512 KiB), (2, 8 MiB), (3, 32 MiB)], in bytes
+pub fn get_cache_sizes() -> Vec<(u8, u64)> {
+    // Caches shared by several CPUs show up in each of them, so they're told
+    // apart by their level and the CPUs sharing them
+    let mut caches: Vec<(u8, String, u64)> = vec![];
+
+    for cpu_dir in get_cpu_dirs() {
+        let entries = match fs::read_dir(cpu_dir.join("cache")) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let cache_dir = entry.path();
+            if read_trimmed(&cache_dir.join("type")).as_deref() == Some("Instruction") {
+                continue;
+            }
+
+            let level = read_trimmed(&cache_dir.join("level")).and_then(|level| level.parse().ok());
+            let shared_cpus = read_trimmed(&cache_dir.join("shared_cpu_list"));
+            // e.g. "2048K"
+            let size = read_trimmed(&cache_dir.join("size"))
+                .and_then(|size| size.strip_suffix('K')?.parse::<u64>().ok())
+                .map(|kibibytes| kibibytes * 1024);
+
+            if let (Some(level), Some(shared_cpus), Some(size)) = (level, shared_cpus, size) {
+                if !caches.iter().any(|(l, cpus, _)| *l == level && *cpus == shared_cpus) {
+                    caches.push((level, shared_cpus, size));
+                }
+            }
+        }
+    }
+
+    let mut sizes: Vec<(u8, u64)> = vec![];
+    for (level, _, size) in caches {
+        match sizes.iter_mut().find(|(l, _)| *l == level) {
+            Some((_, total)) => *total += size,
+            None => sizes.push((level, size)),
+        }
+    }
+
+    sizes.sort_unstable();
+    sizes
+}
+
+/// Reads the given cpufreq file (e.g. "scaling_cur_freq") of every CPU, in kHz
+fn read_cpufreq(file_name: &str) -> Vec<f64> {
+    get_cpu_dirs()
+        .iter()
+        .filter_map(|cpu_dir| read_trimmed(&cpu_dir.join("cpufreq").join(file_name)))
+        .filter_map(|freq| freq.parse().ok())
+        .collect()
+}
+
+/// Reads the "cpu MHz" lines of /proc/cpuinfo, which are there even when
+/// cpufreq isn't available (as in most VMs), in kHz
+fn read_cpuinfo_freqs() -> Vec<f64> {
+    let cpuinfo = match fs::read_to_string("/proc/cpuinfo") {
+        Ok(cpuinfo) => cpuinfo,
+        Err(_) => return vec![],
+    };
+
+    cpuinfo
+        .lines()
+        .filter(|line| line.starts_with("cpu MHz"))
+        .filter_map(|line| line.split_once(':')?.1.trim().parse::<f64>().ok())
+        .map(|mhz| mhz * 1000.0)
+        .collect()
+}
+
+/// Average of the current frequency of the CPUs, in kHz
+pub fn get_current_freq() -> Option<f64> {
+    let mut freqs = read_cpufreq("scaling_cur_freq");
+    if freqs.is_empty() {
+        freqs = read_cpuinfo_freqs();
+    }
+
+    if freqs.is_empty() {
+        None
+    } else {
+        Some(freqs.iter().sum::<f64>() / freqs.len() as f64)
+    }
+}
+
+/// Lowest and highest frequencies supported by any of the CPUs, in kHz
+pub fn get_freq_range() -> Option<(f64, f64)> {
+    let min_freqs = read_cpufreq("cpuinfo_min_freq");
+    let max_freqs = read_cpufreq("cpuinfo_max_freq");
+
+    let (min_freqs, max_freqs) = if min_freqs.is_empty() || max_freqs.is_empty() {
+        let freqs = read_cpuinfo_freqs();
+        (freqs.clone(), freqs)
+    } else {
+        (min_freqs, max_freqs)
+    };
+
+    let min = min_freqs.iter().copied().reduce(f64::min)?;
+    let max = max_freqs.iter().copied().reduce(f64::max)?;
+
+    Some((min, max))
+}
+
+/// Distinct cpufreq governors of the CPUs, e.g. ["schedutil"]
+pub fn get_governors() -> Vec<String> {
+    let mut governors: Vec<String> = vec![];
+
+    for cpu_dir in get_cpu_dirs() {
+        if let Some(governor) = read_trimmed(&cpu_dir.join("cpufreq").join("scaling_governor")) {
+            if !governors.contains(&governor) {
+                governors.push(governor);
+            }
+        }
+    }
+
+    governors
+}
+
+/// Power profile of the platform (e.g. "performance", "balanced" or "low-power"),
+/// which laptop firmwares expose through ACPI
+pub fn get_platform_profile() -> Option<String> {
+    read_trimmed(Path::new("/sys/firmware/acpi/platform_profile"))
+}
+
+/// Finds the value of the first line of /proc/cpuinfo with the given key, e.g. "Hardware"
+pub fn cpuinfo_field<'a>(cpuinfo: &'a str, key: &str) -> Option<&'a str> {
+    cpuinfo.lines().find_map(|line| {
+        let (line_key, value) = line.split_once(':')?;
+        if line_key.trim() == key {
+            Some(value.trim())
+        } else {
+            None
+        }
+    })
+}
+
+/// Device tree properties are NUL-terminated, and lists are NUL-separated
+pub fn read_device_tree_strings(property: &str) -> Vec<String> {
+    match fs::read(Path::new("/proc/device-tree/").join(property)) {
+        Ok(bytes) => bytes
+            .split(|&byte| byte == 0)
+            .filter(|string| !string.is_empty())
+            .map(|string| String::from_utf8_lossy(string).into_owned())
+            .collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Identifies boards whose CPU has no meaningful model name, such as Raspberry Pis,
+/// e.g. "Raspberry Pi 4 Model B (BCM2711)"
+pub fn get_board_and_soc(cpuinfo: &str) -> Option<String> {
+    // e.g. "Raspberry Pi 4 Model B Rev 1.4"
+    let board = read_device_tree_strings("model")
+        .into_iter()
+        .next()
+        .or_else(|| cpuinfo_field(cpuinfo, "Model").map(ToString::to_string))
+        .map(|board| match board.find(" Rev ") {
+            Some(index) => board[..index].to_string(),
+            None => board,
+        });
+
+    // The most generic entry of `compatible` names the SoC, e.g. "brcm,bcm2711".
+    // The kernel claims that every Raspberry Pi has a BCM2835 in `Hardware`.
+    let soc = read_device_tree_strings("compatible")
+        .last()
+        .and_then(|compatible| compatible.split_once(','))
+        .map(|(_, soc)| soc.to_uppercase())
+        .or_else(|| cpuinfo_field(cpuinfo, "Hardware").map(ToString::to_string));
+
+    match (board, soc) {
+        (Some(board), Some(soc)) => Some(format!("{} ({})", board, soc)),
+        (board, soc) => board.or(soc),
+    }
+}
+
+/// Formats a RISC-V microarchitecture, e.g. "sifive,u74-mc" as "SiFive U74-MC"
+fn riscv_uarch_name(uarch: &str) -> String {
+    let (vendor, core) = match uarch.split_once(',') {
+        Some(pair) => pair,
+        None => return uarch.to_string(),
+    };
+
+    let vendor = match vendor {
+        "sifive" => "SiFive",
+        "thead" => "T-Head",
+        "starfive" => "StarFive",
+        "spacemit" => "SpacemiT",
+        vendor => vendor,
+    };
+
+    format!("{} {}", vendor, core.to_uppercase())
+}
+
+/// RISC-V has no `model name` in /proc/cpuinfo, but `isa` and (on newer kernels)
+/// `uarch` lines instead, e.g. "SiFive U74-MC (rv64imafdc)"
+pub fn get_riscv_model(cpuinfo: &str) -> Option<String> {
+    // e.g. "rv64imafdc_zicntr_zicsr_zifencei_zihpm_zba_zbb"
+    let isa = cpuinfo_field(cpuinfo, "isa")?;
+    let base_isa = isa.split('_').next().unwrap_or(isa);
+
+    let core = cpuinfo_field(cpuinfo, "uarch")
+        .map(riscv_uarch_name)
+        .or_else(|| get_board_and_soc(cpuinfo));
+
+    match core {
+        Some(core) => Some(format!("{} ({})", core, base_isa)),
+        None => Some(base_isa.to_string()),
+    }
+}
+
+/// A group of cores of the same kind, in CPUs with heterogeneous cores such
+/// as ARM big.LITTLE designs or Intel hybrid ones
+#[derive(Debug)]
+pub struct CoreCluster {
+    // e.g. "Cortex-A76" or "P-core"
+    pub name:     Option<String>,
+    // Number of physical cores
+    pub cores:    usize,
+    // In kHz
+    pub max_freq: Option<f64>,
+}
+
+/// Parses a list of CPUs, e.g. "0-3,8-11"
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((first, last)) => Some((first.parse().ok()?..=last.parse().ok()?).collect()),
+            None => Some(vec![range.parse().ok()?]),
+        })
+        .flatten()
+        .collect()
+}
+
+/// Names of the cores designed by ARM, from the "CPU part" of /proc/cpuinfo
+fn arm_core_name(part: u32) -> Option<&'static str> {
+    let name = match part {
+        0xd03 => "Cortex-A53",
+        0xd04 => "Cortex-A35",
+        0xd05 => "Cortex-A55",
+        0xd07 => "Cortex-A57",
+        0xd08 => "Cortex-A72",
+        0xd09 => "Cortex-A73",
+        0xd0a => "Cortex-A75",
+        0xd0b => "Cortex-A76",
+        0xd0d => "Cortex-A77",
+        0xd41 => "Cortex-A78",
+        0xd44 => "Cortex-X1",
+        0xd46 => "Cortex-A510",
+        0xd47 => "Cortex-A710",
+        0xd48 => "Cortex-X2",
+        0xd4d => "Cortex-A715",
+        0xd4e => "Cortex-X3",
+        0xd80 => "Cortex-A520",
+        0xd81 => "Cortex-A720",
+        0xd82 => "Cortex-X4",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Finds out the kind of each CPU, indexed by their number
+fn get_core_names(cpuinfo: &str) -> Vec<(usize, String)> {
+    // Intel hybrid CPUs have a PMU for each kind of core
+    let hybrid: Vec<(usize, String)> = [("cpu_core", "P-core"), ("cpu_atom", "E-core")]
+        .iter()
+        .filter_map(|(pmu, name)| {
+            let cpus = read_trimmed(&Path::new("/sys/devices/").join(pmu).join("cpus"))?;
+            Some(parse_cpu_list(&cpus).into_iter().map(move |cpu| (cpu, name.to_string())))
+        })
+        .flatten()
+        .collect();
+
+    if !hybrid.is_empty() {
+        return hybrid;
+    }
+
+    // On ARM, each processor block has its own "CPU implementer" and "CPU part"
+    cpuinfo
+        .split("\n\n")
+        .filter_map(|block| {
+            let cpu = cpuinfo_field(block, "processor")?.parse().ok()?;
+            if cpuinfo_field(block, "CPU implementer")? != "0x41" {
+                return None;
+            }
+            let part = cpuinfo_field(block, "CPU part")?.strip_prefix("0x")?;
+            let name = arm_core_name(u32::from_str_radix(part, 16).ok()?)?;
+
+            Some((cpu, name.to_string()))
+        })
+        .collect()
+}
+
+/// Groups the cores by kind and maximum frequency. Returns a single cluster
+/// (or none) for CPUs whose cores are all alike.
+pub fn get_core_clusters() -> Vec<CoreCluster> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let core_names = get_core_names(&cpuinfo);
+
+    // Other than hybrid ones, x86 CPUs may still have cores with slightly
+    // different maximum frequencies (e.g. Intel's Turbo Boost Max favored cores)
+    if core_names.is_empty() && cpuinfo_field(&cpuinfo, "CPU implementer").is_none() {
+        return vec![];
+    }
+
+    // Cores are told apart by their kind and maximum frequency, and their
+    // threads by their (package, core) pair
+    let mut clusters: Vec<(CoreCluster, Vec<(String, String)>)> = vec![];
+
+    for cpu_dir in get_cpu_dirs() {
+        let index: Option<usize> = cpu_dir
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("cpu")?.parse().ok());
+
+        let name = core_names
+            .iter()
+            .find(|(cpu, _)| Some(*cpu) == index)
+            .map(|(_, name)| name.clone());
+        let max_freq = read_trimmed(&cpu_dir.join("cpufreq").join("cpuinfo_max_freq"))
+            .and_then(|freq| freq.parse::<f64>().ok());
+
+        let topology_dir = cpu_dir.join("topology");
+        let core = match read_trimmed(&topology_dir.join("physical_package_id"))
+            .zip(read_trimmed(&topology_dir.join("core_id")))
+        {
+            Some(core) => core,
+            // Offline
+            None => continue,
+        };
+
+        let cluster = clusters
+            .iter_mut()
+            .find(|(cluster, _)| cluster.name == name && cluster.max_freq == max_freq);
+
+        match cluster {
+            Some((cluster, cores)) => {
+                if !cores.contains(&core) {
+                    cores.push(core);
+                    cluster.cores += 1;
+                }
+            },
+            None => clusters.push((CoreCluster { name, cores: 1, max_freq }, vec![core])),
+        }
+    }
+
+    // The fastest cores come first
+    clusters.sort_by(|(a, _), (b, _)| b.max_freq.partial_cmp(&a.max_freq).unwrap_or(Ordering::Equal));
+    clusters.into_iter().map(|(cluster, _)| cluster).collect()
+}
@@ -0,0 +1,64 @@
+use crate::{
+    dconf, gtk,
+    util::{get_config_dir, nonempty_var, read_ini},
+};
+
+use std::{env, path::PathBuf};
+
+/// The size GTK uses, either from gsettings or from settings.ini
+fn gtk_cursor_size() -> Option<i32> {
+    if gtk::uses_gsettings() {
+        if let Some(size) = dconf::read_i32("/org/gnome/desktop/interface/cursor-size") {
+            return Some(size);
+        }
+    }
+
+    let (gtk3, gtk4) = gtk::get_setting("gtk-cursor-theme-size", "cursor-size");
+    gtk3.or(gtk4)?.parse().ok()
+}
+
+/// KDE keeps the cursor in kcminputrc, e.g. `[Mouse]` then `cursorTheme=breeze_cursors`
+fn kde_cursor() -> Option<(String, Option<i32>)> {
+    let kcminputrc = get_config_dir()?.join("kcminputrc");
+    let theme = read_ini(&kcminputrc, "Mouse", "cursorTheme")?;
+    let size = read_ini(&kcminputrc, "Mouse", "cursorSize").and_then(|size| size.parse().ok());
+
+    Some((theme, size))
+}
+
+/// The cursor theme inherited by the "default" one, which libXcursor falls back to
+fn default_cursor_theme() -> Option<String> {
+    let home = PathBuf::from(env::var_os("HOME")?);
+    let data_dir = nonempty_var("XDG_DATA_HOME")
+        .map_or_else(|| home.join(".local").join("share"), PathBuf::from);
+
+    [home.join(".icons"), data_dir.join("icons")].iter().find_map(|icons| {
+        let index = icons.join("default").join("index.theme");
+        read_ini(&index, "Icon Theme", "Inherits")
+    })
+}
+
+/// Name and size of the cursor theme, e.g. "Adwaita (24px)"
+pub fn get_cursor_theme() -> Option<String> {
+    let desktop = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+
+    // Compositors such as Sway and Hyprland export the cursor they use to their clients
+    let (theme, size) = match nonempty_var("XCURSOR_THEME") {
+        Some(theme) => (theme, nonempty_var("XCURSOR_SIZE").and_then(|size| size.parse().ok())),
+        None => {
+            let kde = if desktop.contains("kde") { kde_cursor() } else { None };
+            let gtk = || {
+                let (gtk3, gtk4) = gtk::get_setting("gtk-cursor-theme-name", "cursor-theme");
+                Some((gtk3.or(gtk4)?, gtk_cursor_size()))
+            };
+
+            kde.or_else(gtk)
+                .or_else(|| Some((default_cursor_theme()?, None)))?
+        },
+    };
+
+    match size.or_else(|| nonempty_var("XCURSOR_SIZE")?.parse().ok()) {
+        Some(size) if size > 0 => Some(format!("{} ({}px)", theme, size)),
+        _ => Some(theme),
+    }
+}
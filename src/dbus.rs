@@ -0,0 +1,266 @@
+use std::{
+    convert::TryInto,
+    env,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    time::Duration,
+};
+
+/// Where the system bus listens, unless $DBUS_SYSTEM_BUS_ADDRESS says otherwise
+const SYSTEM_BUS: &str = "/run/dbus/system_bus_socket";
+/// How long to wait for the bus, which may be stuck, e.g. while the system shuts down
+const TIMEOUT: Duration = Duration::from_millis(500);
+
+// Types of messages
+const METHOD_CALL: u8 = 1;
+const METHOD_RETURN: u8 = 2;
+const ERROR: u8 = 3;
+
+// Codes of the header fields
+const PATH: u8 = 1;
+const INTERFACE: u8 = 2;
+const MEMBER: u8 = 3;
+const REPLY_SERIAL: u8 = 5;
+const DESTINATION: u8 = 6;
+const SIGNATURE: u8 = 8;
+
+/// Rounds an offset up to a multiple of `alignment`, as every value is aligned to its size
+fn align(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Marshals a message, whose values are aligned relative to its start
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn pad(&mut self, alignment: usize) {
+        self.bytes.resize(align(self.bytes.len(), alignment), 0);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.pad(4);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Strings and object paths, which start with their length and end with a NUL
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+    }
+
+    /// Signatures, whose length fits in a byte
+    fn signature(&mut self, value: &str) {
+        self.bytes.push(value.len() as u8);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+    }
+
+    /// A header field, which is a struct of its code and of a variant holding its value
+    fn field(&mut self, code: u8, kind: &str, value: &str) {
+        self.pad(8);
+        self.bytes.push(code);
+        self.signature(kind);
+        match kind {
+            "g" => self.signature(value),
+            _ => self.string(value),
+        }
+    }
+}
+
+/// The body of a reply, which is in the byte order of whoever sent it
+struct Reply {
+    big_endian: bool,
+    body:       Vec<u8>,
+}
+
+impl Reply {
+    fn read_u32(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.body.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    }
+
+    fn read_u64(&self, offset: usize) -> Option<u64> {
+        let bytes: [u8; 8] = self.body.get(offset..offset + 8)?.try_into().ok()?;
+        Some(if self.big_endian { u64::from_be_bytes(bytes) } else { u64::from_le_bytes(bytes) })
+    }
+}
+
+/// A connection to the system bus, just enough to read the properties of services
+pub struct Connection {
+    stream: UnixStream,
+    serial: u32,
+}
+
+/// Reads $DBUS_SYSTEM_BUS_ADDRESS, e.g. "unix:path=/run/dbus/system_bus_socket"
+fn bus_path() -> String {
+    let address = env::var("DBUS_SYSTEM_BUS_ADDRESS").unwrap_or_default();
+    address
+        .split(';')
+        .find_map(|address| {
+            let params = address.strip_prefix("unix:")?;
+            let path = params.split(',').find_map(|param| param.strip_prefix("path="))?;
+            Some(path.to_string())
+        })
+        .unwrap_or_else(|| SYSTEM_BUS.to_string())
+}
+
+impl Connection {
+    pub fn system() -> Option<Connection> {
+        let mut stream = UnixStream::connect(bus_path()).ok()?;
+        stream.set_read_timeout(Some(TIMEOUT)).ok()?;
+        stream.set_write_timeout(Some(TIMEOUT)).ok()?;
+
+        // The bus checks the user ID through the socket, given in hex digits of its decimal
+        let uid = unsafe { libc::getuid() }.to_string();
+        let uid: String = uid.bytes().map(|byte| format!("{:02x}", byte)).collect();
+        stream.write_all(format!("\0AUTH EXTERNAL {}\r\n", uid).as_bytes()).ok()?;
+
+        let mut line = vec![];
+        while !line.ends_with(b"\r\n") {
+            let mut byte = [0_u8];
+            stream.read_exact(&mut byte).ok()?;
+            line.push(byte[0]);
+        }
+        if !line.starts_with(b"OK ") {
+            return None;
+        }
+        stream.write_all(b"BEGIN\r\n").ok()?;
+
+        // Every connection must say hello before calling anything else
+        let mut connection = Connection { stream, serial: 0 };
+        connection.call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "Hello",
+            &[],
+        )?;
+
+        Some(connection)
+    }
+
+    /// Reads a message, returning its type, the serial of the call it replies to and its body
+    fn read_message(&mut self) -> Option<(u8, Option<u32>, Reply)> {
+        let mut header = [0_u8; 16];
+        self.stream.read_exact(&mut header).ok()?;
+
+        let big_endian = header[0] == b'B';
+        let read_u32 = |bytes: &[u8]| {
+            let bytes: [u8; 4] = bytes.try_into().ok()?;
+            Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+        };
+        let body_len = read_u32(&header[4..8])? as usize;
+        let fields_len = read_u32(&header[12..16])? as usize;
+
+        // The header fields are followed by padding up to the body
+        let mut fields = vec![0_u8; align(16 + fields_len, 8) - 16];
+        self.stream.read_exact(&mut fields).ok()?;
+        let mut body = vec![0_u8; body_len];
+        self.stream.read_exact(&mut body).ok()?;
+
+        // The fields start 16 bytes into the message, so they can be aligned on their own
+        let mut reply_serial = None;
+        let mut offset = 0;
+        while offset < fields_len {
+            offset = align(offset, 8);
+            let code = *fields.get(offset)?;
+            let signature_len = *fields.get(offset + 1)? as usize;
+            let kind = *fields.get(offset + 2)?;
+            offset += 3 + signature_len;
+
+            match kind {
+                b'u' => {
+                    offset = align(offset, 4);
+                    if code == REPLY_SERIAL {
+                        reply_serial = read_u32(fields.get(offset..offset + 4)?);
+                    }
+                    offset += 4;
+                },
+                b's' | b'o' => {
+                    offset = align(offset, 4);
+                    offset += 4 + read_u32(fields.get(offset..offset + 4)?)? as usize + 1;
+                },
+                b'g' => offset += 1 + *fields.get(offset)? as usize + 1,
+                _ => break,
+            }
+        }
+
+        Some((header[1], reply_serial, Reply { big_endian, body }))
+    }
+
+    /// Calls a method whose arguments are strings, and waits for its reply,
+    /// skipping the signals which may come in first
+    fn call(
+        &mut self,
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        args: &[&str],
+    ) -> Option<Reply> {
+        self.serial += 1;
+
+        let mut body = Writer::default();
+        for arg in args {
+            body.string(arg);
+        }
+
+        let mut message = Writer::default();
+        message.bytes.extend_from_slice(&[b'l', METHOD_CALL, 0, 1]);
+        message.u32(body.bytes.len() as u32);
+        message.u32(self.serial);
+        // The length of the array of fields, filled in once they're written
+        message.u32(0);
+        message.field(PATH, "o", path);
+        message.field(INTERFACE, "s", interface);
+        message.field(MEMBER, "s", member);
+        message.field(DESTINATION, "s", destination);
+        if !args.is_empty() {
+            message.field(SIGNATURE, "g", &"s".repeat(args.len()));
+        }
+        let fields_len = message.bytes.len() as u32 - 16;
+        message.bytes[12..16].copy_from_slice(&fields_len.to_le_bytes());
+        message.pad(8);
+        message.bytes.extend_from_slice(&body.bytes);
+
+        self.stream.write_all(&message.bytes).ok()?;
+
+        loop {
+            match self.read_message()? {
+                (METHOD_RETURN, Some(serial), reply) if serial == self.serial => return Some(reply),
+                (ERROR, Some(serial), _) if serial == self.serial => return None,
+                _ => continue,
+            }
+        }
+    }
+
+    /// Reads a property holding an unsigned integer, such as a timestamp ('t') or a count ('u')
+    pub fn get_unsigned(
+        &mut self,
+        destination: &str,
+        path: &str,
+        interface: &str,
+        property: &str,
+    ) -> Option<u64> {
+        let reply = self.call(
+            destination,
+            path,
+            "org.freedesktop.DBus.Properties",
+            "Get",
+            &[interface, property],
+        )?;
+
+        // The property comes in a variant, made of its signature and then its value
+        let signature_len = *reply.body.first()? as usize;
+        let offset = 1 + signature_len + 1;
+        match reply.body.get(1..1 + signature_len)? {
+            b"t" => reply.read_u64(align(offset, 8)),
+            b"u" => reply.read_u32(align(offset, 4)).map(u64::from),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::util::{get_config_dir, read_le_u32};
+
+use std::fs;
+
+/// Start of every GVDB file, "GVariant" in little-endian words
+const SIGNATURE: &[u8; 8] = b"GVariant";
+/// Size of a hash item: hash, parent, key start, key size, type, padding and value pointer
+const ITEM_SIZE: usize = 24;
+/// Items without a parent, such as the root "/"
+const NO_PARENT: u32 = u32::MAX;
+
+struct Item {
+    parent: u32,
+    key:    String,
+    // 'v' for values, 'L' for the lists of children of directories
+    kind:   u8,
+    // Offsets of the serialized GVariant, for items holding a value
+    value:  (usize, usize),
+}
+
+/// Reads the hash items of the root table of a GVDB file, in which dconf stores its keys
+fn read_items(data: &[u8]) -> Option<Vec<Item>> {
+    if data.get(..8)? != SIGNATURE {
+        return None;
+    }
+
+    let (start, end) = (read_le_u32(data, 16)? as usize, read_le_u32(data, 20)? as usize);
+    // The table starts with the sizes of its bloom filter and of its buckets
+    let bloom_words = (read_le_u32(data, start)? & ((1 << 27) - 1)) as usize;
+    let buckets = read_le_u32(data, start + 4)? as usize;
+    let items_start = start + 8 + 4 * (bloom_words + buckets);
+
+    let mut items = vec![];
+    for item in data.get(items_start..end)?.chunks_exact(ITEM_SIZE) {
+        let parent = read_le_u32(item, 4)?;
+        let key_start = read_le_u32(item, 8)? as usize;
+        let key_size = u16::from_le_bytes([item[12], item[13]]) as usize;
+        let key = data.get(key_start..key_start + key_size)?;
+        let value = (read_le_u32(item, 16)? as usize, read_le_u32(item, 20)? as usize);
+
+        let key = String::from_utf8_lossy(key).into_owned();
+        items.push(Item { parent, key, kind: item[14], value });
+    }
+
+    Some(items)
+}
+
+/// Keys only hold their last component, e.g. "gtk-theme", so the full path is
+/// built by following their parents up to "/"
+fn full_path(items: &[Item], item: &Item) -> String {
+    let mut path = item.key.clone();
+    let mut parent = item.parent;
+
+    // Bounded, in case of a corrupt file with a loop
+    for _ in 0..items.len() {
+        match items.get(parent as usize) {
+            Some(item) if parent != NO_PARENT => {
+                path.insert_str(0, &item.key);
+                parent = item.parent;
+            },
+            _ => break,
+        }
+    }
+
+    path
+}
+
+/// Reads a value from the user's dconf database, as a serialized GVariant of type "v"
+fn read_variant(path: &str) -> Option<(Vec<u8>, String)> {
+    let data = fs::read(get_config_dir()?.join("dconf").join("user")).ok()?;
+    let items = read_items(&data)?;
+
+    let item = items
+        .iter()
+        .find(|item| item.kind == b'v' && full_path(&items, item) == path)?;
+    let variant = data.get(item.value.0..item.value.1)?;
+
+    // Variants hold their value, a NUL and then the type of the value
+    let separator = variant.iter().rposition(|&byte| byte == 0)?;
+    let kind = String::from_utf8_lossy(&variant[separator + 1..]).into_owned();
+
+    Some((variant[..separator].to_vec(), kind))
+}
+
+/// Reads a string set by the user through gsettings, e.g.
+/// "/org/gnome/desktop/interface/gtk-theme"
+pub fn read_string(path: &str) -> Option<String> {
+    match read_variant(path)? {
+        (value, kind) if kind == "s" => {
+            let text = String::from_utf8_lossy(&value);
+            Some(text.trim_end_matches('\0').to_string())
+        },
+        _ => None,
+    }
+}
+
+/// Reads a 32-bit integer set by the user through gsettings, e.g.
+/// "/org/gnome/desktop/interface/cursor-size"
+pub fn read_i32(path: &str) -> Option<i32> {
+    match read_variant(path)? {
+        (value, kind) if kind == "i" && value.len() == 4 => {
+            Some(i32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,125 @@
+use crate::util::get_base;
+
+use std::{env, fs};
+
+/// Desktops and compositors as named in XDG_CURRENT_DESKTOP or DESKTOP_SESSION
+const DESKTOPS: &[(&str, &str)] = &[
+    ("gnome", "GNOME"),
+    ("kde", "KDE Plasma"),
+    ("plasma", "KDE Plasma"),
+    ("x-cinnamon", "Cinnamon"),
+    ("cinnamon", "Cinnamon"),
+    ("xfce", "Xfce"),
+    ("mate", "MATE"),
+    ("lxqt", "LXQt"),
+    ("lxde", "LXDE"),
+    ("budgie", "Budgie"),
+    ("budgie-desktop", "Budgie"),
+    ("pantheon", "Pantheon"),
+    ("unity", "Unity"),
+    ("deepin", "Deepin"),
+    ("dde", "Deepin"),
+    ("cosmic", "COSMIC"),
+    ("enlightenment", "Enlightenment"),
+    ("trinity", "Trinity"),
+    ("hyprland", "Hyprland"),
+    ("sway", "Sway"),
+    ("river", "river"),
+    ("wayfire", "Wayfire"),
+    ("niri", "niri"),
+    ("labwc", "labwc"),
+    ("i3", "i3"),
+    ("openbox", "Openbox"),
+];
+
+/// Finds the value of a tag in the version files of GNOME and MATE, e.g.
+/// `<platform>46</platform>`
+fn find_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find("</")?;
+    Some(xml[start..end].trim())
+}
+
+/// Reads e.g. /usr/share/gnome/gnome-version.xml, which holds the platform version and its
+/// minor release. Since GNOME 40, the platform version is the major one, e.g. "46.2".
+fn read_version_xml(path: &str) -> Option<String> {
+    let xml = fs::read_to_string(path).ok()?;
+    let platform = find_tag(&xml, "platform")?;
+    let minor = find_tag(&xml, "minor")?;
+
+    let is_major = platform.parse::<u32>().is_ok_and(|platform| platform >= 40);
+    if is_major && minor == "0" {
+        Some(platform.to_string())
+    } else {
+        Some(format!("{}.{}", platform, minor))
+    }
+}
+
+/// The session files of Plasma carry its version, e.g. "X-KDE-PluginInfo-Version=5.27.10",
+/// and Plasma sessions export their major version in KDE_SESSION_VERSION otherwise
+fn plasma_version() -> Option<String> {
+    let from_session = [
+        "/usr/share/wayland-sessions/plasma.desktop",
+        "/usr/share/xsessions/plasma.desktop",
+        "/usr/share/xsessions/plasmax11.desktop",
+    ]
+    .iter()
+    .find_map(|path| {
+        let session = fs::read_to_string(path).ok()?;
+        let version = session
+            .lines()
+            .find_map(|line| line.strip_prefix("X-KDE-PluginInfo-Version="))?;
+        Some(version.trim().to_string())
+    });
+
+    from_session.or_else(|| {
+        env::var("KDE_SESSION_VERSION")
+            .ok()
+            .filter(|version| !version.is_empty())
+    })
+}
+
+fn desktop_version(desktop: &str) -> Option<String> {
+    match desktop {
+        "GNOME" => read_version_xml("/usr/share/gnome/gnome-version.xml"),
+        "MATE" => read_version_xml("/usr/share/mate-about/mate-version.xml"),
+        "KDE Plasma" => plasma_version(),
+        _ => None,
+    }
+}
+
+/// Names the desktop from its entries in XDG_CURRENT_DESKTOP, such as "ubuntu:GNOME",
+/// preferring the ones known to Pulga
+fn name_desktop(entries: &str) -> Option<String> {
+    let entries: Vec<String> = entries
+        .split(':')
+        .map(|entry| get_base(entry.trim()).to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .collect();
+
+    let known = entries.iter().find_map(|entry| {
+        DESKTOPS
+            .iter()
+            .find(|(name, _)| entry == name)
+            // Session names carry suffixes, e.g. "plasmawayland" or "gnome-xorg"
+            .or_else(|| DESKTOPS.iter().find(|(name, _)| entry.starts_with(name)))
+            .map(|(_, pretty_name)| pretty_name.to_string())
+    });
+
+    known.or_else(|| entries.into_iter().next())
+}
+
+/// Name and version of the desktop environment, e.g. "KDE Plasma 6.0.3" or "GNOME 46"
+pub fn get_desktop_environment() -> String {
+    let desktop = ["XDG_CURRENT_DESKTOP", "XDG_SESSION_DESKTOP", "DESKTOP_SESSION"]
+        .iter()
+        .find_map(|var| name_desktop(&env::var(var).ok()?));
+
+    match desktop {
+        Some(desktop) => match desktop_version(&desktop) {
+            Some(version) => format!("{} {}", desktop, version),
+            None => desktop,
+        },
+        None => "Unknown".to_string(),
+    }
+}
@@ -0,0 +1,370 @@
+use libc::{c_ulong, statvfs};
+
+use std::{
+    ffi::CString,
+    fs,
+    fs::File,
+    mem,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+/// struct nvme_passthru_cmd, from linux/nvme_ioctl.h
+#[repr(C)]
+#[derive(Default)]
+struct NvmePassthruCmd {
+    opcode:       u8,
+    flags:        u8,
+    rsvd1:        u16,
+    nsid:         u32,
+    cdw2:         u32,
+    cdw3:         u32,
+    metadata:     u64,
+    addr:         u64,
+    metadata_len: u32,
+    data_len:     u32,
+    cdw10:        u32,
+    cdw11:        u32,
+    cdw12:        u32,
+    cdw13:        u32,
+    cdw14:        u32,
+    cdw15:        u32,
+    timeout_ms:   u32,
+    result:       u32,
+}
+
+/// _IOWR('N', 0x41, struct nvme_admin_cmd)
+const NVME_IOCTL_ADMIN_CMD: c_ulong = 0xC048_4E41;
+/// Admin command opcode of "Get Log Page"
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+/// Log page identifier of "SMART / Health Information"
+const NVME_LOG_SMART: u32 = 0x02;
+
+/// Filesystems which don't hold user data
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts",
+    "devtmpfs", "efivarfs", "fusectl", "hugetlbfs", "mqueue", "nsfs", "overlay", "proc", "pstore",
+    "ramfs", "rpc_pipefs", "securityfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+];
+
+/// An entry of /proc/self/mountinfo
+#[derive(Debug)]
+pub struct Mount {
+    // Device number of the filesystem, e.g. "259:2"
+    pub device:      String,
+    // Directory of the filesystem which is mounted, e.g. "/@home" for a btrfs subvolume
+    pub root:        String,
+    // Where the filesystem is mounted, e.g. "/home"
+    pub mount_point: String,
+    // Filesystem type, e.g. "ext4"
+    pub fs_type:     String,
+    // Mount source, usually a device such as "/dev/nvme0n1p2"
+    pub source:      String,
+}
+
+/// Mount points escape whitespace and backslashes in octal, e.g. "\040" for ' '
+fn unescape_octal(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('\\') {
+        unescaped.push_str(&rest[..start]);
+        let escaped = rest.get(start + 1..start + 4);
+        match escaped.and_then(|digits| u8::from_str_radix(digits, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[start + 4..];
+            },
+            None => {
+                unescaped.push('\\');
+                rest = &rest[start + 1..];
+            },
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Parses a line such as
+/// "36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue"
+fn parse_mountinfo_line(line: &str) -> Option<Mount> {
+    // The optional fields end with a single hyphen
+    let (mount_fields, fs_fields) = line.split_once(" - ")?;
+
+    let mut mount_fields = mount_fields.split(' ');
+    let device = mount_fields.nth(2)?;
+    let root = mount_fields.next()?;
+    let mount_point = mount_fields.next()?;
+
+    let mut fs_fields = fs_fields.split(' ');
+
+    Some(Mount {
+        device:      device.to_string(),
+        root:        unescape_octal(root),
+        mount_point: unescape_octal(mount_point),
+        fs_type:     fs_fields.next()?.to_string(),
+        source:      unescape_octal(fs_fields.next()?),
+    })
+}
+
+pub fn get_mounts() -> Vec<Mount> {
+    fs::read_to_string("/proc/self/mountinfo")
+        .map(|mountinfo| mountinfo.lines().filter_map(parse_mountinfo_line).collect())
+        .unwrap_or_default()
+}
+
+impl Mount {
+    /// Checks if the mount holds actual data, as opposed to e.g. /proc or /sys
+    pub fn is_real(&self) -> bool {
+        // FUSE filesystems without a backing device, e.g. "fuse.gvfsd-fuse" and "fuse.portal"
+        let virtual_fuse = self.fs_type.starts_with("fuse.") && !self.source.starts_with('/');
+
+        !PSEUDO_FILESYSTEMS.contains(&self.fs_type.as_str()) && !virtual_fuse
+    }
+
+    /// Name of the device the mount comes from, as sysfs knows it, e.g. "dm-0" for
+    /// "/dev/mapper/luks-<uuid>", which is a link to it
+    pub fn device_name(&self) -> Option<String> {
+        let source = fs::canonicalize(&self.source).unwrap_or_else(|_| PathBuf::from(&self.source));
+        Some(source.file_name()?.to_str()?.to_string())
+    }
+}
+
+/// Lists the mounts holding actual data, each filesystem only once
+/// (i.e. excluding bind mounts and extra btrfs subvolumes).
+pub fn get_real_mounts() -> Vec<Mount> {
+    let mut mounts: Vec<Mount> = vec![];
+
+    for mount in get_mounts().into_iter().filter(Mount::is_real) {
+        match mounts.iter_mut().find(|other| other.device == mount.device) {
+            // Keep the mount closest to `/`
+            Some(other) if mount.mount_point.len() < other.mount_point.len() => *other = mount,
+            Some(_) => {},
+            None => mounts.push(mount),
+        }
+    }
+
+    mounts.sort_unstable_by(|a, b| a.mount_point.cmp(&b.mount_point));
+    mounts
+}
+
+/// Finds the filesystem mounted at `/`. If something has been mounted over
+/// it (e.g. in some live systems), the last entry is the one that's visible.
+pub fn get_root_mount() -> Option<Mount> {
+    get_mounts()
+        .into_iter()
+        .rfind(|mount| mount.mount_point == "/")
+}
+
+/// Returns the used and total space of the filesystem containing `path`, in bytes
+pub fn get_usage(path: &str) -> Option<(u64, u64)> {
+    let path = CString::new(path).ok()?;
+    let mut stats: statvfs = unsafe { mem::zeroed() };
+
+    let ret_val = unsafe { libc::statvfs(path.as_ptr(), &mut stats) };
+    if ret_val != 0 {
+        return None;
+    }
+
+    let block_size = stats.f_frsize as u64;
+    let total = stats.f_blocks as u64 * block_size;
+    let free = stats.f_bfree as u64 * block_size;
+
+    Some((total - free, total))
+}
+
+/// Finds the block device (e.g. /sys/class/block/nvme0n1p2) holding the given mount
+pub fn get_block_dir(mount: &Mount) -> Option<PathBuf> {
+    // Filesystems such as btrfs have anonymous device numbers, so fall back to
+    // the mount source in that case
+    fs::canonicalize(Path::new("/sys/dev/block/").join(&mount.device))
+        .or_else(|_| {
+            let device_name = mount.device_name().unwrap_or_default();
+            fs::canonicalize(Path::new("/sys/class/block/").join(device_name))
+        })
+        .ok()
+}
+
+/// Devices backing a device-mapper device, e.g. /sys/block/dm-0/slaves/nvme0n1p3
+pub fn get_slaves(block_dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(block_dir.join("slaves"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter_map(|entry| fs::canonicalize(entry.path()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Finds the whole disk (e.g. /sys/block/nvme0n1) behind a block device
+fn get_disk_dir_of(block_dir: PathBuf) -> Option<PathBuf> {
+    // LVM and LUKS volumes are device-mapper devices stacked on top of
+    // partitions, so follow them down to the first one
+    if block_dir.join("dm").exists() {
+        return get_slaves(&block_dir).into_iter().find_map(get_disk_dir_of);
+    }
+
+    // Partitions are subdirectories of their disk
+    if block_dir.join("partition").exists() {
+        block_dir.parent().map(Path::to_path_buf)
+    } else {
+        Some(block_dir)
+    }
+}
+
+/// Finds the whole disk (e.g. /sys/block/nvme0n1) holding the given mount
+pub fn get_disk_dir(mount: &Mount) -> Option<PathBuf> {
+    get_disk_dir_of(get_block_dir(mount)?)
+}
+
+/// Model of the disk, e.g. "Samsung SSD 970 EVO 500GB"
+pub fn get_disk_model(disk_dir: &Path) -> Option<String> {
+    let model = fs::read_to_string(disk_dir.join("device").join("model")).ok()?;
+    let model = model.trim();
+
+    if model.is_empty() {
+        None
+    } else {
+        Some(model.to_string())
+    }
+}
+
+/// Type of storage, e.g. "NVMe SSD" or "HDD"
+pub fn get_media_type(disk_dir: &Path) -> Option<&'static str> {
+    let disk_name = disk_dir.file_name()?.to_str()?;
+
+    if disk_name.starts_with("nvme") {
+        return Some("NVMe SSD");
+    }
+    if disk_name.starts_with("mmcblk") {
+        return Some("eMMC/SD");
+    }
+    // virtio and Xen disks claim to be rotational
+    if disk_name.starts_with("vd") || disk_name.starts_with("xvd") {
+        return Some("Virtual disk");
+    }
+
+    let rotational = fs::read_to_string(disk_dir.join("queue").join("rotational")).ok()?;
+    match rotational.trim() {
+        "1" => Some("HDD"),
+        _ => Some("SSD"),
+    }
+}
+
+/// Cumulative I/O of a block device since boot, from /proc/diskstats
+#[derive(Debug, Clone, Copy)]
+pub struct DiskStats {
+    pub read_bytes:    u64,
+    pub written_bytes: u64,
+}
+
+/// Reads the statistics of a block device, given its name (e.g. "nvme0n1p2")
+pub fn get_disk_stats(device_name: &str) -> Option<DiskStats> {
+    let diskstats = fs::read_to_string("/proc/diskstats").ok()?;
+
+    // e.g. " 259       2 nvme0n1p2 5595 3446 2008394 5988 2549 4623 938416 ..."
+    let mut fields = diskstats
+        .lines()
+        .map(|line| line.split_whitespace())
+        .find(|fields| fields.clone().nth(2) == Some(device_name))?
+        .skip(3);
+
+    // /proc/diskstats always counts 512-byte sectors
+    let sectors_read: u64 = fields.nth(2)?.parse().ok()?;
+    let sectors_written: u64 = fields.nth(3)?.parse().ok()?;
+
+    Some(DiskStats {
+        read_bytes:    sectors_read * 512,
+        written_bytes: sectors_written * 512,
+    })
+}
+
+#[derive(Debug)]
+pub struct NvmeHealth {
+    // Name of the controller, e.g. "nvme0"
+    pub name:         String,
+    pub model:        Option<String>,
+    // Composite temperature, in °C
+    pub temp:         Option<f64>,
+    // Estimate of the drive's life used, in percent. May exceed 100.
+    pub percent_used: Option<u8>,
+}
+
+/// Reads the "SMART / Health Information" log page of an NVMe controller.
+/// Requires permission to open the controller's character device, usually root.
+fn read_nvme_smart_log(controller: &str) -> Option<[u8; 512]> {
+    let device = File::open(Path::new("/dev/").join(controller)).ok()?;
+    let mut log = [0_u8; 512];
+
+    let mut cmd = NvmePassthruCmd {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        // The log applies to the whole controller
+        nsid: 0xFFFF_FFFF,
+        addr: log.as_mut_ptr() as u64,
+        data_len: log.len() as u32,
+        // Number of dwords to read minus one, and the log page identifier
+        cdw10: ((log.len() as u32 / 4 - 1) << 16) | NVME_LOG_SMART,
+        ..Default::default()
+    };
+
+    let ret_val = unsafe { libc::ioctl(device.as_raw_fd(), NVME_IOCTL_ADMIN_CMD as _, &mut cmd) };
+
+    if ret_val == 0 {
+        Some(log)
+    } else {
+        None
+    }
+}
+
+/// NVMe controllers register their hwmon chip either under themselves or under their PCI device
+fn read_nvme_hwmon_temp(controller_dir: &Path) -> Option<f64> {
+    [controller_dir.to_path_buf(), controller_dir.join("device")]
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("hwmon"))
+        .find_map(|entry| {
+            let millidegrees: i64 = fs::read_to_string(entry.path().join("temp1_input"))
+                .ok()?
+                .trim()
+                .parse()
+                .ok()?;
+            Some(millidegrees as f64 / 1000.0)
+        })
+}
+
+pub fn get_nvme_health() -> Vec<NvmeHealth> {
+    let entries = match fs::read_dir("/sys/class/nvme/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut drives: Vec<NvmeHealth> = entries
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let smart_log = read_nvme_smart_log(&name);
+
+            // Bytes 1 and 2 of the SMART log hold the composite temperature in Kelvin
+            let smart_temp = smart_log
+                .map(|log| u16::from_le_bytes([log[1], log[2]]))
+                .filter(|&kelvin| kelvin > 0)
+                .map(|kelvin| kelvin as f64 - 273.15);
+
+            NvmeHealth {
+                model: fs::read_to_string(entry.path().join("model"))
+                    .ok()
+                    .map(|model| model.trim().to_string()),
+                temp: read_nvme_hwmon_temp(&entry.path()).or(smart_temp),
+                // Byte 5 of the SMART log is the "Percentage Used"
+                percent_used: smart_log.map(|log| log[5]),
+                name,
+            }
+        })
+        .collect();
+
+    drives.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    drives
+}
@@ -0,0 +1,260 @@
+use crate::{
+    cpu,
+    util::{read_le_u16, read_le_u32},
+    virt,
+};
+
+use std::{fs, path::Path};
+
+/// SMBIOS structure type of memory devices
+const MEMORY_DEVICE: u8 = 17;
+
+/// A memory module, as described by the firmware in a SMBIOS type 17 structure
+#[derive(Debug)]
+pub struct MemoryDevice {
+    // In mebibytes
+    pub size:  u64,
+    // e.g. "DDR4"
+    pub kind:  Option<&'static str>,
+    // In MT/s, e.g. 3200
+    pub speed: Option<u32>,
+}
+
+fn memory_type_name(memory_type: u8) -> Option<&'static str> {
+    let name = match memory_type {
+        0x0F => "SDRAM",
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x14 => "DDR2 FB-DIMM",
+        0x18 => "DDR3",
+        0x1A => "DDR4",
+        0x1B => "LPDDR",
+        0x1C => "LPDDR2",
+        0x1D => "LPDDR3",
+        0x1E => "LPDDR4",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Decodes the formatted area of a memory device structure.
+/// Returns None for empty slots.
+fn parse_memory_device(raw: &[u8]) -> Option<MemoryDevice> {
+    // The header holds the type and the length of the formatted area
+    if *raw.first()? != MEMORY_DEVICE {
+        return None;
+    }
+    let raw = raw.get(..*raw.get(1)? as usize)?;
+
+    // Bit 15 tells whether the size is given in KiB rather than MiB,
+    // and 0x7FFF means it's in the extended size field
+    let size = match read_le_u16(raw, 0x0C)? {
+        0 | 0xFFFF => return None,
+        0x7FFF => u64::from(read_le_u32(raw, 0x1C)? & 0x7FFF_FFFF),
+        size if size & 0x8000 != 0 => u64::from(size & 0x7FFF) / 1024,
+        size => u64::from(size),
+    };
+
+    // Prefer the speed the module is configured to run at over its maximum speed
+    let speed = [0x20, 0x15]
+        .iter()
+        .filter_map(|&offset| read_le_u16(raw, offset))
+        .find(|&speed| speed != 0)
+        .and_then(|speed| match speed {
+            // The speed is in the extended speed field
+            0xFFFF => read_le_u32(raw, 0x54),
+            speed => Some(u32::from(speed)),
+        });
+
+    Some(MemoryDevice {
+        size,
+        kind: raw.get(0x12).copied().and_then(memory_type_name),
+        speed,
+    })
+}
+
+/// Lists the installed memory modules. The SMBIOS entries are usually only readable by root.
+pub fn get_memory_devices() -> Vec<MemoryDevice> {
+    let entries = match fs::read_dir("/sys/firmware/dmi/entries/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut entries: Vec<_> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            // Entries are named "{type}-{instance}", e.g. "17-0"
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with(&format!("{}-", MEMORY_DEVICE)))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort_unstable();
+
+    entries
+        .iter()
+        .filter_map(|entry| fs::read(entry.join("raw")).ok())
+        .filter_map(|raw| parse_memory_device(&raw))
+        .collect()
+}
+
+/// Strings firmwares fill DMI fields with when the vendor didn't set them
+const PLACEHOLDERS: &[&str] = &[
+    "To be filled by O.E.M.",
+    "To Be Filled By O.E.M.",
+    "Default string",
+    "System Product Name",
+    "System Version",
+    "System manufacturer",
+    "Not Applicable",
+    "Not Specified",
+    "None",
+    "O.E.M.",
+    "Type1ProductConfigId",
+    "x.x",
+];
+
+/// Reads a file of /sys/class/dmi/id/, e.g. "product_name", skipping placeholders
+pub fn read_id(name: &str) -> Option<String> {
+    let value = fs::read_to_string(Path::new("/sys/class/dmi/id/").join(name)).ok()?;
+    let value = value.trim();
+
+    if value.is_empty() || PLACEHOLDERS.contains(&value) {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Model of the machine, e.g. "ThinkPad X1 Carbon Gen 9" or "MS-7C02 B450 TOMAHAWK"
+pub fn get_host_model() -> Option<String> {
+    let product = read_id("product_name");
+    let version = read_id("product_version");
+
+    // Lenovo puts the machine type (e.g. "20XW0055US") in the product name
+    // and the marketing name in the version
+    if read_id("sys_vendor").as_deref() == Some("LENOVO") && version.is_some() {
+        return version;
+    }
+
+    match (product, version) {
+        (Some(product), Some(version)) if !product.contains(&version) => {
+            Some(format!("{} {}", product, version))
+        },
+        (Some(product), _) => Some(product),
+        // Custom-built desktops only have the name of the motherboard
+        _ => match (read_id("board_vendor"), read_id("board_name")) {
+            (Some(vendor), Some(board)) => Some(format!("{} {}", vendor, board)),
+            (_, board) => board,
+        },
+    }
+}
+
+#[derive(Debug)]
+pub struct Firmware {
+    // Whether the system was booted through UEFI rather than a legacy BIOS
+    pub uefi:    bool,
+    // e.g. "N32ET86W (1.62)"
+    pub version: Option<String>,
+    // Release date, as YYYY-MM-DD
+    pub date:    Option<String>,
+}
+
+/// DMI dates are given as MM/DD/YYYY
+fn parse_bios_date(date: &str) -> Option<String> {
+    let mut parts = date.split('/');
+    let (month, day, year) = (parts.next()?, parts.next()?, parts.next()?);
+
+    Some(format!("{}-{}-{}", year, month, day))
+}
+
+pub fn get_firmware() -> Option<Firmware> {
+    let uefi = Path::new("/sys/firmware/efi").exists();
+    let version = read_id("bios_version");
+    let date = read_id("bios_date").map(|date| parse_bios_date(&date).unwrap_or(date));
+
+    // Neither DMI nor EFI, as in most ARM boards
+    if !uefi && version.is_none() {
+        return None;
+    }
+
+    Some(Firmware { uefi, version, date })
+}
+
+/// Rough kind of machine, used to tell which fields are relevant (e.g. batteries on laptops)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Chassis {
+    Desktop,
+    Laptop,
+    Tablet,
+    Server,
+    Embedded,
+    VirtualMachine,
+}
+
+impl Chassis {
+    pub fn name(self) -> &'static str {
+        match self {
+            Chassis::Desktop => "Desktop",
+            Chassis::Laptop => "Laptop",
+            Chassis::Tablet => "Tablet",
+            Chassis::Server => "Server",
+            Chassis::Embedded => "Embedded",
+            Chassis::VirtualMachine => "Virtual machine",
+        }
+    }
+
+    /// Whether the machine may run on batteries and have a built-in display
+    pub fn is_portable(self) -> bool {
+        matches!(self, Chassis::Laptop | Chassis::Tablet)
+    }
+}
+
+/// Classifies the SMBIOS chassis types (see the System Enclosure structure)
+fn chassis_from_dmi_type(chassis_type: u8) -> Option<Chassis> {
+    let chassis = match chassis_type {
+        3..=7 | 13 | 15 | 16 | 24 | 35 | 36 => Chassis::Desktop,
+        8..=10 | 14 | 31 | 32 => Chassis::Laptop,
+        11 | 30 => Chassis::Tablet,
+        17 | 23 | 25 | 28 => Chassis::Server,
+        33 | 34 => Chassis::Embedded,
+        _ => return None,
+    };
+
+    Some(chassis)
+}
+
+/// Classifies the `chassis-type` property of the device tree, used on ARM
+fn chassis_from_device_tree(chassis_type: &str) -> Option<Chassis> {
+    let chassis = match chassis_type {
+        "desktop" => Chassis::Desktop,
+        "laptop" | "convertible" => Chassis::Laptop,
+        "tablet" | "handset" | "watch" => Chassis::Tablet,
+        "server" => Chassis::Server,
+        "embedded" => Chassis::Embedded,
+        _ => return None,
+    };
+
+    Some(chassis)
+}
+
+pub fn get_chassis() -> Option<Chassis> {
+    // Hypervisors usually report a desktop or an unknown chassis
+    if virt::get_hypervisor().is_some() {
+        return Some(Chassis::VirtualMachine);
+    }
+
+    read_id("chassis_type")
+        .and_then(|chassis_type| chassis_type.parse().ok())
+        .and_then(chassis_from_dmi_type)
+        .or_else(|| {
+            cpu::read_device_tree_strings("chassis-type")
+                .first()
+                .and_then(|chassis_type| chassis_from_device_tree(chassis_type))
+        })
+}
@@ -0,0 +1,239 @@
+use libc::c_ulong;
+
+use std::{fs, fs::File, os::unix::io::AsRawFd, path::Path};
+
+/// struct drm_mode_card_res, from drm/drm_mode.h
+#[repr(C)]
+#[derive(Default)]
+struct CardResources {
+    fb_id_ptr:        u64,
+    crtc_id_ptr:      u64,
+    connector_id_ptr: u64,
+    encoder_id_ptr:   u64,
+    count_fbs:        u32,
+    count_crtcs:      u32,
+    count_connectors: u32,
+    count_encoders:   u32,
+    min_width:        u32,
+    max_width:        u32,
+    min_height:       u32,
+    max_height:       u32,
+}
+
+/// struct drm_mode_modeinfo, from drm/drm_mode.h
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct ModeInfo {
+    clock:       u32,
+    hdisplay:    u16,
+    hsync_start: u16,
+    hsync_end:   u16,
+    htotal:      u16,
+    hskew:       u16,
+    vdisplay:    u16,
+    vsync_start: u16,
+    vsync_end:   u16,
+    vtotal:      u16,
+    vscan:       u16,
+    vrefresh:    u32,
+    flags:       u32,
+    kind:        u32,
+    name:        [u8; 32],
+}
+
+/// struct drm_mode_get_connector, from drm/drm_mode.h
+#[repr(C)]
+#[derive(Default)]
+struct GetConnector {
+    encoders_ptr:      u64,
+    modes_ptr:         u64,
+    props_ptr:         u64,
+    prop_values_ptr:   u64,
+    count_modes:       u32,
+    count_props:       u32,
+    count_encoders:    u32,
+    encoder_id:        u32,
+    connector_id:      u32,
+    connector_type:    u32,
+    connector_type_id: u32,
+    connection:        u32,
+    mm_width:          u32,
+    mm_height:         u32,
+    subpixel:          u32,
+    pad:               u32,
+}
+
+/// struct drm_mode_get_encoder, from drm/drm_mode.h
+#[repr(C)]
+#[derive(Default)]
+struct GetEncoder {
+    encoder_id:      u32,
+    encoder_type:    u32,
+    crtc_id:         u32,
+    possible_crtcs:  u32,
+    possible_clones: u32,
+}
+
+/// struct drm_mode_crtc, from drm/drm_mode.h
+#[repr(C)]
+#[derive(Default)]
+struct Crtc {
+    set_connectors_ptr: u64,
+    count_connectors:   u32,
+    crtc_id:            u32,
+    fb_id:              u32,
+    x:                  u32,
+    y:                  u32,
+    gamma_size:         u32,
+    mode_valid:         u32,
+    mode:               ModeInfo,
+}
+
+/// _IOWR('d', 0xA0, struct drm_mode_card_res)
+const DRM_IOCTL_MODE_GETRESOURCES: c_ulong = 0xC040_64A0;
+/// _IOWR('d', 0xA1, struct drm_mode_crtc)
+const DRM_IOCTL_MODE_GETCRTC: c_ulong = 0xC068_64A1;
+/// _IOWR('d', 0xA6, struct drm_mode_get_encoder)
+const DRM_IOCTL_MODE_GETENCODER: c_ulong = 0xC014_64A6;
+/// _IOWR('d', 0xA7, struct drm_mode_get_connector)
+const DRM_IOCTL_MODE_GETCONNECTOR: c_ulong = 0xC050_64A7;
+
+/// Value of drm_mode_get_connector.connection when a display is plugged in
+const CONNECTOR_CONNECTED: u32 = 1;
+const MODE_FLAG_INTERLACE: u32 = 1 << 4;
+const MODE_FLAG_DBLSCAN: u32 = 1 << 5;
+
+/// Names of the connector types, as used by the kernel in e.g. /sys/class/drm/card0-DP-1
+const CONNECTOR_TYPES: &[&str] = &[
+    "Unknown", "VGA", "DVI-I", "DVI-D", "DVI-A", "Composite", "SVIDEO", "LVDS", "Component",
+    "DIN", "DP", "HDMI-A", "HDMI-B", "TV", "eDP", "Virtual", "DSI", "DPI", "Writeback", "SPI",
+    "USB",
+];
+
+/// A connected display and the mode it's driven at
+#[derive(Debug)]
+pub struct Output {
+    // Name of the connector, prefixed by its card, e.g. "card0-DP-1"
+    pub connector: String,
+    pub width:     u16,
+    pub height:    u16,
+    // Refresh rate, in Hz
+    pub refresh:   f64,
+}
+
+fn ioctl<T>(card: &File, request: c_ulong, arg: &mut T) -> bool {
+    unsafe { libc::ioctl(card.as_raw_fd(), request as _, arg as *mut T) == 0 }
+}
+
+/// Computes the refresh rate from the pixel clock, more precisely than `vrefresh`
+fn refresh_rate(mode: &ModeInfo) -> f64 {
+    if mode.htotal == 0 || mode.vtotal == 0 {
+        return mode.vrefresh as f64;
+    }
+
+    let mut refresh = mode.clock as f64 * 1000.0 / (mode.htotal as f64 * mode.vtotal as f64);
+    if mode.flags & MODE_FLAG_INTERLACE != 0 {
+        refresh *= 2.0;
+    }
+    if mode.flags & MODE_FLAG_DBLSCAN != 0 {
+        refresh /= 2.0;
+    }
+    if mode.vscan > 1 {
+        refresh /= mode.vscan as f64;
+    }
+
+    refresh
+}
+
+fn get_connector_ids(card: &File) -> Option<Vec<u32>> {
+    // The first call only fills in the counts
+    let mut resources = CardResources::default();
+    if !ioctl(card, DRM_IOCTL_MODE_GETRESOURCES, &mut resources) {
+        return None;
+    }
+
+    let mut connector_ids = vec![0_u32; resources.count_connectors as usize];
+    let mut resources = CardResources {
+        connector_id_ptr: connector_ids.as_mut_ptr() as u64,
+        count_connectors: connector_ids.len() as u32,
+        ..Default::default()
+    };
+    if !ioctl(card, DRM_IOCTL_MODE_GETRESOURCES, &mut resources) {
+        return None;
+    }
+
+    // A connector may have gone away in between
+    connector_ids.truncate(resources.count_connectors as usize);
+    Some(connector_ids)
+}
+
+fn get_output(card: &File, card_name: &str, connector_id: u32) -> Option<Output> {
+    // Asking for no modes at all would make the kernel probe the connector, which is slow,
+    // so room for one is given instead. It's only filled in if there's a single mode.
+    let mut mode = ModeInfo::default();
+    let mut connector = GetConnector {
+        connector_id,
+        modes_ptr: &mut mode as *mut ModeInfo as u64,
+        count_modes: 1,
+        ..Default::default()
+    };
+    if !ioctl(card, DRM_IOCTL_MODE_GETCONNECTOR, &mut connector) {
+        return None;
+    }
+    if connector.connection != CONNECTOR_CONNECTED || connector.encoder_id == 0 {
+        return None;
+    }
+
+    let mut encoder = GetEncoder { encoder_id: connector.encoder_id, ..Default::default() };
+    if !ioctl(card, DRM_IOCTL_MODE_GETENCODER, &mut encoder) || encoder.crtc_id == 0 {
+        return None;
+    }
+
+    let mut crtc = Crtc { crtc_id: encoder.crtc_id, ..Default::default() };
+    if !ioctl(card, DRM_IOCTL_MODE_GETCRTC, &mut crtc) || crtc.mode_valid == 0 {
+        return None;
+    }
+
+    let connector_type = CONNECTOR_TYPES
+        .get(connector.connector_type as usize)
+        .unwrap_or(&"Unknown");
+
+    Some(Output {
+        connector: format!("{}-{}-{}", card_name, connector_type, connector.connector_type_id),
+        width: crtc.mode.hdisplay,
+        height: crtc.mode.vdisplay,
+        refresh: refresh_rate(&crtc.mode),
+    })
+}
+
+/// Reads the outputs being driven by every card, through the KMS API.
+/// Requires access to /dev/dri/card*, which the user logged in to the seat usually has.
+pub fn get_outputs() -> Vec<Output> {
+    let entries = match fs::read_dir("/dev/dri/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut cards: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("card"))
+        .collect();
+    cards.sort();
+
+    let mut outputs = vec![];
+    for card_name in cards {
+        let card = match File::open(Path::new("/dev/dri/").join(&card_name)) {
+            Ok(card) => card,
+            Err(_) => continue,
+        };
+
+        for connector_id in get_connector_ids(&card).unwrap_or_default() {
+            if let Some(output) = get_output(&card, &card_name, connector_id) {
+                outputs.push(output);
+            }
+        }
+    }
+
+    outputs
+}
@@ -0,0 +1,103 @@
+use std::{fs, path::Path};
+
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+
+/// Display descriptor holding the monitor name, e.g. "U2720Q"
+const DESCRIPTOR_NAME: u8 = 0xFC;
+/// Display descriptor holding unspecified text, which laptop panels use for
+/// their part number, e.g. "B140HAN04.0"
+const DESCRIPTOR_TEXT: u8 = 0xFE;
+
+/// Names the manufacturers of most monitors and laptop panels by their PNP ID
+fn manufacturer_name(pnp_id: &str) -> Option<&'static str> {
+    let name = match pnp_id {
+        "ACR" => "Acer",
+        "AOC" => "AOC",
+        "APP" => "Apple",
+        "AUO" => "AU Optronics",
+        "AUS" => "ASUS",
+        "BNQ" => "BenQ",
+        "BOE" => "BOE",
+        "CMN" => "Chimei Innolux",
+        "CSO" => "CSOT",
+        "DEL" => "Dell",
+        "EIZ" => "EIZO",
+        "GBT" => "Gigabyte",
+        "GSM" => "LG",
+        "HSD" => "HannStar",
+        "HWP" => "HP",
+        "IVM" => "Iiyama",
+        "IVO" => "InfoVision",
+        "LEN" => "Lenovo",
+        "LGD" => "LG Display",
+        "MSI" => "MSI",
+        "NEC" => "NEC",
+        "PHL" => "Philips",
+        "SAM" => "Samsung",
+        "SDC" => "Samsung Display",
+        "SHP" => "Sharp",
+        "SNY" => "Sony",
+        "VSC" => "ViewSonic",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// The three letters of the PNP ID are packed in bytes 8 and 9, five bits each
+fn pnp_id(edid: &[u8]) -> String {
+    let packed = u16::from_be_bytes([edid[8], edid[9]]);
+
+    [10, 5, 0]
+        .iter()
+        .map(|shift| (b'@' + ((packed >> shift) & 0x1F) as u8) as char)
+        .collect()
+}
+
+/// Reads the text of the first display descriptor of the given kind
+fn descriptor_text(edid: &[u8], kind: u8) -> Option<String> {
+    // Four 18-byte descriptors follow the basic display parameters and timings
+    (0..4).map(|index| &edid[54 + index * 18..72 + index * 18]).find_map(|descriptor| {
+        // Display descriptors start with a zeroed pixel clock
+        if descriptor[0..2] != [0, 0] || descriptor[3] != kind {
+            return None;
+        }
+
+        // The text ends with a newline and is padded with spaces
+        let text = descriptor[5..].split(|&byte| byte == b'\n').next()?;
+        let text = String::from_utf8_lossy(text).trim().to_string();
+
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    })
+}
+
+/// Names a monitor by its manufacturer and model, e.g. "Dell U2720Q"
+pub fn monitor_name(edid: &[u8]) -> Option<String> {
+    if edid.len() < 128 || edid[..8] != HEADER {
+        return None;
+    }
+
+    let pnp_id = pnp_id(edid);
+    let manufacturer = manufacturer_name(&pnp_id).unwrap_or(&pnp_id);
+
+    let model = descriptor_text(edid, DESCRIPTOR_NAME)
+        .or_else(|| descriptor_text(edid, DESCRIPTOR_TEXT))
+        .unwrap_or_else(|| format!("{:04X}", u16::from_le_bytes([edid[10], edid[11]])));
+
+    // Names often repeat the manufacturer, e.g. "DELL U2720Q"
+    if model.to_lowercase().starts_with(&manufacturer.to_lowercase()) {
+        Some(model)
+    } else {
+        Some(format!("{} {}", manufacturer, model))
+    }
+}
+
+/// Reads the name of the monitor plugged into a connector, e.g. "card0-DP-1"
+pub fn read_monitor_name(connector: &str) -> Option<String> {
+    let edid = fs::read(Path::new("/sys/class/drm/").join(connector).join("edid")).ok()?;
+    monitor_name(&edid)
+}
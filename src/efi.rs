@@ -0,0 +1,52 @@
+use std::{fs, path::Path};
+
+/// GUID of the variables defined by the UEFI specification
+const GLOBAL_VARIABLE_GUID: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SecureBoot {
+    Enabled,
+    Disabled,
+    // Disabled, and the firmware accepts new keys from the OS
+    SetupMode,
+    // Booted through a legacy BIOS, or the firmware doesn't implement it
+    NotSupported,
+}
+
+impl SecureBoot {
+    pub fn name(self) -> &'static str {
+        match self {
+            SecureBoot::Enabled => "Enabled",
+            SecureBoot::Disabled => "Disabled",
+            SecureBoot::SetupMode => "Disabled (setup mode)",
+            SecureBoot::NotSupported => "Not supported",
+        }
+    }
+}
+
+/// Reads a one-byte global EFI variable, such as SecureBoot or SetupMode
+fn read_variable(name: &str) -> Option<u8> {
+    let file_name = format!("{}-{}", name, GLOBAL_VARIABLE_GUID);
+
+    // efivarfs prefixes the data with 4 bytes of attributes
+    if let Ok(bytes) = fs::read(Path::new("/sys/firmware/efi/efivars/").join(&file_name)) {
+        return bytes.get(4).copied();
+    }
+
+    // The deprecated sysfs interface, for kernels without efivarfs
+    let sysfs_data = Path::new("/sys/firmware/efi/vars/").join(&file_name).join("data");
+    fs::read(sysfs_data).ok()?.first().copied()
+}
+
+pub fn get_secure_boot() -> SecureBoot {
+    if !Path::new("/sys/firmware/efi").exists() {
+        return SecureBoot::NotSupported;
+    }
+
+    match read_variable("SecureBoot") {
+        Some(1) => SecureBoot::Enabled,
+        Some(_) if read_variable("SetupMode") == Some(1) => SecureBoot::SetupMode,
+        Some(_) => SecureBoot::Disabled,
+        None => SecureBoot::NotSupported,
+    }
+}
@@ -0,0 +1,822 @@
+use crate::{inflate::decompress_zlib, sha1::sha1, util::read_be_u32};
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::{BinaryHeap, HashMap},
+    convert::TryInto,
+    fs::{self, File},
+    io::ErrorKind,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::{FileExt, MetadataExt, PermissionsExt},
+    },
+    path::{Path, PathBuf},
+};
+
+/// How many commits to walk when counting how far a branch is from its upstream,
+/// past which they're considered too far apart
+const MAX_COMMITS: usize = 10_000;
+/// How many symbolic refs to follow, e.g. from "HEAD" to "refs/heads/main"
+const MAX_SYMREFS: usize = 5;
+/// How many bytes of objects to keep around as the bases of deltas, per pack
+const MAX_CACHED_BASES: usize = 16 * 1024 * 1024;
+/// How many files of the work tree to hash, past which whether it's dirty is left unsaid
+const MAX_HASHED_FILES: usize = 64;
+/// Size past which an object is taken for a corrupt one, as commits and trees are far smaller
+const MAX_OBJECT_SIZE: u64 = 64 * 1024 * 1024;
+
+// Types of objects, as numbered within packs
+const COMMIT: u8 = 1;
+const TREE: u8 = 2;
+const BLOB: u8 = 3;
+const TAG: u8 = 4;
+const OFS_DELTA: u8 = 6;
+const REF_DELTA: u8 = 7;
+
+// Modes of the entries of trees and of the index
+const MODE_TYPE_MASK: u32 = 0o170000;
+const MODE_DIR: u32 = 0o040000;
+const MODE_SYMLINK: u32 = 0o120000;
+const MODE_GITLINK: u32 = 0o160000;
+
+type Oid = [u8; 20];
+
+fn parse_oid(hex: &str) -> Option<Oid> {
+    let hex = hex.get(..40)?;
+    let mut oid = [0_u8; 20];
+    for (byte, chunk) in oid.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).ok()?, 16).ok()?;
+    }
+    Some(oid)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// A pack of objects, with the index which tells where each of them starts.
+/// Neither is read whole, as they may take gigabytes in large repositories.
+struct Pack {
+    index:      File,
+    pack:       File,
+    // Number of objects with a name starting with each byte or a lower one
+    fanout:     Vec<u32>,
+    objects:    u32,
+    // Objects which others are deltas of, by their offset, as the versions of a tree are
+    // mostly deltas of a few others
+    bases:      RefCell<HashMap<u64, (u8, Vec<u8>)>>,
+    bases_size: Cell<usize>,
+}
+
+impl Pack {
+    /// Opens a pack by its version 2 index, e.g. "pack-<hash>.idx"
+    fn open(index_path: &Path) -> Option<Pack> {
+        let index = File::open(index_path).ok()?;
+        let pack = File::open(index_path.with_extension("pack")).ok()?;
+
+        let mut header = vec![0_u8; 8 + 256 * 4];
+        index.read_exact_at(&mut header, 0).ok()?;
+        if header[..8] != [0xFF, 0x74, 0x4F, 0x63, 0, 0, 0, 2] {
+            return None;
+        }
+        let fanout: Vec<u32> =
+            (0..256).map(|i| read_be_u32(&header, 8 + i * 4)).collect::<Option<_>>()?;
+        let objects = fanout[255];
+
+        Some(Pack {
+            index,
+            pack,
+            fanout,
+            objects,
+            bases: RefCell::new(HashMap::new()),
+            bases_size: Cell::new(0),
+        })
+    }
+
+    fn get_base(&self, offset: u64) -> Option<(u8, Vec<u8>)> {
+        self.bases.borrow().get(&offset).cloned()
+    }
+
+    /// Keeps an object around as a base, starting over once the cache is full
+    fn cache_base(&self, offset: u64, kind: u8, data: &[u8]) {
+        let mut bases = self.bases.borrow_mut();
+        if self.bases_size.get() + data.len() > MAX_CACHED_BASES {
+            bases.clear();
+            self.bases_size.set(0);
+        }
+
+        self.bases_size.set(self.bases_size.get() + data.len());
+        bases.insert(offset, (kind, data.to_vec()));
+    }
+
+    /// Finds where an object starts within the pack, by a binary search of the sorted names
+    fn find(&self, oid: &Oid) -> Option<u64> {
+        let names_start = 8 + 256 * 4;
+        let first = oid[0] as usize;
+        let mut low = if first == 0 { 0 } else { self.fanout[first - 1] };
+        let mut high = self.fanout[first];
+
+        let mut name = [0_u8; 20];
+        while low < high {
+            let middle = low + (high - low) / 2;
+            self.index.read_exact_at(&mut name, names_start + middle as u64 * 20).ok()?;
+
+            match name.cmp(oid) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return self.read_offset(middle),
+            }
+        }
+
+        None
+    }
+
+    /// Offsets which don't fit in 31 bits point to a table of 8-byte ones
+    fn read_offset(&self, position: u32) -> Option<u64> {
+        let objects = u64::from(self.objects);
+        let offsets_start = 8 + 256 * 4 + objects * 20 + objects * 4;
+
+        let mut bytes = [0_u8; 4];
+        self.index.read_exact_at(&mut bytes, offsets_start + u64::from(position) * 4).ok()?;
+        let offset = u32::from_be_bytes(bytes);
+        if offset & 0x8000_0000 == 0 {
+            return Some(u64::from(offset));
+        }
+
+        let mut bytes = [0_u8; 8];
+        let large_offsets_start = offsets_start + objects * 4;
+        let position = u64::from(offset & 0x7FFF_FFFF);
+        self.index.read_exact_at(&mut bytes, large_offsets_start + position * 8).ok()?;
+        Some(u64::from_be_bytes(bytes))
+    }
+
+    /// Reads the entry at `offset`: its type, the base it's a delta of, if it is one,
+    /// and its data, decompressed
+    fn read_entry(&self, offset: u64) -> Option<(u8, Option<Base>, Vec<u8>)> {
+        // The header is at most a few bytes, and the base of a delta at most 20 more
+        let mut header = [0_u8; 32];
+        let len = self.pack.read_at(&mut header, offset).ok()?;
+        let header = &header[..len];
+
+        // The type and the size come first, with the size continued in 7-bit groups
+        let mut byte = *header.first()?;
+        let kind = (byte >> 4) & 0x07;
+        let mut size = u64::from(byte & 0x0F);
+        let mut shift = 4;
+        let mut pos = 1;
+        while byte & 0x80 != 0 {
+            byte = *header.get(pos)?;
+            if shift > 63 {
+                return None;
+            }
+            size |= u64::from(byte & 0x7F) << shift;
+            shift += 7;
+            pos += 1;
+        }
+        if size > MAX_OBJECT_SIZE {
+            return None;
+        }
+
+        let base = match kind {
+            OFS_DELTA => {
+                // The distance back to the base
+                let distance = read_offset_varint(header, &mut pos)? as u64;
+                Some(Base::Offset(offset.checked_sub(distance)?))
+            },
+            REF_DELTA => {
+                let oid = header.get(pos..pos + 20)?.try_into().ok()?;
+                pos += 20;
+                Some(Base::Oid(oid))
+            },
+            _ => None,
+        };
+
+        // The most zlib may take to compress the object, as its deflateBound() tells
+        let max_len = size + (size >> 12) + (size >> 14) + (size >> 25) + 13;
+        let mut data = vec![0_u8; max_len as usize];
+        let len = self.pack.read_at(&mut data, offset + pos as u64).ok()?;
+        let data = decompress_zlib(&data[..len])?;
+
+        if data.len() as u64 != size {
+            return None;
+        }
+        Some((kind, base, data))
+    }
+}
+
+/// The tree the whole index amounts to, as cached by its TREE extension, which starts with
+/// the root: its empty path, its count of entries, which is -1 once a change is staged,
+/// its count of subtrees and the name of the tree, e.g. "\0" "1234 56\n" and 20 bytes
+fn parse_cache_tree(data: &[u8]) -> Option<Oid> {
+    let data = data.strip_prefix(b"\0")?;
+    let newline = data.iter().position(|&byte| byte == b'\n')?;
+    let counts = std::str::from_utf8(&data[..newline]).ok()?;
+
+    let entries: i64 = counts.split(' ').next()?.parse().ok()?;
+    if entries < 0 {
+        return None;
+    }
+    data.get(newline + 1..newline + 21)?.try_into().ok()
+}
+
+/// What a delta applies to: an entry earlier in the same pack, or any object
+enum Base {
+    Offset(u64),
+    Oid(Oid),
+}
+
+/// Reads a number in 7-bit groups, most significant first, where each group but the last
+/// also adds one, as used for the distances to the bases of deltas and within the index
+fn read_offset_varint(data: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut byte = *data.get(*pos)?;
+    let mut value = (byte & 0x7F) as usize;
+    *pos += 1;
+    while byte & 0x80 != 0 {
+        byte = *data.get(*pos)?;
+        value = value.checked_add(1)?.checked_mul(0x80)? | (byte & 0x7F) as usize;
+        *pos += 1;
+    }
+    Some(value)
+}
+
+/// Reads a size within a delta, in 7-bit groups, least significant first
+fn read_delta_size(delta: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut size = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *delta.get(*pos)?;
+        *pos += 1;
+        if shift > 63 {
+            return None;
+        }
+        size |= ((byte & 0x7F) as usize) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Some(size);
+        }
+    }
+}
+
+/// Rebuilds an object from its base and a delta, which is made of instructions to either
+/// copy a range of the base or insert new bytes
+fn apply_delta(base: &[u8], delta: &[u8]) -> Option<Vec<u8>> {
+    let mut pos = 0;
+    if read_delta_size(delta, &mut pos)? != base.len() {
+        return None;
+    }
+    let size = read_delta_size(delta, &mut pos)?;
+    if size as u64 > MAX_OBJECT_SIZE {
+        return None;
+    }
+    let mut output = Vec::with_capacity(size);
+
+    while pos < delta.len() {
+        let instruction = delta[pos];
+        pos += 1;
+
+        if instruction & 0x80 != 0 {
+            // Which bytes of the offset and of the size follow is told by the other bits
+            let mut read = |bits: std::ops::Range<u8>| -> Option<usize> {
+                let mut value = 0;
+                for (i, bit) in bits.enumerate() {
+                    if instruction & (1 << bit) != 0 {
+                        value |= (*delta.get(pos)? as usize) << (i * 8);
+                        pos += 1;
+                    }
+                }
+                Some(value)
+            };
+            let offset = read(0..4)?;
+            let len = match read(4..7)? {
+                0 => 0x10000,
+                len => len,
+            };
+            output.extend_from_slice(base.get(offset..offset + len)?);
+        } else if instruction != 0 {
+            let len = instruction as usize;
+            output.extend_from_slice(delta.get(pos..pos + len)?);
+            pos += len;
+        } else {
+            return None;
+        }
+    }
+
+    if output.len() == size {
+        Some(output)
+    } else {
+        None
+    }
+}
+
+/// A commit, as much of it as is needed to walk the history
+struct Commit {
+    tree:    Oid,
+    parents: Vec<Oid>,
+    time:    i64,
+}
+
+/// An entry of the index, which lists the files as they're staged
+struct IndexEntry {
+    mtime:         (u32, u32),
+    size:          u32,
+    mode:          u32,
+    oid:           Oid,
+    path:          String,
+    // Left out of a sparse checkout, so not in the work tree
+    skip_worktree: bool,
+    // Added with `git add -N`, which stages an empty file
+    intent_to_add: bool,
+}
+
+/// The index, with the tree it amounts to if that's still cached
+struct Index {
+    entries:  Vec<IndexEntry>,
+    tree:     Option<Oid>,
+    // Whether a merge left conflicts, as entries of the other stages
+    unmerged: bool,
+}
+
+/// A repository, found through the ".git" directory, or file, of its work tree
+struct Repository {
+    git_dir:    PathBuf,
+    // Where the objects and the shared refs are, which differs in worktrees
+    common_dir: PathBuf,
+    work_tree:  PathBuf,
+    packs:      Vec<Pack>,
+    commits:    HashMap<Oid, Commit>,
+}
+
+impl Repository {
+    /// Finds the repository the directory is in, if any
+    fn discover(dir: &Path) -> Option<Repository> {
+        let (work_tree, git_dir) = dir.ancestors().find_map(|dir| {
+            let dot_git = dir.join(".git");
+            if dot_git.is_dir() {
+                return Some((dir.to_path_buf(), dot_git));
+            }
+
+            // Submodules and worktrees point to their git directory,
+            // e.g. "gitdir: ../.git/modules/x"
+            let link = fs::read_to_string(&dot_git).ok()?;
+            let git_dir = link.strip_prefix("gitdir:")?.trim();
+            Some((dir.to_path_buf(), dir.join(git_dir)))
+        })?;
+
+        let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common_dir) => git_dir.join(common_dir.trim()),
+            Err(_) => git_dir.clone(),
+        };
+
+        let packs = fs::read_dir(common_dir.join("objects/pack"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "idx"))
+            .filter_map(|path| Pack::open(&path))
+            .collect();
+
+        Some(Repository { git_dir, common_dir, work_tree, packs, commits: HashMap::new() })
+    }
+
+    /// Reads a ref, loose or packed, following the symbolic ones
+    fn read_ref(&self, name: &str) -> Option<Oid> {
+        let mut name = name.to_string();
+
+        for _ in 0..MAX_SYMREFS {
+            // HEAD and the like belong to each worktree, the rest is shared
+            let loose = fs::read_to_string(self.git_dir.join(&name))
+                .or_else(|_| fs::read_to_string(self.common_dir.join(&name)));
+
+            match loose {
+                Ok(contents) => match contents.trim().strip_prefix("ref: ") {
+                    Some(target) => name = target.to_string(),
+                    None => return parse_oid(contents.trim()),
+                },
+                Err(_) => {
+                    let packed = fs::read_to_string(self.common_dir.join("packed-refs")).ok()?;
+                    return packed.lines().find_map(|line| {
+                        let (oid, line_name) = line.split_once(' ')?;
+                        if line_name == name { parse_oid(oid) } else { None }
+                    });
+                },
+            }
+        }
+
+        None
+    }
+
+    /// Reads an object, returning its type and its contents
+    fn read_object(&self, oid: &Oid) -> Option<(u8, Vec<u8>)> {
+        let hex = to_hex(oid);
+        let loose = self.common_dir.join("objects").join(&hex[..2]).join(&hex[2..]);
+        if let Ok(data) = fs::read(loose) {
+            // Loose objects start with a header such as "commit 230\0"
+            let data = decompress_zlib(&data)?;
+            let header_end = data.iter().position(|&byte| byte == 0)?;
+            let kind = match data[..header_end].split(|&byte| byte == b' ').next()? {
+                b"commit" => COMMIT,
+                b"tree" => TREE,
+                b"blob" => BLOB,
+                b"tag" => TAG,
+                _ => return None,
+            };
+            return Some((kind, data[header_end + 1..].to_vec()));
+        }
+
+        self.packs.iter().find_map(|pack| {
+            let offset = pack.find(oid)?;
+            self.read_packed(pack, offset)
+        })
+    }
+
+    /// Reads an object of a pack, applying the chain of deltas it's made of
+    fn read_packed(&self, pack: &Pack, offset: u64) -> Option<(u8, Vec<u8>)> {
+        // The deltas walked through, by the offsets of the objects they make up
+        let mut deltas = vec![];
+        let mut offset = offset;
+
+        let (kind, mut data) = loop {
+            if let Some(base) = pack.get_base(offset) {
+                break base;
+            }
+
+            let (kind, base, data) = pack.read_entry(offset)?;
+            match base {
+                None => {
+                    if !deltas.is_empty() {
+                        pack.cache_base(offset, kind, &data);
+                    }
+                    break (kind, data);
+                },
+                Some(Base::Offset(base_offset)) => {
+                    deltas.push((offset, data));
+                    offset = base_offset;
+                },
+                Some(Base::Oid(base)) => {
+                    deltas.push((offset, data));
+                    break self.read_object(&base)?;
+                },
+            }
+        };
+
+        // Every object made up along the way but the last one is the base of another
+        for (i, (offset, delta)) in deltas.iter().enumerate().rev() {
+            data = apply_delta(&data, delta)?;
+            if i > 0 {
+                pack.cache_base(*offset, kind, &data);
+            }
+        }
+        Some((kind, data))
+    }
+
+    /// Reads a commit, keeping it around for the next walks
+    fn read_commit(&mut self, oid: &Oid) -> Option<&Commit> {
+        if !self.commits.contains_key(oid) {
+            let (kind, data) = self.read_object(oid)?;
+            if kind != COMMIT {
+                return None;
+            }
+
+            let text = String::from_utf8_lossy(&data);
+            let mut commit = Commit { tree: [0; 20], parents: vec![], time: 0 };
+            // The headers end at the first empty line, before the message
+            for line in text.lines().take_while(|line| !line.is_empty()) {
+                if let Some(tree) = line.strip_prefix("tree ") {
+                    commit.tree = parse_oid(tree)?;
+                } else if let Some(parent) = line.strip_prefix("parent ") {
+                    commit.parents.push(parse_oid(parent)?);
+                } else if let Some(committer) = line.strip_prefix("committer ") {
+                    // e.g. "Name <email> 1710000000 +0100"
+                    let time = committer.rsplit(' ').nth(1)?;
+                    commit.time = time.parse().ok()?;
+                }
+            }
+            self.commits.insert(*oid, commit);
+        }
+
+        self.commits.get(oid)
+    }
+
+    /// Counts the commits only `local` has and the ones only `upstream` has. Commits are
+    /// walked newest first, so that the ones both have are mostly known as such before
+    /// they're reached. Those which turn out to be shared later on are walked again.
+    fn count_ahead_behind(&mut self, local: Oid, upstream: Oid) -> Option<(usize, usize)> {
+        const LOCAL: u8 = 1;
+        const UPSTREAM: u8 = 2;
+        const BOTH: u8 = LOCAL | UPSTREAM;
+
+        let mut flags: HashMap<Oid, u8> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        // The flags each commit was last walked with
+        let mut walked: HashMap<Oid, u8> = HashMap::new();
+        for (oid, flag) in [(local, LOCAL), (upstream, UPSTREAM)] {
+            *flags.entry(oid).or_insert(0) |= flag;
+            queue.push((self.read_commit(&oid)?.time, oid));
+        }
+
+        while let Some((_, oid)) = queue.pop() {
+            let flag = flags[&oid];
+            if walked.insert(oid, flag) == Some(flag) {
+                continue;
+            }
+            if walked.len() > MAX_COMMITS {
+                return None;
+            }
+
+            let parents = self.read_commit(&oid)?.parents.clone();
+            for parent in parents {
+                let parent_flag = flags.entry(parent).or_insert(0);
+                if *parent_flag | flag != *parent_flag {
+                    *parent_flag |= flag;
+                    queue.push((self.read_commit(&parent)?.time, parent));
+                }
+            }
+
+            // What's left is in the history of both
+            if queue.iter().all(|(_, oid)| flags[oid] == BOTH) {
+                break;
+            }
+        }
+
+        let count = |side| flags.values().filter(|&&flag| flag == side).count();
+        Some((count(LOCAL), count(UPSTREAM)))
+    }
+
+    /// Lists the files of a tree and of its subtrees, by their path
+    fn read_tree(&self, oid: &Oid, prefix: &str, files: &mut HashMap<String, (u32, Oid)>) {
+        let data = match self.read_object(oid) {
+            Some((TREE, data)) => data,
+            _ => return,
+        };
+
+        // Entries are made of their mode in octal, their name and their 20-byte name
+        let mut pos = 0;
+        while let Some(space) = data[pos..].iter().position(|&byte| byte == b' ') {
+            let mode = std::str::from_utf8(&data[pos..pos + space]).unwrap_or_default();
+            let mode = u32::from_str_radix(mode, 8).unwrap_or_default();
+            let name_start = pos + space + 1;
+            let name_end = match data[name_start..].iter().position(|&byte| byte == 0) {
+                Some(len) => name_start + len,
+                None => return,
+            };
+            let entry_oid: Oid = match data.get(name_end + 1..name_end + 21) {
+                Some(entry_oid) => entry_oid.try_into().unwrap(),
+                None => return,
+            };
+            pos = name_end + 21;
+
+            let name = String::from_utf8_lossy(&data[name_start..name_end]);
+            let path = format!("{}{}", prefix, name);
+            if mode & MODE_TYPE_MASK == MODE_DIR {
+                self.read_tree(&entry_oid, &format!("{}/", path), files);
+            } else {
+                files.insert(path, (mode, entry_oid));
+            }
+        }
+    }
+
+    /// Reads the index, of version 2, 3 or 4. A repository where nothing was staged yet
+    /// has none. Sparse and split indexes are None, as they only hold part of the entries.
+    fn read_index(&self) -> Option<Index> {
+        let data = match fs::read(self.git_dir.join("index")) {
+            Ok(data) => data,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Some(Index { entries: vec![], tree: None, unmerged: false });
+            },
+            Err(_) => return None,
+        };
+        if data.get(..4)? != b"DIRC" {
+            return None;
+        }
+        let version = read_be_u32(&data, 4)?;
+        let count = read_be_u32(&data, 8)?;
+
+        let mut entries = vec![];
+        let mut unmerged = false;
+        let mut pos = 12;
+        let mut previous_path: Vec<u8> = vec![];
+        for _ in 0..count {
+            let start = pos;
+            let flags = u16::from_be_bytes(data.get(start + 60..start + 62)?.try_into().ok()?);
+            let mut extended_flags = 0;
+            pos = start + 62;
+            if version >= 3 && flags & 0x4000 != 0 {
+                extended_flags = u16::from_be_bytes(data.get(pos..pos + 2)?.try_into().ok()?);
+                pos += 2;
+            }
+
+            // Version 4 only keeps what differs from the previous path
+            let path = if version >= 4 {
+                let strip = read_offset_varint(&data, &mut pos)?;
+                let suffix_len = data.get(pos..)?.iter().position(|&byte| byte == 0)?;
+                let kept = previous_path.len().checked_sub(strip)?;
+                let mut path = previous_path[..kept].to_vec();
+                path.extend_from_slice(&data[pos..pos + suffix_len]);
+                pos += suffix_len + 1;
+                path
+            } else {
+                let len = data.get(pos..)?.iter().position(|&byte| byte == 0)?;
+                let path = data[pos..pos + len].to_vec();
+                // Entries are padded with NULs up to a multiple of 8 bytes
+                pos = start + (pos - start + len + 8) / 8 * 8;
+                path
+            };
+
+            // Entries of unmerged paths have a stage other than 0
+            if (flags >> 12) & 0x03 != 0 {
+                unmerged = true;
+            }
+
+            entries.push(IndexEntry {
+                mtime:         (read_be_u32(&data, start + 8)?, read_be_u32(&data, start + 12)?),
+                mode:          read_be_u32(&data, start + 24)?,
+                size:          read_be_u32(&data, start + 36)?,
+                oid:           data.get(start + 40..start + 60)?.try_into().ok()?,
+                path:          String::from_utf8_lossy(&path).into_owned(),
+                skip_worktree: extended_flags & 0x4000 != 0,
+                intent_to_add: extended_flags & 0x2000 != 0,
+            });
+            previous_path = path;
+        }
+
+        // Extensions follow, each made of its signature and its size, up to the checksum
+        let mut tree = None;
+        while pos + 8 <= data.len().saturating_sub(20) {
+            let size = read_be_u32(&data, pos + 4)? as usize;
+            let extension = data.get(pos + 8..pos + 8 + size)?;
+            match &data[pos..pos + 4] {
+                b"TREE" => tree = parse_cache_tree(extension),
+                // Directories left out of a sparse index, and the base of a split one
+                b"sdir" | b"link" => return None,
+                _ => {},
+            }
+            pos += 8 + size;
+        }
+
+        Some(Index { entries, tree, unmerged })
+    }
+
+    /// Tells whether a file of the work tree differs from its entry in the index,
+    /// trusting the entry when the size and modification time it cached still match.
+    /// None if it would be hashed past the first MAX_HASHED_FILES.
+    fn is_modified(&self, entry: &IndexEntry, hashed: &mut usize) -> Option<bool> {
+        let path = self.work_tree.join(&entry.path);
+        let metadata = match fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => return Some(true),
+        };
+
+        let is_executable = metadata.permissions().mode() & 0o111 != 0;
+        if entry.mode & MODE_TYPE_MASK != MODE_SYMLINK
+            && (entry.mode & 0o111 != 0) != is_executable
+        {
+            return Some(true);
+        }
+        if metadata.size() as u32 != entry.size {
+            return Some(true);
+        }
+        if (metadata.mtime() as u32, metadata.mtime_nsec() as u32) == entry.mtime {
+            return Some(false);
+        }
+
+        // The file was touched, so hash it as git would, unless too many were
+        *hashed += 1;
+        if *hashed > MAX_HASHED_FILES {
+            return None;
+        }
+        let contents = if metadata.file_type().is_symlink() {
+            fs::read_link(&path).map(|target| target.as_os_str().as_bytes().to_vec())
+        } else {
+            fs::read(&path)
+        };
+        match contents {
+            Ok(contents) => {
+                let mut blob = format!("blob {}\0", contents.len()).into_bytes();
+                blob.extend_from_slice(&contents);
+                Some(sha1(&blob) != entry.oid)
+            },
+            Err(_) => Some(true),
+        }
+    }
+
+    /// Tells whether there are changes to the tracked files, staged or not. None if that
+    /// can't be told quickly, as too many files were touched since they were staged.
+    fn is_dirty(&mut self, head: Option<Oid>) -> Option<bool> {
+        let index = self.read_index()?;
+        if index.unmerged || index.entries.iter().any(|entry| entry.intent_to_add) {
+            return Some(true);
+        }
+
+        // The index is compared file by file against HEAD unless it still amounts to
+        // the tree of HEAD, as it does after a commit or a checkout
+        let head_tree = match head {
+            Some(head) => Some(self.read_commit(&head)?.tree),
+            None => None,
+        };
+        if head_tree.is_none() || index.tree != head_tree {
+            let mut files = HashMap::new();
+            if let Some(tree) = head_tree {
+                self.read_tree(&tree, "", &mut files);
+            }
+
+            let staged = index
+                .entries
+                .iter()
+                .any(|entry| files.remove(&entry.path) != Some((entry.mode, entry.oid)));
+            // Files of HEAD which aren't in the index were staged to be removed
+            if staged || !files.is_empty() {
+                return Some(true);
+            }
+        }
+
+        let mut hashed = 0;
+        let checked_out = index.entries.iter().filter(|entry| {
+            !entry.skip_worktree && entry.mode & MODE_TYPE_MASK != MODE_GITLINK
+        });
+        for entry in checked_out {
+            if self.is_modified(entry, &mut hashed)? {
+                return Some(true);
+            }
+        }
+
+        Some(false)
+    }
+
+    /// The upstream of a branch, as set in the config, e.g. "refs/remotes/origin/main" for
+    /// `[branch "main"]` with `remote = origin` and `merge = refs/heads/main`
+    fn get_upstream(&self, branch: &str) -> Option<String> {
+        let config = fs::read_to_string(self.common_dir.join("config")).ok()?;
+        let section = format!("[branch \"{}\"]", branch);
+
+        let mut in_section = false;
+        let (mut remote, mut merge) = (None, None);
+        for line in config.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_section = line == section;
+            } else if in_section {
+                if let Some((key, value)) = line.split_once('=') {
+                    match key.trim() {
+                        "remote" => remote = Some(value.trim().to_string()),
+                        "merge" => merge = Some(value.trim().to_string()),
+                        _ => {},
+                    }
+                }
+            }
+        }
+
+        let merge = merge?;
+        match remote?.as_str() {
+            // Branches may track another local branch
+            "." => Some(merge),
+            remote => {
+                let branch = merge.strip_prefix("refs/heads/")?;
+                Some(format!("refs/remotes/{}/{}", remote, branch))
+            },
+        }
+    }
+}
+
+/// Describes the repository the directory is in, e.g. "main (2 ahead, 1 behind, dirty)"
+/// or "1a2b3c4 (detached)", reading the .git directory itself
+pub fn get_git_info(dir: &Path) -> Option<String> {
+    let mut repository = Repository::discover(dir)?;
+
+    let head = fs::read_to_string(repository.git_dir.join("HEAD")).ok()?;
+    let branch = head.trim().strip_prefix("ref: refs/heads/").map(ToString::to_string);
+    let head_oid = repository.read_ref("HEAD");
+
+    let mut status = vec![];
+    let name = match (&branch, head_oid) {
+        (Some(branch), _) => branch.clone(),
+        (None, Some(oid)) => {
+            status.push("detached".to_string());
+            to_hex(&oid)[..7].to_string()
+        },
+        (None, None) => return None,
+    };
+
+    let upstream = branch
+        .as_deref()
+        .and_then(|branch| repository.get_upstream(branch))
+        .and_then(|upstream| repository.read_ref(&upstream));
+    if let (Some(local), Some(upstream)) = (head_oid, upstream) {
+        if let Some((ahead, behind)) = repository.count_ahead_behind(local, upstream) {
+            if ahead > 0 {
+                status.push(format!("{} ahead", ahead));
+            }
+            if behind > 0 {
+                status.push(format!("{} behind", behind));
+            }
+        }
+    }
+    if repository.is_dirty(head_oid) == Some(true) {
+        status.push("dirty".to_string());
+    }
+
+    if status.is_empty() {
+        Some(name)
+    } else {
+        Some(format!("{} ({})", name, status.join(", ")))
+    }
+}
@@ -0,0 +1,407 @@
+use crate::util::read_u64;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Possible locations of the PCI ID database, as shipped by hwdata/pciutils.
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+#[derive(Debug)]
+pub struct Gpu {
+    // Human-readable name, e.g. "AMD Radeon RX 580"
+    pub name:       String,
+    // Canonical sysfs path of the device, e.g. "/sys/devices/pci0000:00/0000:00:01.0/0000:01:00.0"
+    pub sysfs_dir:  PathBuf,
+    // Dedicated video memory in bytes, only exposed by some drivers (e.g. amdgpu)
+    pub vram_total: Option<u64>,
+    pub vram_used:  Option<u64>,
+    // Current load, as reported by `gpu_busy_percent`
+    pub busy:       Option<u64>,
+    // Temperature in degrees Celsius, as reported by the device's hwmon
+    pub temp:       Option<f64>,
+    // Version of the proprietary driver in use, if any
+    pub driver:     Option<String>,
+}
+
+/// Reads a sysfs file containing an hexadecimal number, such as "0x10de".
+fn read_hex(path: &Path) -> Option<u32> {
+    let text = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Short names for the most common GPU vendors, since the pci.ids ones are
+/// rather verbose (e.g. "Advanced Micro Devices, Inc. [AMD/ATI]").
+fn vendor_short_name(vendor_id: u16) -> Option<&'static str> {
+    match vendor_id {
+        0x1002 | 0x1022 => Some("AMD"),
+        0x10de => Some("NVIDIA"),
+        0x8086 => Some("Intel"),
+        0x1af4 => Some("Red Hat"),
+        0x15ad => Some("VMware"),
+        0x80ee => Some("VirtualBox"),
+        _ => None,
+    }
+}
+
+/// Looks up the vendor and device names in the pci.ids database.
+fn lookup_pci_ids(vendor_id: u16, device_id: u16) -> Option<(String, Option<String>)> {
+    let database = PCI_IDS_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())?;
+
+    let vendor_id = format!("{:04x}", vendor_id);
+    let device_id = format!("{:04x}", device_id);
+
+    let mut lines = database.lines();
+
+    // Vendor lines are not indented, e.g. "10de  NVIDIA Corporation"
+    let vendor_name = lines.find_map(|line| {
+        line.strip_prefix(vendor_id.as_str())
+            .map(|name| name.trim().to_string())
+    })?;
+
+    // Device lines are indented by one tab, e.g. "\t1b80  GP104 [GeForce GTX 1080]"
+    // and end when the next vendor starts
+    let device_name = lines
+        .take_while(|line| line.starts_with('\t') || line.starts_with('#') || line.is_empty())
+        .filter(|line| !line.starts_with("\t\t"))
+        .find_map(|line| {
+            line.strip_prefix('\t')?
+                .strip_prefix(device_id.as_str())
+                .map(|name| name.trim().to_string())
+        });
+
+    Some((vendor_name, device_name))
+}
+
+/// Turns a pci.ids device name such as "Ellesmere [Radeon RX 470/480/570/570X/580/580X/590]"
+/// into its marketing name, i.e. what's inside the brackets.
+fn marketing_name(device_name: &str) -> &str {
+    match (device_name.rfind('['), device_name.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &device_name[start + 1..end],
+        _ => device_name,
+    }
+}
+
+fn pci_gpu_name(vendor_id: u16, device_id: u16) -> String {
+    let (vendor_name, device_name) = match lookup_pci_ids(vendor_id, device_id) {
+        Some((vendor_name, device_name)) => (
+            vendor_short_name(vendor_id)
+                .map(ToString::to_string)
+                .unwrap_or(vendor_name),
+            device_name,
+        ),
+        None => (
+            vendor_short_name(vendor_id)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("{:04x}", vendor_id)),
+            None,
+        ),
+    };
+
+    match device_name {
+        Some(device_name) => format!("{} {}", vendor_name, marketing_name(&device_name)),
+        None => format!("{} Device {:04x}", vendor_name, device_id),
+    }
+}
+
+/// Non-PCI GPUs (e.g. on ARM SoCs) only tell us their driver name
+fn platform_gpu_name(device_dir: &Path) -> Option<String> {
+    let uevent = fs::read_to_string(device_dir.join("uevent")).ok()?;
+    uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DRIVER="))
+        .map(ToString::to_string)
+}
+
+/// Reads the first temperature sensor from the hwmon chip bound to the device
+fn read_device_temp(device_dir: &Path) -> Option<f64> {
+    let hwmon_dir = fs::read_dir(device_dir.join("hwmon"))
+        .ok()?
+        .filter_map(Result::ok)
+        .next()?
+        .path();
+
+    // Value in millidegrees Celsius
+    let millidegrees = read_u64(&hwmon_dir.join("temp1_input"))?;
+    Some(millidegrees as f64 / 1000.0)
+}
+
+fn gpu_from_device_dir(device_dir: &Path) -> Option<Gpu> {
+    let sysfs_dir = fs::canonicalize(device_dir).ok()?;
+
+    let ids = (
+        read_hex(&sysfs_dir.join("vendor")),
+        read_hex(&sysfs_dir.join("device")),
+    );
+
+    let name = match ids {
+        (Some(vendor_id), Some(device_id)) => pci_gpu_name(vendor_id as u16, device_id as u16),
+        _ => platform_gpu_name(&sysfs_dir)?,
+    };
+
+    Some(Gpu {
+        name,
+        vram_total: read_u64(&sysfs_dir.join("mem_info_vram_total")),
+        vram_used: read_u64(&sysfs_dir.join("mem_info_vram_used")),
+        busy: read_u64(&sysfs_dir.join("gpu_busy_percent")),
+        temp: read_device_temp(&sysfs_dir),
+        driver: None,
+        sysfs_dir,
+    })
+}
+
+/// Directories where Mesa's libraries are usually installed
+fn library_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("/usr/lib/"), PathBuf::from("/usr/lib64/")];
+
+    // Debian-based distros use multiarch directories such as /usr/lib/x86_64-linux-gnu
+    if let Ok(entries) = fs::read_dir("/usr/lib/") {
+        dirs.extend(
+            entries
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_name().to_string_lossy().ends_with("-linux-gnu"))
+                .map(|entry| entry.path()),
+        );
+    }
+
+    dirs
+}
+
+/// Since Mesa 24.1, all Gallium drivers live in a library named after the
+/// Mesa version, e.g. "libgallium-24.2.3-arch1.1.so"
+fn mesa_version_from_libgallium() -> Option<String> {
+    library_dirs().into_iter().find_map(|dir| {
+        fs::read_dir(dir).ok()?.filter_map(Result::ok).find_map(|entry| {
+            let file_name = entry.file_name();
+            let version = file_name
+                .to_str()?
+                .strip_prefix("libgallium-")?
+                .strip_suffix(".so")?;
+
+            // Remove distro suffixes such as "-arch1.1"
+            Some(version.split('-').next()?.to_string())
+        })
+    })
+}
+
+/// Looks for Mesa in pacman's local database, e.g. "/var/lib/pacman/local/mesa-1:24.1.1-1"
+fn mesa_version_from_pacman() -> Option<String> {
+    fs::read_dir("/var/lib/pacman/local/")
+        .ok()?
+        .filter_map(Result::ok)
+        .find_map(|entry| {
+            let file_name = entry.file_name();
+            let version = file_name.to_str()?.strip_prefix("mesa-")?;
+            // Skip the epoch, if any
+            let version = version.rsplit(':').next()?;
+            // Skip packages such as "mesa-utils"
+            if !version.starts_with(|ch: char| ch.is_ascii_digit()) {
+                return None;
+            }
+            // Skip the pkgrel
+            version
+                .rsplit_once('-')
+                .map(|(version, _pkgrel)| version.to_string())
+        })
+}
+
+/// Looks for Mesa's DRI drivers package in dpkg's database
+fn mesa_version_from_dpkg() -> Option<String> {
+    let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+
+    let paragraph = status.split("\n\n").find(|paragraph| {
+        paragraph.starts_with("Package: libgl1-mesa-dri\n")
+            || paragraph.starts_with("Package: libglx-mesa0\n")
+    })?;
+
+    // e.g. "Version: 22.3.6-1+deb12u1"
+    let version = paragraph
+        .lines()
+        .find_map(|line| line.strip_prefix("Version: "))?;
+    // Skip the epoch, if any
+    let version = version.rsplit(':').next()?;
+    // Skip the Debian revision
+    version.split('-').next().map(ToString::to_string)
+}
+
+pub fn get_mesa_version() -> Option<String> {
+    mesa_version_from_libgallium()
+        .or_else(mesa_version_from_pacman)
+        .or_else(mesa_version_from_dpkg)
+}
+
+/// Vulkan ICD (Installable Client Driver) manifests, such as
+/// "/usr/share/vulkan/icd.d/radeon_icd.x86_64.json"
+#[derive(Debug)]
+pub struct VulkanIcd {
+    // Driver name, e.g. "radeon" or "nvidia"
+    pub name:        String,
+    // Highest Vulkan version supported by the driver, e.g. "1.3.255"
+    pub api_version: Option<String>,
+}
+
+pub fn get_vulkan_icds() -> Vec<VulkanIcd> {
+    let mut icds: Vec<VulkanIcd> = vec![];
+
+    for dir in &["/usr/share/vulkan/icd.d/", "/etc/vulkan/icd.d/"] {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name();
+            let name = match file_name.to_str().and_then(|name| name.split("_icd").next()) {
+                Some(name) if name.ends_with(".json") => name.trim_end_matches(".json"),
+                Some(name) => name,
+                None => continue,
+            };
+
+            if icds.iter().any(|icd| icd.name == name) {
+                continue;
+            }
+
+            // Quick and dirty search for `"api_version": "1.3.255"` to avoid a JSON parser
+            let api_version = fs::read_to_string(entry.path()).ok().and_then(|manifest| {
+                let start = manifest.find("\"api_version\"")? + "\"api_version\"".len();
+                manifest[start..]
+                    .split('"')
+                    .nth(1)
+                    .map(ToString::to_string)
+            });
+
+            icds.push(VulkanIcd {
+                name: name.to_string(),
+                api_version,
+            });
+        }
+    }
+
+    icds.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    icds
+}
+
+/// Gets the version of the loaded NVIDIA kernel module from a line such as
+/// "NVRM version: NVIDIA UNIX x86_64 Kernel Module  535.54.03  Tue Jun  6 22:20:39 UTC 2023"
+fn get_nvidia_driver_version() -> Option<String> {
+    let text = fs::read_to_string("/proc/driver/nvidia/version").ok()?;
+    let nvrm_line = text.lines().find(|line| line.starts_with("NVRM"))?;
+
+    nvrm_line
+        .split_whitespace()
+        .find(|word| word.contains('.') && word.chars().all(|ch| ch.is_ascii_digit() || ch == '.'))
+        .map(ToString::to_string)
+}
+
+/// The DRM path gives poor results with the proprietary NVIDIA driver, so we
+/// look at what the driver itself reports in /proc/driver/nvidia/gpus/{bus location}
+fn apply_nvidia_driver_info(gpus: &mut Vec<Gpu>) {
+    let entries = match fs::read_dir("/proc/driver/nvidia/gpus/") {
+        Ok(entries) => entries,
+        // The nvidia kernel module is not loaded
+        Err(_) => return,
+    };
+
+    let driver_version = get_nvidia_driver_version();
+
+    for entry in entries.filter_map(Result::ok) {
+        let information = match fs::read_to_string(entry.path().join("information")) {
+            Ok(information) => information,
+            Err(_) => continue,
+        };
+
+        // e.g. "Model: 		 NVIDIA GeForce RTX 3080"
+        let model = information
+            .lines()
+            .find_map(|line| line.strip_prefix("Model:"))
+            .map(|model| model.trim().to_string());
+
+        // The directory is named after the PCI bus location, e.g. "0000:01:00.0"
+        let bus_location = entry.file_name();
+        let existing = gpus
+            .iter_mut()
+            .find(|gpu| gpu.sysfs_dir.file_name() == Some(bus_location.as_os_str()));
+
+        match (existing, model) {
+            (Some(gpu), model) => {
+                if let Some(model) = model {
+                    gpu.name = model;
+                }
+                gpu.driver = driver_version.clone();
+            },
+            (None, Some(model)) => gpus.push(Gpu {
+                name:       model,
+                sysfs_dir:  PathBuf::from("/sys/bus/pci/devices/").join(&bus_location),
+                vram_total: None,
+                vram_used:  None,
+                busy:       None,
+                temp:       None,
+                driver:     driver_version.clone(),
+            }),
+            (None, None) => {},
+        }
+    }
+}
+
+/// Lists the GPUs present in the system, looking first at the DRM subsystem
+/// and then at PCI display controllers which may have no DRM driver bound.
+pub fn get_gpus() -> Vec<Gpu> {
+    let mut gpus: Vec<Gpu> = vec![];
+
+    if let Ok(entries) = fs::read_dir("/sys/class/drm/") {
+        let mut cards: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                // Skip connectors such as "card0-HDMI-A-1" and render nodes
+                name.starts_with("card") && !name.contains('-')
+            })
+            .map(|entry| entry.path().join("device"))
+            .collect();
+
+        cards.sort_unstable();
+
+        for card in cards {
+            if let Some(gpu) = gpu_from_device_dir(&card) {
+                if !gpus.iter().any(|other| other.sysfs_dir == gpu.sysfs_dir) {
+                    gpus.push(gpu);
+                }
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir("/sys/bus/pci/devices/") {
+        let mut devices: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect();
+
+        devices.sort_unstable();
+
+        for device in devices {
+            // Base class 0x03 is "Display controller"
+            match read_hex(&device.join("class")) {
+                Some(class) if class >> 16 == 0x03 => {},
+                _ => continue,
+            }
+
+            if let Some(gpu) = gpu_from_device_dir(&device) {
+                if !gpus.iter().any(|other| other.sysfs_dir == gpu.sysfs_dir) {
+                    gpus.push(gpu);
+                }
+            }
+        }
+    }
+
+    apply_nvidia_driver_info(&mut gpus);
+
+    gpus
+}
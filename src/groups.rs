@@ -0,0 +1,72 @@
+use crate::util::char_ptr_to_string;
+
+use libc::{getgrgid_r, getgroups, gid_t, group};
+
+use std::{mem, ptr};
+
+/// Groups worth pointing out, as they grant root or access to devices
+const NOTABLE_GROUPS: &[&str] = &[
+    "wheel",
+    "sudo",
+    "admin",
+    "adm",
+    "docker",
+    "podman",
+    "libvirt",
+    "kvm",
+    "lxd",
+    "incus-admin",
+    "video",
+    "render",
+    "audio",
+    "input",
+    "plugdev",
+    "dialout",
+    "uucp",
+];
+
+/// Name of a group, as in /etc/group or given by NSS, e.g. through LDAP
+fn get_group_name(gid: gid_t) -> Option<String> {
+    // Let rustc infer the type of `buf`, as in get_username_home_dir_and_shell
+    let mut buf = [0; 2048];
+    let mut result = ptr::null_mut();
+    let mut group: group = unsafe { mem::zeroed() };
+
+    let code = unsafe { getgrgid_r(gid, &mut group, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if code == 0 && !result.is_null() {
+        Some(unsafe { char_ptr_to_string(group.gr_name) })
+    } else {
+        None
+    }
+}
+
+/// Counts the supplementary groups of the user, pointing out the notable ones,
+/// e.g. "12 (wheel, docker, video)"
+pub fn get_groups() -> Option<String> {
+    let count = unsafe { getgroups(0, ptr::null_mut()) };
+    if count <= 0 {
+        return None;
+    }
+
+    let mut gids: Vec<gid_t> = vec![0; count as usize];
+    let count = unsafe { getgroups(count, gids.as_mut_ptr()) };
+    if count < 0 {
+        return None;
+    }
+    gids.truncate(count as usize);
+    // Groups without a name still count, e.g. the ones of a container
+    let total = gids.len();
+
+    let names: Vec<String> = gids.into_iter().filter_map(get_group_name).collect();
+    let notable: Vec<&str> = NOTABLE_GROUPS
+        .iter()
+        .filter(|notable| names.iter().any(|name| name == *notable))
+        .copied()
+        .collect();
+
+    if notable.is_empty() {
+        Some(total.to_string())
+    } else {
+        Some(format!("{} ({})", total, notable.join(", ")))
+    }
+}
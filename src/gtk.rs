@@ -0,0 +1,70 @@
+use crate::{
+    dconf,
+    util::{get_config_dir, read_ini},
+};
+
+use std::env;
+
+/// Reads a key of the [Settings] group of e.g. ~/.config/gtk-3.0/settings.ini
+fn read_settings_ini(version: &str, key: &str) -> Option<String> {
+    let path = get_config_dir()?.join(format!("gtk-{}", version)).join("settings.ini");
+    read_ini(&path, "Settings", key)
+}
+
+/// GNOME and the desktops built on it apply the settings stored in dconf,
+/// and don't keep settings.ini up to date
+pub fn uses_gsettings() -> bool {
+    let desktop = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+    ["gnome", "unity", "budgie", "pantheon"]
+        .iter()
+        .any(|name| desktop.contains(name))
+}
+
+/// Reads a setting of GTK 3 and GTK 4, given its name in settings.ini, e.g. "gtk-theme-name",
+/// and its gsettings key, e.g. "gtk-theme"
+pub fn get_setting(key: &str, gsettings_key: &str) -> (Option<String>, Option<String>) {
+    let gsettings =
+        || dconf::read_string(&format!("/org/gnome/desktop/interface/{}", gsettings_key));
+
+    if uses_gsettings() {
+        if let Some(value) = gsettings() {
+            return (Some(value.clone()), Some(value));
+        }
+    }
+
+    let (gtk3, gtk4) = (read_settings_ini("3.0", key), read_settings_ini("4.0", key));
+    if gtk3.is_none() && gtk4.is_none() {
+        let value = gsettings();
+        (value.clone(), value)
+    } else {
+        (gtk3, gtk4)
+    }
+}
+
+/// e.g. "Adwaita-dark (GTK3/4)" or "Arc (GTK3), Adwaita (GTK4)"
+pub fn format_versions(gtk3: Option<String>, gtk4: Option<String>) -> Option<String> {
+    match (gtk3, gtk4) {
+        (Some(gtk3), Some(gtk4)) if gtk3 == gtk4 => Some(format!("{} (GTK3/4)", gtk3)),
+        (Some(gtk3), Some(gtk4)) => Some(format!("{} (GTK3), {} (GTK4)", gtk3, gtk4)),
+        (Some(gtk3), None) => Some(format!("{} (GTK3)", gtk3)),
+        (None, Some(gtk4)) => Some(format!("{} (GTK4)", gtk4)),
+        (None, None) => None,
+    }
+}
+
+/// Name of the GTK theme, e.g. "Adwaita-dark (GTK3/4)"
+pub fn get_gtk_theme() -> Option<String> {
+    let (mut gtk3, gtk4) = get_setting("gtk-theme-name", "gtk-theme");
+
+    // GTK_THEME overrides the theme of GTK 3 applications, e.g. "Adwaita:dark"
+    if let Ok(theme) = env::var("GTK_THEME") {
+        let mut theme = theme.split(':');
+        let name = theme.next().unwrap_or_default();
+        if !name.is_empty() {
+            let variant = theme.next().map(|variant| format!("-{}", variant)).unwrap_or_default();
+            gtk3 = Some(format!("{}{}", name, variant));
+        }
+    }
+
+    format_versions(gtk3, gtk4)
+}
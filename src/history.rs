@@ -0,0 +1,220 @@
+use crate::{
+    disk,
+    sqlite::{Database, Value},
+};
+
+use std::{
+    fs::{self, File},
+    mem,
+    os::unix::fs::{FileExt, MetadataExt},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+// Offsets within the ext2/3/4 superblock, which starts 1024 bytes into the device
+const EXT_SUPERBLOCK: u64 = 1024;
+const EXT_MAGIC_OFFSET: usize = 0x38;
+const EXT_MKFS_TIME_OFFSET: usize = 0x108;
+const EXT_MAGIC: u16 = 0xEF53;
+
+/// Days between 1970-01-01 and the given date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+/// Converts a time of the local timezone to a Unix timestamp, as mktime(3) knows the timezone
+fn local_to_timestamp(date: [i64; 6]) -> Option<i64> {
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    tm.tm_year = (date[0] - 1900) as libc::c_int;
+    tm.tm_mon = (date[1] - 1) as libc::c_int;
+    tm.tm_mday = date[2] as libc::c_int;
+    tm.tm_hour = date[3] as libc::c_int;
+    tm.tm_min = date[4] as libc::c_int;
+    tm.tm_sec = date[5] as libc::c_int;
+    // Let mktime figure out whether daylight saving time was in effect
+    tm.tm_isdst = -1;
+
+    match unsafe { libc::mktime(&mut tm) } {
+        -1 => None,
+        timestamp => Some(timestamp),
+    }
+}
+
+/// Parses the timestamps of the logs, which are either in local time, e.g.
+/// "2024-03-12 10:15:32" or "2024-03-12 10:15", or ISO 8601 with an offset, e.g.
+/// "2024-03-12T10:15:32+0100"
+fn parse_timestamp(text: &str) -> Option<i64> {
+    let (date, time) = text.trim().split_once([' ', 'T'])?;
+    let time = time.trim_start();
+
+    // The offset follows the time, as "+0100", "-03:00" or "Z"
+    let offset_start = time.find(['+', '-', 'Z']);
+    let (time, offset) = match offset_start {
+        Some(start) => (&time[..start], Some(&time[start..])),
+        None => (time, None),
+    };
+
+    let mut fields = [0_i64; 6];
+    let date = date.split('-').chain(time.split(':'));
+    for (field, value) in fields.iter_mut().zip(date) {
+        *field = value.parse().ok()?;
+    }
+
+    let offset = match offset {
+        None => return local_to_timestamp(fields),
+        Some("Z") => 0,
+        Some(offset) => {
+            let digits = offset[1..].replace(':', "");
+            let hours: i64 = digits.get(..2)?.parse().ok()?;
+            let minutes: i64 = digits.get(2..).unwrap_or("0").parse().unwrap_or(0);
+            let seconds = hours * 3600 + minutes * 60;
+            if offset.starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            }
+        },
+    };
+
+    let days = days_from_civil(fields[0], fields[1], fields[2]);
+    Some(days * 86400 + fields[3] * 3600 + fields[4] * 60 + fields[5] - offset)
+}
+
+/// pacman logs every transaction, e.g.
+/// "[2024-03-12T10:15:32+0100] [PACMAN] starting full system upgrade"
+/// and then "[2024-03-12T10:15:40+0100] [ALPM] upgraded linux (6.8.0 -> 6.8.1)"
+fn pacman_last_update() -> Option<i64> {
+    let log = fs::read_to_string("/var/log/pacman.log").ok()?;
+    let line = log.lines().rev().find(|line| {
+        line.contains("[PACMAN] starting full system upgrade") || line.contains("[ALPM] upgraded ")
+    })?;
+
+    let timestamp = line.strip_prefix('[')?.split(']').next()?;
+    parse_timestamp(timestamp)
+}
+
+/// dpkg logs every action, e.g. "2024-03-12 10:15:32 upgrade bash:amd64 5.2.15-2 5.2.15-2+b1".
+/// Logs are rotated weekly, so the previous one is also read.
+fn dpkg_last_update() -> Option<i64> {
+    ["/var/log/dpkg.log", "/var/log/dpkg.log.1"].iter().find_map(|path| {
+        let log = fs::read_to_string(path).ok()?;
+        let fields = log
+            .lines()
+            .rev()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .find(|fields| fields.get(2) == Some(&"upgrade"))?;
+
+        parse_timestamp(&format!("{} {}", fields[0], fields[1]))
+    })
+}
+
+/// dnf keeps its history in SQLite, with a row per transaction in the `trans` table, e.g.
+/// (id, dt_begin, dt_end, rpmdb_version_begin, rpmdb_version_end, releasever, user_id,
+/// cmdline, ...), where dnf5 calls the command line its description
+fn dnf_last_update() -> Option<i64> {
+    [
+        "/usr/lib/sysimage/libdnf5/transaction_history.sqlite",
+        "/var/lib/dnf/history.sqlite",
+    ]
+    .iter()
+    .find_map(|path| {
+        let database = Database::open(path.as_ref())?;
+        let mut last_update = None;
+
+        database.visit_table("trans", &mut |row| {
+            let is_update = match row.get(7) {
+                Some(Value::Bytes(cmdline)) => {
+                    let cmdline = String::from_utf8_lossy(cmdline);
+                    cmdline.contains("upgrade") || cmdline.contains("update")
+                },
+                _ => false,
+            };
+
+            if let (true, Some(Value::Integer(end))) = (is_update, row.get(2)) {
+                last_update = last_update.max(Some(*end));
+            }
+        });
+
+        last_update
+    })
+}
+
+/// When packages were last upgraded, as a Unix timestamp, going by the logs
+/// of the package manager
+pub fn get_last_update() -> Option<i64> {
+    pacman_last_update()
+        .or_else(dpkg_last_update)
+        .or_else(dnf_last_update)
+}
+
+/// ext2, ext3 and ext4 record when the filesystem was made in their superblock,
+/// which is only readable with access to the device, usually as root
+fn ext_creation_time() -> Option<i64> {
+    let mount = disk::get_root_mount()?;
+    if !["ext2", "ext3", "ext4"].contains(&mount.fs_type.as_str()) {
+        return None;
+    }
+
+    let block_dir = disk::get_block_dir(&mount)?;
+    let device = File::open(Path::new("/dev").join(block_dir.file_name()?)).ok()?;
+    let mut superblock = [0_u8; 1024];
+    device.read_exact_at(&mut superblock, EXT_SUPERBLOCK).ok()?;
+
+    let magic = [superblock[EXT_MAGIC_OFFSET], superblock[EXT_MAGIC_OFFSET + 1]];
+    if u16::from_le_bytes(magic) != EXT_MAGIC {
+        return None;
+    }
+
+    let time = &superblock[EXT_MKFS_TIME_OFFSET..EXT_MKFS_TIME_OFFSET + 4];
+    match u32::from_le_bytes([time[0], time[1], time[2], time[3]]) {
+        0 => None,
+        time => Some(time as i64),
+    }
+}
+
+/// When the root directory was created, which most filesystems record,
+/// as e.g. `stat -c %W /` shows
+fn root_birth_time() -> Option<i64> {
+    let created = fs::metadata("/").ok()?.created().ok()?;
+    match created.duration_since(UNIX_EPOCH).ok()?.as_secs() {
+        0 => None,
+        time => Some(time as i64),
+    }
+}
+
+/// When the first packages were installed, as pacman records for each package,
+/// e.g. "%INSTALLDATE%" followed by "1711234567", and as dpkg keeps a list
+/// of the files of each package
+fn first_package_time() -> Option<i64> {
+    if let Ok(packages) = fs::read_dir("/var/lib/pacman/local") {
+        return packages
+            .flatten()
+            .filter_map(|package| fs::read_to_string(package.path().join("desc")).ok())
+            .filter_map(|desc| {
+                let (_, rest) = desc.split_once("%INSTALLDATE%\n")?;
+                rest.lines().next()?.trim().parse().ok()
+            })
+            .min();
+    }
+
+    fs::read_dir("/var/lib/dpkg/info")
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".list"))
+        .filter_map(|entry| Some(entry.metadata().ok()?.mtime()))
+        .min()
+}
+
+/// Estimates when the system was installed, as a Unix timestamp, from when the root
+/// filesystem was made or else from when the first packages were installed
+pub fn get_install_date() -> Option<i64> {
+    ext_creation_time()
+        .or_else(root_birth_time)
+        .or_else(first_package_time)
+}
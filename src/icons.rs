@@ -0,0 +1,24 @@
+use crate::{
+    gtk,
+    util::{get_config_dir, read_ini},
+};
+
+/// Name of the icon theme of GTK and of KDE applications, e.g. "Papirus-Dark (GTK3/4)"
+/// or "Adwaita (GTK3/4), breeze-dark (KDE)"
+pub fn get_icon_theme() -> Option<String> {
+    let (gtk3, gtk4) = gtk::get_setting("gtk-icon-theme-name", "icon-theme");
+    let kde = get_config_dir()
+        .and_then(|config_dir| read_ini(&config_dir.join("kdeglobals"), "Icons", "Theme"));
+
+    match kde {
+        // Plasma keeps the GTK settings in sync with its own
+        Some(kde) if gtk3.as_ref() == Some(&kde) && gtk4.as_ref() == Some(&kde) => {
+            Some(format!("{} (GTK3/4, KDE)", kde))
+        },
+        Some(kde) => match gtk::format_versions(gtk3, gtk4) {
+            Some(gtk) => Some(format!("{}, {} (KDE)", gtk, kde)),
+            None => Some(format!("{} (KDE)", kde)),
+        },
+        None => gtk::format_versions(gtk3, gtk4),
+    }
+}
@@ -0,0 +1,277 @@
+/// Base lengths of the length codes 257 to 285, and their extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances of the distance codes, and their extra bits
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which the lengths of the code length codes are stored
+const CODE_LENGTH_ORDER: [usize; 19] =
+    [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const END_OF_BLOCK: u16 = 256;
+
+// Flags of the gzip header
+const FEXTRA: u8 = 1 << 2;
+const FNAME: u8 = 1 << 3;
+const FCOMMENT: u8 = 1 << 4;
+const FHCRC: u8 = 1 << 1;
+
+/// Reads the bits of a DEFLATE stream, least significant first
+struct BitReader<'a> {
+    data:    &'a [u8],
+    pos:     usize,
+    buffer:  u64,
+    count:   u32,
+    // Bytes fed past the end of the data, as zeros, to peek at the last codes
+    overrun: usize,
+}
+
+impl BitReader<'_> {
+    fn refill(&mut self) {
+        while self.count <= 56 {
+            let byte = match self.data.get(self.pos) {
+                Some(&byte) => byte,
+                None => {
+                    self.overrun += 1;
+                    0
+                },
+            };
+            self.buffer |= (byte as u64) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+    }
+
+    fn bits(&mut self, count: u32) -> Option<u32> {
+        if count == 0 {
+            return Some(0);
+        }
+        self.refill();
+        // Reading into the padding means the stream was cut short
+        if count as usize > (self.count as usize).saturating_sub(self.overrun * 8) {
+            return None;
+        }
+
+        let value = (self.buffer & ((1 << count) - 1)) as u32;
+        self.buffer >>= count;
+        self.count -= count;
+        Some(value)
+    }
+
+    /// Stored blocks start at the next byte
+    fn align(&mut self) {
+        let padding = self.count % 8;
+        self.buffer >>= padding;
+        self.count -= padding;
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> Option<u16> {
+        self.refill();
+        let entry = huffman.table[(self.buffer & ((1 << huffman.max_len) - 1)) as usize];
+        let (symbol, len) = ((entry & 0xFFFF) as u16, entry >> 16);
+        if len == 0 {
+            return None;
+        }
+
+        self.bits(len)?;
+        Some(symbol)
+    }
+}
+
+/// A canonical Huffman code, decoded through a table indexed by the next `max_len` bits
+struct Huffman {
+    // The symbol in the low 16 bits, and the length of its code in the high ones
+    table:   Vec<u32>,
+    max_len: u32,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Option<Huffman> {
+        let max_len = lengths.iter().copied().max().unwrap_or(0) as u32;
+        if max_len > 15 {
+            return None;
+        }
+
+        let mut counts = [0_u32; 16];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut next_code = [0_u32; 16];
+        let mut code = 0;
+        for len in 1..16 {
+            code = (code + counts[len - 1]) << 1;
+            next_code[len] = code;
+        }
+
+        let mut table = vec![0_u32; 1 << max_len];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len == 0 {
+                continue;
+            }
+            let len = len as u32;
+            let code = next_code[len as usize];
+            next_code[len as usize] += 1;
+
+            // Codes are packed starting from their most significant bit
+            let reversed = code.reverse_bits() >> (32 - len);
+            let mut index = reversed as usize;
+            while index < table.len() {
+                table[index] = symbol as u32 | (len << 16);
+                index += 1 << len;
+            }
+        }
+
+        Some(Huffman { table, max_len })
+    }
+}
+
+fn fixed_codes() -> Option<(Huffman, Huffman)> {
+    let mut lengths = [0_u8; 288];
+    lengths[..144].iter_mut().for_each(|len| *len = 8);
+    lengths[144..256].iter_mut().for_each(|len| *len = 9);
+    lengths[256..280].iter_mut().for_each(|len| *len = 7);
+    lengths[280..].iter_mut().for_each(|len| *len = 8);
+
+    Some((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+fn dynamic_codes(reader: &mut BitReader) -> Option<(Huffman, Huffman)> {
+    let literals = reader.bits(5)? as usize + 257;
+    let distances = reader.bits(5)? as usize + 1;
+    let code_lengths = reader.bits(4)? as usize + 4;
+
+    let mut code_length_lengths = [0_u8; 19];
+    for &index in CODE_LENGTH_ORDER.iter().take(code_lengths) {
+        code_length_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&code_length_lengths)?;
+
+    let mut lengths = vec![];
+    while lengths.len() < literals + distances {
+        let (len, repeat) = match reader.decode(&code_length_code)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last()?, 3 + reader.bits(2)?),
+            17 => (0, 3 + reader.bits(3)?),
+            18 => (0, 11 + reader.bits(7)?),
+            _ => return None,
+        };
+        lengths.resize(lengths.len() + repeat as usize, len);
+    }
+    if lengths.len() != literals + distances {
+        return None;
+    }
+
+    Some((Huffman::new(&lengths[..literals])?, Huffman::new(&lengths[literals..])?))
+}
+
+/// Decompresses a raw DEFLATE stream, as described in RFC 1951
+pub fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = BitReader { data, pos: 0, buffer: 0, count: 0, overrun: 0 };
+    let mut output = Vec::with_capacity(data.len() * 4);
+
+    loop {
+        let is_final = reader.bits(1)? == 1;
+
+        let (literal_code, distance_code) = match reader.bits(2)? {
+            0 => {
+                reader.align();
+                let len = reader.bits(16)?;
+                let complement = reader.bits(16)?;
+                if len != !complement & 0xFFFF {
+                    return None;
+                }
+                for _ in 0..len {
+                    output.push(reader.bits(8)? as u8);
+                }
+
+                if is_final {
+                    return Some(output);
+                }
+                continue;
+            },
+            1 => fixed_codes()?,
+            2 => dynamic_codes(&mut reader)?,
+            _ => return None,
+        };
+
+        loop {
+            let symbol = reader.decode(&literal_code)?;
+            if symbol < END_OF_BLOCK {
+                output.push(symbol as u8);
+                continue;
+            }
+            if symbol == END_OF_BLOCK {
+                break;
+            }
+
+            let index = (symbol - 257) as usize;
+            let len = *LENGTH_BASE.get(index)? as usize
+                + reader.bits(*LENGTH_EXTRA.get(index)? as u32)? as usize;
+
+            let index = reader.decode(&distance_code)? as usize;
+            let distance = *DISTANCE_BASE.get(index)? as usize
+                + reader.bits(*DISTANCE_EXTRA.get(index)? as u32)? as usize;
+
+            // The match may overlap the bytes it produces
+            let start = output.len().checked_sub(distance)?;
+            for offset in 0..len {
+                output.push(output[start + offset]);
+            }
+        }
+
+        if is_final {
+            return Some(output);
+        }
+    }
+}
+
+/// Decompresses a gzip file, whose header may carry a file name and comment
+pub fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+    if data.get(..3)? != [0x1F, 0x8B, 0x08] {
+        return None;
+    }
+
+    let flags = data[3];
+    let mut offset = 10;
+
+    if flags & FEXTRA != 0 {
+        let len = u16::from_le_bytes([*data.get(offset)?, *data.get(offset + 1)?]) as usize;
+        offset += 2 + len;
+    }
+    // The file name and the comment end with a NUL
+    for flag in &[FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            offset += data.get(offset..)?.iter().position(|&byte| byte == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        offset += 2;
+    }
+
+    inflate(data.get(offset..)?)
+}
+
+/// Decompresses a zlib stream, as described in RFC 1950, such as the objects of git
+pub fn decompress_zlib(data: &[u8]) -> Option<Vec<u8>> {
+    let (method, flags) = (*data.first()?, *data.get(1)?);
+    // The header is a multiple of 31, and no preset dictionary is expected
+    let is_valid = (u16::from(method) << 8 | u16::from(flags)) % 31 == 0;
+    if method & 0x0F != 8 || !is_valid || flags & 0x20 != 0 {
+        return None;
+    }
+
+    inflate(&data[2..])
+}
@@ -0,0 +1,30 @@
+use std::{fs, path::Path};
+
+/// Names the init system, e.g. "systemd" or "OpenRC", from the name of PID 1 and the
+/// directories each of them creates in /run
+pub fn get_init_system() -> Option<String> {
+    let comm = fs::read_to_string("/proc/1/comm").ok()?;
+    let exists = |path: &str| Path::new(path).exists();
+
+    let init = match comm.trim_end() {
+        "systemd" => "systemd",
+        "dinit" => "dinit",
+        "s6-svscan" => "s6",
+        "runit" | "runit-init" => "runit",
+        "shepherd" => "GNU Shepherd",
+        "openrc-init" => "OpenRC",
+        // Init processes for containers
+        "tini" | "docker-init" => "tini",
+        "dumb-init" => "dumb-init",
+        "catatonit" => "catatonit",
+        // OpenRC and runit may also be started by sysvinit or BusyBox
+        "init" if exists("/run/systemd/system") => "systemd",
+        "init" if exists("/run/openrc") => "OpenRC",
+        "init" if exists("/run/runit") || exists("/etc/runit/1") => "runit",
+        "init" if exists("/run/s6") || exists("/run/66") => "s6",
+        "init" if exists("/etc/inittab") => "SysV init",
+        _ => return None,
+    };
+
+    Some(init.to_string())
+}
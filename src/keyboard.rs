@@ -0,0 +1,105 @@
+use crate::util::get_config_dir;
+
+use std::{env, fs, path::PathBuf};
+
+/// Finds the value following a key in config files of many shapes, such as
+/// `XKBLAYOUT="us"`, `kb_layout = us`, `input * xkb_layout us` or
+/// `Option "XkbLayout" "us"`
+fn find_value(text: &str, key: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .find_map(|line| {
+            let line = line.replace('=', " ");
+            let mut tokens = line.split_whitespace().map(|token| token.trim_matches('"'));
+            tokens.find(|token| *token == key)?;
+
+            let value = tokens.next()?;
+            if value.is_empty() {
+                None
+            } else {
+                Some(value.to_string())
+            }
+        })
+}
+
+/// Reads the layout and the variant from a config file, e.g. ("us,br", ",abnt2")
+fn read_layout(path: PathBuf, layout_key: &str, variant_key: &str) -> Option<(String, String)> {
+    let text = fs::read_to_string(path).ok()?;
+    let layout = find_value(&text, layout_key)?;
+    let variant = find_value(&text, variant_key).unwrap_or_default();
+
+    Some((layout, variant))
+}
+
+/// Layout configured for the Wayland compositor, which doesn't expose its keymap to clients
+fn get_wayland_layout() -> Option<(String, String)> {
+    // wlroots compositors without a config of their own, e.g. river or labwc
+    if let Ok(layout) = env::var("XKB_DEFAULT_LAYOUT") {
+        return Some((layout, env::var("XKB_DEFAULT_VARIANT").unwrap_or_default()));
+    }
+
+    let config_dir = get_config_dir()?;
+
+    read_layout(config_dir.join("sway").join("config"), "xkb_layout", "xkb_variant")
+        .or_else(|| {
+            let hyprland = config_dir.join("hypr").join("hyprland.conf");
+            read_layout(hyprland, "kb_layout", "kb_variant")
+        })
+        .or_else(|| read_layout(config_dir.join("kxkbrc"), "LayoutList", "VariantList"))
+}
+
+/// Layout configured system-wide, as set by localectl or the installer
+fn get_system_layout() -> Option<(String, String)> {
+    read_layout(
+        PathBuf::from("/etc/X11/xorg.conf.d/00-keyboard.conf"),
+        "XkbLayout",
+        "XkbVariant",
+    )
+    .or_else(|| read_layout(PathBuf::from("/etc/default/keyboard"), "XKBLAYOUT", "XKBVARIANT"))
+    .or_else(|| read_layout(PathBuf::from("/etc/vconsole.conf"), "XKBLAYOUT", "XKBVARIANT"))
+    // The console keymap, e.g. "br-abnt2"
+    .or_else(|| {
+        let vconsole = fs::read_to_string("/etc/vconsole.conf").ok()?;
+        Some((find_value(&vconsole, "KEYMAP")?, String::new()))
+    })
+}
+
+/// Pairs each layout with its variant, e.g. ("us,br", ",abnt2") -> "us, br (abnt2)"
+fn format_layout(layout: &str, variant: &str) -> String {
+    let mut variants = variant.split(',');
+
+    layout
+        .split(',')
+        .map(|layout| match variants.next().map(str::trim) {
+            Some(variant) if !variant.is_empty() => format!("{} ({})", layout.trim(), variant),
+            _ => layout.trim().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// e.g. "us, br (abnt2)"
+pub fn get_keyboard_layout() -> Option<String> {
+    #[cfg(feature = "use_xlib")]
+    let x11_layout = if env::var_os("DISPLAY").is_some() {
+        unsafe { crate::keyboardx11::get_keyboard_layout() }
+    } else {
+        None
+    };
+
+    #[cfg(not(feature = "use_xlib"))]
+    let x11_layout = None;
+
+    let wayland_layout = || {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            get_wayland_layout()
+        } else {
+            None
+        }
+    };
+
+    let (layout, variant) = x11_layout.or_else(wayland_layout).or_else(get_system_layout)?;
+
+    Some(format_layout(&layout, &variant))
+}
@@ -0,0 +1,68 @@
+use x11::xlib::{
+    Atom, Display, XCloseDisplay, XDefaultRootWindow, XFree, XGetWindowProperty, XInternAtom,
+    XOpenDisplay, XA_STRING,
+};
+
+use std::{ffi::CString, os::raw::{c_int, c_uchar, c_ulong}, ptr, slice};
+
+/// Reads the layouts and variants of the X keymap, e.g. ("us,br", ",abnt2")
+///
+/// The XKB rules, model, layouts, variants and options set through setxkbmap
+/// or the X server config are kept as NUL-separated strings in the
+/// `_XKB_RULES_NAMES` property of the root window.
+pub unsafe fn get_keyboard_layout() -> Option<(String, String)> {
+    let display: *mut Display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return None;
+    }
+
+    let property_name = CString::new("_XKB_RULES_NAMES").ok()?;
+    let property = XInternAtom(display, property_name.as_ptr(), 1);
+
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut items: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut value: *mut c_uchar = ptr::null_mut();
+
+    let status = if property == 0 {
+        -1
+    } else {
+        XGetWindowProperty(
+            display,
+            XDefaultRootWindow(display),
+            property,
+            0,
+            1024,
+            0,
+            XA_STRING,
+            &mut actual_type,
+            &mut actual_format,
+            &mut items,
+            &mut bytes_after,
+            &mut value,
+        )
+    };
+
+    let names = if status == 0 && !value.is_null() && actual_format == 8 {
+        let bytes = slice::from_raw_parts(value, items as usize);
+        let names: Vec<String> = bytes
+            .split(|&byte| byte == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        Some(names)
+    } else {
+        None
+    };
+
+    if !value.is_null() {
+        XFree(value as *mut _);
+    }
+    XCloseDisplay(display);
+
+    let names = names?;
+    let layout = names.get(2).filter(|layout| !layout.is_empty())?.clone();
+    let variant = names.get(3).cloned().unwrap_or_default();
+
+    Some((layout, variant))
+}
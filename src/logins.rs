@@ -0,0 +1,250 @@
+use crate::sqlite::{Database, Value};
+
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    fs::{self, File},
+    os::unix::fs::FileExt,
+    path::Path,
+};
+
+/// Lists the sessions open right now, one record per terminal
+const UTMP: &str = "/run/utmp";
+/// Lists every login and logout since it was rotated
+const WTMP: &str = "/var/log/wtmp";
+/// Holds the last login of each user, as a record per user ID
+const LASTLOG: &str = "/var/log/lastlog";
+/// What replaces lastlog, with no limits on the user IDs and timestamps. Its `Lastlog2`
+/// table holds (Name, Time, TTY, RemoteHost, Service)
+const LASTLOG2: &str = "/var/lib/lastlog/lastlog2.db";
+/// Where logind keeps the state of each session, e.g. "/run/systemd/sessions/3"
+const SESSIONS: &str = "/run/systemd/sessions";
+
+/// Size of a record of utmp and wtmp, which glibc keeps the same on 32 and 64-bit machines
+const RECORD_SIZE: usize = 384;
+/// Size of a record of lastlog, made of the time, the line and the host
+const LASTLOG_RECORD_SIZE: usize = 292;
+/// Type of the records of login sessions, as opposed to boots, runlevels or dead processes
+const USER_PROCESS: i16 = 7;
+
+/// How many places sessions come from to show, before leaving the rest to a count
+const MAX_SOURCES: usize = 3;
+
+/// A record of utmp or wtmp
+struct Entry {
+    kind: i16,
+    pid:  u32,
+    line: String,
+    user: String,
+    host: String,
+    time: i64,
+}
+
+/// Reads a string of a record, which is NUL-padded but not always NUL-terminated
+fn read_field(record: &[u8]) -> String {
+    let end = record.iter().position(|&byte| byte == 0).unwrap_or(record.len());
+    String::from_utf8_lossy(&record[..end]).into_owned()
+}
+
+/// Reads the records of a file laid out as struct utmp, which is in the byte order
+/// of the machine
+fn read_entries(path: &str) -> Vec<Entry> {
+    let data = match fs::read(path) {
+        Ok(data) => data,
+        Err(_) => return vec![],
+    };
+
+    data.chunks_exact(RECORD_SIZE)
+        .map(|record| {
+            let read_i32 = |offset: usize| {
+                i32::from_ne_bytes(record[offset..offset + 4].try_into().unwrap())
+            };
+
+            Entry {
+                kind: i16::from_ne_bytes([record[0], record[1]]),
+                pid:  read_i32(4) as u32,
+                line: read_field(&record[8..40]),
+                user: read_field(&record[44..76]),
+                host: read_field(&record[76..332]),
+                time: i64::from(read_i32(340)),
+            }
+        })
+        .collect()
+}
+
+/// A session someone is logged in, with the terminal or the host it comes from
+struct Session {
+    user:   String,
+    source: String,
+}
+
+/// Sessions as utmp lists them, leaving out the ones whose processes exited uncleanly
+fn get_utmp_sessions() -> Vec<Session> {
+    read_entries(UTMP)
+        .into_iter()
+        .filter(|entry| entry.kind == USER_PROCESS && !entry.user.is_empty())
+        .filter(|entry| Path::new(&format!("/proc/{}", entry.pid)).exists())
+        .map(|entry| {
+            let source = if entry.host.is_empty() { entry.line } else { entry.host };
+            Session { user: entry.user, source }
+        })
+        .collect()
+}
+
+/// Sessions as logind tracks them, for systems which no longer write utmp
+fn get_logind_sessions() -> Vec<Session> {
+    let entries = match fs::read_dir(SESSIONS) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let state = fs::read_to_string(entry.path()).ok()?;
+            let get = |key: &str| {
+                state.lines().find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            };
+
+            // Display managers log in their greeters as sessions of their own
+            if get("CLASS") != Some("user") || get("STATE") == Some("closing") {
+                return None;
+            }
+
+            let source = get("REMOTE_HOST").or_else(|| get("TTY")).or_else(|| get("SEAT"));
+            Some(Session {
+                user:   get("USER")?.to_string(),
+                source: source.unwrap_or("unknown").to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Counts who's logged in and the places they're logged in from, e.g.
+/// "2 users, 3 sessions (tty1, 192.168.0.12, pts/3)"
+pub fn get_users() -> Option<String> {
+    let mut sessions = get_utmp_sessions();
+    if sessions.is_empty() {
+        sessions = get_logind_sessions();
+    }
+    if sessions.is_empty() {
+        return None;
+    }
+
+    let users: HashSet<&str> = sessions.iter().map(|session| session.user.as_str()).collect();
+    let mut sources: Vec<&str> = vec![];
+    for session in &sessions {
+        if !sources.contains(&session.source.as_str()) {
+            sources.push(&session.source);
+        }
+    }
+
+    let plural = |count: usize, noun: &str| {
+        format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+    };
+
+    let mut shown = sources.iter().take(MAX_SOURCES).copied().collect::<Vec<_>>().join(", ");
+    if sources.len() > MAX_SOURCES {
+        shown.push_str(&format!(" and {} more", sources.len() - MAX_SOURCES));
+    }
+
+    Some(format!(
+        "{}, {} ({})",
+        plural(users.len(), "user"),
+        plural(sessions.len(), "session"),
+        shown
+    ))
+}
+
+/// Tells where a login came from, e.g. "from 192.168.0.12" or "on tty1"
+fn describe_source(line: &str, host: &str) -> String {
+    if host.is_empty() {
+        format!("on {}", line)
+    } else {
+        format!("from {}", host)
+    }
+}
+
+/// The terminal of the login session we run in, e.g. "pts/3" for "/dev/pts/3", and when it
+/// started, as listed in utmp
+fn get_current_session() -> Option<(String, i64)> {
+    let tty = ["/proc/self/fd/0", "/proc/self/fd/2"].iter().find_map(|fd| {
+        let path = fs::read_link(fd).ok()?;
+        Some(path.to_str()?.strip_prefix("/dev/")?.to_string())
+    })?;
+
+    read_entries(UTMP)
+        .into_iter()
+        .find(|entry| entry.kind == USER_PROCESS && entry.line == tty)
+        .map(|entry| (entry.line, entry.time))
+}
+
+/// Whether a login, as lastlog recorded it, is the session we run in, as lastlog only keeps
+/// the latest login, which it's already overwritten with ours
+fn is_current_session(current: &Option<(String, i64)>, line: &str, time: i64) -> bool {
+    match current {
+        Some((current_line, start)) => line == current_line && time >= *start,
+        None => false,
+    }
+}
+
+fn lastlog2_last_login(username: &str) -> Option<(i64, String, String)> {
+    let database = Database::open(LASTLOG2.as_ref())?;
+    let mut last_login = None;
+
+    database.visit_table("Lastlog2", &mut |row| {
+        let text = |value: Option<&Value>| match value {
+            Some(Value::Bytes(text)) => String::from_utf8_lossy(text).into_owned(),
+            _ => String::new(),
+        };
+
+        if let (Some(Value::Integer(time)), true) = (row.get(1), text(row.first()) == username) {
+            last_login = Some((*time, text(row.get(2)), text(row.get(3))));
+        }
+    });
+
+    last_login
+}
+
+/// Reads the record of the user, which is at an offset given by their ID. The file is
+/// sparse, and so as large as the highest ID makes it, e.g. hundreds of gigabytes with
+/// the IDs given out by LDAP, so only the record itself is read.
+fn lastlog_last_login() -> Option<(i64, String, String)> {
+    let lastlog = File::open(LASTLOG).ok()?;
+    let offset = u64::from(unsafe { libc::getuid() }) * LASTLOG_RECORD_SIZE as u64;
+    let mut record = [0_u8; LASTLOG_RECORD_SIZE];
+    lastlog.read_exact_at(&mut record, offset).ok()?;
+
+    match u32::from_ne_bytes(record[..4].try_into().ok()?) {
+        // Users who never logged in have an empty record
+        0 => None,
+        time => Some((i64::from(time), read_field(&record[4..36]), read_field(&record[36..]))),
+    }
+}
+
+/// The latest login of the user before the session we run in, which wtmp lists
+/// along with every other one
+fn wtmp_last_login(username: &str, current: &Option<(String, i64)>) -> Option<(i64, String)> {
+    read_entries(WTMP)
+        .into_iter()
+        .filter(|entry| entry.kind == USER_PROCESS && entry.user == username)
+        .filter(|entry| current.as_ref() != Some(&(entry.line.clone(), entry.time)))
+        .max_by_key(|entry| entry.time)
+        .map(|entry| (entry.time, describe_source(&entry.line, &entry.host)))
+}
+
+/// When the user logged in before the current session, as a Unix timestamp, and where
+/// from, as login shells tell it
+pub fn get_last_login(username: &str) -> Option<(i64, String)> {
+    let current = get_current_session();
+    if let Some(last_login) = wtmp_last_login(username, &current) {
+        return Some(last_login);
+    }
+
+    // Otherwise, the only login lastlog keeps is left out if it's the one of this session
+    let (time, line, host) = lastlog2_last_login(username).or_else(lastlog_last_login)?;
+    if is_current_session(&current, &line, time) {
+        return None;
+    }
+    Some((time, describe_source(&line, &host)))
+}
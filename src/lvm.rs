@@ -0,0 +1,104 @@
+use crate::{disk::get_slaves, util::read_trimmed};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug)]
+pub struct LogicalVolume {
+    // Name of the volume group, e.g. "vg0"
+    pub vg:      String,
+    // Name of the logical volume, e.g. "root"
+    pub lv:      String,
+    // In bytes
+    pub size:    u64,
+    // Size of the physical volumes backing the volume group, in bytes
+    pub vg_size: u64,
+}
+
+/// Sizes in /sys/block are given in 512-byte sectors
+fn read_size(block_dir: &Path) -> Option<u64> {
+    read_trimmed(&block_dir.join("size"))?
+        .parse::<u64>()
+        .ok()
+        .map(|sectors| sectors * 512)
+}
+
+/// Device-mapper names join the VG and LV names with a hyphen, and double
+/// the hyphens inside of them, e.g. "my--vg-root" for "my-vg" and "root"
+fn split_dm_name(dm_name: &str) -> Option<(String, String)> {
+    let bytes = dm_name.as_bytes();
+    let separator = (0..bytes.len()).find(|&i| {
+        bytes[i] == b'-'
+            && bytes.get(i + 1) != Some(&b'-')
+            && (i == 0 || bytes[i - 1] != b'-')
+    })?;
+
+    let vg = dm_name[..separator].replace("--", "-");
+    let lv = dm_name[separator + 1..].replace("--", "-");
+    Some((vg, lv))
+}
+
+fn is_logical_volume(block_dir: &Path) -> bool {
+    read_trimmed(&block_dir.join("dm").join("uuid"))
+        .is_some_and(|uuid| uuid.starts_with("LVM-"))
+}
+
+/// Adds up the physical volumes backing every logical volume of the given VG
+fn get_vg_size(vg: &str) -> u64 {
+    let entries = match fs::read_dir("/sys/block/") {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    let mut physical_volumes: Vec<PathBuf> = vec![];
+
+    for entry in entries.filter_map(Result::ok) {
+        let block_dir = entry.path();
+        if !is_logical_volume(&block_dir) {
+            continue;
+        }
+
+        let same_vg = read_trimmed(&block_dir.join("dm").join("name"))
+            .and_then(|dm_name| split_dm_name(&dm_name))
+            .is_some_and(|(other_vg, _)| other_vg == vg);
+
+        if same_vg {
+            for slave in get_slaves(&block_dir) {
+                if !physical_volumes.contains(&slave) {
+                    physical_volumes.push(slave);
+                }
+            }
+        }
+    }
+
+    physical_volumes
+        .iter()
+        .filter_map(|pv| read_size(pv))
+        .sum()
+}
+
+/// Finds the logical volume behind the given block device, looking through
+/// stacked device-mapper devices such as LUKS on top of LVM
+pub fn get_logical_volume(block_dir: &Path) -> Option<LogicalVolume> {
+    if !block_dir.join("dm").exists() {
+        return None;
+    }
+
+    if !is_logical_volume(block_dir) {
+        return get_slaves(block_dir)
+            .iter()
+            .find_map(|slave| get_logical_volume(slave));
+    }
+
+    let dm_name = read_trimmed(&block_dir.join("dm").join("name"))?;
+    let (vg, lv) = split_dm_name(&dm_name)?;
+
+    Some(LogicalVolume {
+        size: read_size(block_dir)?,
+        vg_size: get_vg_size(&vg),
+        vg,
+        lv,
+    })
+}
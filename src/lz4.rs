@@ -0,0 +1,108 @@
+use crate::util::read_le_u32;
+
+/// Start of every LZ4 frame
+const MAGIC: u32 = 0x184D_2204;
+/// Matches are at least this long, so their lengths are stored minus it
+const MIN_MATCH: usize = 4;
+
+// Flags of the frame descriptor
+const BLOCK_CHECKSUM: u8 = 1 << 4;
+const CONTENT_SIZE: u8 = 1 << 3;
+const DICTIONARY_ID: u8 = 1 << 0;
+/// Blocks whose size has this bit set are stored uncompressed
+const UNCOMPRESSED: u32 = 1 << 31;
+
+/// Lengths of 15 and over go on in the next bytes, until one which isn't 255
+fn read_length(block: &[u8], pos: &mut usize) -> Option<usize> {
+    let mut len = 0;
+    loop {
+        let byte = *block.get(*pos)?;
+        *pos += 1;
+        len += byte as usize;
+        if byte != 255 {
+            return Some(len);
+        }
+    }
+}
+
+/// Decompresses a block, made of sequences of literals followed by a match
+/// with the previous output, which may come from previous blocks
+fn decompress_block(block: &[u8], output: &mut Vec<u8>) -> Option<()> {
+    let mut pos = 0;
+
+    loop {
+        let token = *block.get(pos)?;
+        pos += 1;
+
+        let mut literals = (token >> 4) as usize;
+        if literals == 15 {
+            literals += read_length(block, &mut pos)?;
+        }
+        output.extend_from_slice(block.get(pos..pos + literals)?);
+        pos += literals;
+
+        // The last sequence holds only literals
+        if pos == block.len() {
+            return Some(());
+        }
+
+        let distance = u16::from_le_bytes([*block.get(pos)?, *block.get(pos + 1)?]) as usize;
+        pos += 2;
+        let mut len = (token & 0x0F) as usize;
+        if len == 15 {
+            len += read_length(block, &mut pos)?;
+        }
+
+        // The match may overlap the bytes it produces
+        let start = output.len().checked_sub(distance).filter(|_| distance > 0)?;
+        for offset in 0..len + MIN_MATCH {
+            output.push(output[start + offset]);
+        }
+    }
+}
+
+/// Decompresses an LZ4 frame, as written by the `lz4` tool and by apt
+pub fn decompress(data: &[u8]) -> Option<Vec<u8>> {
+    if read_le_u32(data, 0)? != MAGIC {
+        return None;
+    }
+
+    // Only version 1 of the format exists
+    let flags = *data.get(4)?;
+    if flags >> 6 != 1 {
+        return None;
+    }
+
+    // The descriptor holds the flags, the block size, the optional content size
+    // and dictionary ID, and a checksum
+    let mut offset = 6;
+    if flags & CONTENT_SIZE != 0 {
+        offset += 8;
+    }
+    if flags & DICTIONARY_ID != 0 {
+        offset += 4;
+    }
+    offset += 1;
+
+    let mut output = vec![];
+    loop {
+        let size = read_le_u32(data, offset)?;
+        offset += 4;
+        if size == 0 {
+            return Some(output);
+        }
+
+        let len = (size & !UNCOMPRESSED) as usize;
+        let block = data.get(offset..offset + len)?;
+        if size & UNCOMPRESSED != 0 {
+            output.extend_from_slice(block);
+        } else {
+            decompress_block(block, &mut output)?;
+        }
+
+        offset += len;
+        if flags & BLOCK_CHECKSUM != 0 {
+            offset += 4;
+        }
+    }
+}
@@ -1,21 +1,99 @@
 mod arts;
+mod audio;
+mod backlight;
+mod battery;
+mod btrfs;
+mod camera;
+mod cmdline;
+mod compositor;
+mod config;
+mod container;
+mod cpu;
+mod cursor;
+mod dbus;
+mod dconf;
+mod desktop;
+mod disk;
 mod distros;
+mod dmi;
+mod drm;
+mod edid;
+mod efi;
+mod git;
+mod gpu;
+mod groups;
+mod gtk;
+mod history;
+mod icons;
+mod inflate;
+mod init;
+mod keyboard;
+#[cfg(feature = "use_xlib")]
+mod keyboardx11;
+mod logins;
+mod lvm;
+mod lz4;
+mod mdraid;
+mod meminfo;
+mod modules;
+mod netlink;
+mod network;
+mod nixos;
+mod packages;
+mod pacman;
+mod power;
+mod pressure;
 mod pulga;
+mod qt;
+mod scale;
 mod screenres;
+mod screenreswayland;
 #[cfg(feature = "use_xlib")]
 mod screenresx11;
+mod sensors;
+mod session;
+mod sha1;
+mod shell;
+mod sqlite;
+mod state;
+mod swap;
 mod sysinfo;
+mod systemd;
+mod taint;
+mod termcolors;
+mod termfont;
+mod terminal;
+mod toolchains;
+mod tpm;
 mod uname;
+mod updates;
+mod usb;
 mod util;
+mod vercmp;
+mod virt;
+mod vpn;
+mod wifi;
+mod wm;
+#[cfg(feature = "use_xlib")]
+mod wmx11;
+mod wsl;
+mod xserver;
+#[cfg(feature = "use_xlib")]
+mod xserverx11;
+mod zfs;
 
-use crate::{pulga::UserData, util::get_rand};
+use crate::{
+    config::{Config, Theme},
+    pulga::UserData,
+    util::get_rand,
+};
 use std::io::{self, BufWriter, Write};
 
 use smallvec::SmallVec;
 use sugars::boxed;
-use termion::{color::*, cursor::*};
+use termion::{color::*, cursor::*, style};
 
-use std::{cmp, collections::HashMap, env};
+use std::{cmp, collections::HashMap};
 
 fn display_information_and_logo(text: String, art: &str) -> io::Result<()> {
     let lines: SmallVec<[&str; 128]> = text.lines().map(|x| x.trim()).collect();
@@ -81,8 +159,14 @@ fn display_information_and_logo(text: String, art: &str) -> io::Result<()> {
     }
     writeln!(output)?;
 
-    let logo_lines = logo.iter().filter(|x| **x == '\n').count() as u16;
+    let mut logo_lines = logo.iter().filter(|x| **x == '\n').count() as u16;
     let info_lines = lines.len() as u16;
+
+    // Make room for the information that doesn't fit alongside the logo
+    while logo_lines < info_lines {
+        writeln!(output)?;
+        logo_lines += 1;
+    }
     let up_how_many_times = info_lines + (logo_lines - info_lines) / 2 + 1;
 
     // Code to insert information at the side of the logo
@@ -103,58 +187,407 @@ fn display_information_and_logo(text: String, art: &str) -> io::Result<()> {
     Ok(())
 }
 
+/// Colors of the information, picked to stay readable on the terminal's background
+struct Palette {
+    label:     String,
+    separator: String,
+    value:     String,
+    // Values which say that all is well, or that something's wrong
+    good:      String,
+    bad:       String,
+}
+
+impl Palette {
+    fn new(light_background: bool) -> Palette {
+        if light_background {
+            Palette {
+                label:     Fg(Blue).to_string(),
+                separator: Fg(LightBlack).to_string(),
+                value:     Fg(Red).to_string(),
+                good:      Fg(Green).to_string(),
+                bad:       format!("{}{}", Fg(Red), style::Bold),
+            }
+        } else {
+            Palette {
+                label:     Fg(LightCyan).to_string(),
+                separator: Fg(LightBlack).to_string(),
+                value:     Fg(LightRed).to_string(),
+                good:      Fg(LightGreen).to_string(),
+                bad:       format!("{}{}", Fg(Red), style::Bold),
+            }
+        }
+    }
+
+    /// Formats a line of information such as "cpu: Intel i5-7200U"
+    fn format_field(&self, name: &str, value: &str) -> String {
+        format!(
+            "{c}{}{w}: {r}{}{R}",
+            name,
+            value,
+            c = self.label,
+            w = self.separator,
+            R = Fg(Reset),
+            r = self.value,
+        )
+    }
+
+    /// Formats a line of information which is either good or bad news,
+    /// e.g. "failed units: 0" in green
+    fn format_status(&self, name: &str, value: &str, is_good: bool) -> String {
+        format!(
+            "{c}{}{w}: {r}{}{R}{N}",
+            name,
+            value,
+            c = self.label,
+            w = self.separator,
+            r = if is_good { &self.good } else { &self.bad },
+            R = Fg(Reset),
+            N = style::Reset,
+        )
+    }
+}
+
 fn main() -> io::Result<()> {
+    let config = Config::load();
+
     let UserData {
         username,
         hostname,
         cpu_info,
+        cpu_temp,
+        cpu_usage,
+        power_mode,
+        gpu_info,
+        graphics,
+        x_server,
+        ssh_client,
         uptime,
+        users,
+        last_login,
+        groups,
+        processes,
+        packages,
+        updates,
+        last_update,
+        install_date,
         hmd,
+        git,
         shell,
+        terminal,
+        terminal_font,
         editor,
+        toolchains,
         distro,
+        nixos,
         kernel_version,
+        init,
+        cmdline,
+        taint,
+        modules,
+        boot_time,
+        failed_units,
+        host,
+        firmware,
+        chassis,
+        hypervisor,
+        container,
+        wsl,
+        secure_boot,
+        tpm,
+        audio,
+        keyboard,
+        local_ip,
+        ipv6,
+        gateway,
+        vpn,
+        dns,
+        domain,
+        wifi,
+        link_speed,
+        mac,
+        net_io,
+        public_ip,
+        display,
         desk_env,
+        wm,
+        compositor,
+        gtk_theme,
+        qt_theme,
+        icon_theme,
+        cursor_theme,
         monitor_res,
         used_memory,
         total_memory,
-        cwd: _, // Unused
-    } = pulga::get_user_data();
+        memory_modules,
+        hugepages,
+        swap,
+        disk_usage,
+        nvme_health,
+        disk_io,
+        mounts,
+        zfs_pools,
+        raid_arrays,
+        battery,
+        brightness,
+        cpu_power,
+        sensors,
+        fans,
+        pressure,
+        usb_devices,
+        cameras,
+        term_colors,
+        light_bg,
+        cwd,
+    } = pulga::get_user_data(&config);
 
-    #[rustfmt::skip]
-    let text = format!(
-        "{c}{}{R}@{c}{}{R}\n\
-        \n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}/{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R}\n\
-         {c}{}{w}: {r}{}{R} / {r}{}{R}",
-        username, hostname,
-        "cpu", cpu_info,
-        "uptime", uptime,
-        "home", hmd,
-        "shell", shell,
-        "editor", editor,
-        "distro", distro,
-        "kernel", kernel_version,
-        "desktop env.", desk_env,
-        "monitor", monitor_res,
-        "memory usage", used_memory, total_memory,
-        c = Fg(LightCyan),
-        w = Fg(LightBlack),
-        R = Fg(Reset),
-        r = Fg(LightRed),
-    );
-
-    let random_distro = env::args().skip(1).any(|x| x == "--random" || x == "-r");
-
-    let distro = distros::choose_distro(random_distro);
+    let palette = Palette::new(match config.theme {
+        Theme::Auto => light_bg,
+        Theme::Dark => false,
+        Theme::Light => true,
+    });
+
+    let mut lines = vec![
+        format!(
+            "{c}{}{R}@{c}{}{R}",
+            username,
+            hostname,
+            c = palette.label,
+            R = Fg(Reset),
+        ),
+        String::new(),
+    ];
+
+    lines.push(palette.format_field("cpu", &cpu_info));
+    lines.push(palette.format_field("gpu", &gpu_info));
+    lines.push(palette.format_field("graphics", &graphics));
+    if let Some(x_server) = x_server {
+        lines.push(palette.format_field("x server", &x_server));
+    }
+    if let Some(ssh_client) = ssh_client {
+        lines.push(palette.format_field("ssh", &ssh_client));
+    }
+    lines.push(palette.format_field("uptime", &uptime));
+    if let Some(users) = users {
+        lines.push(palette.format_field("users", &users));
+    }
+    if let Some(last_login) = last_login {
+        lines.push(palette.format_field("last login", &last_login));
+    }
+    if let Some(groups) = groups {
+        lines.push(palette.format_field("groups", &groups));
+    }
+    if let Some(processes) = processes {
+        lines.push(palette.format_field("processes", &processes));
+    }
+    if let Some(packages) = packages {
+        lines.push(palette.format_field("packages", &packages));
+    }
+    if let Some(updates) = updates {
+        lines.push(palette.format_field("updates", &updates));
+    }
+    if let Some(last_update) = last_update {
+        lines.push(palette.format_field("last update", &last_update));
+    }
+    if let Some(install_date) = install_date {
+        lines.push(palette.format_field("installed", &install_date));
+    }
+    lines.push(palette.format_field("home", &format!("{}/", hmd)));
+    let cwd = match git {
+        Some(git) => format!("{} on {}", cwd, git),
+        None => cwd,
+    };
+    lines.push(palette.format_field("cwd", &cwd));
+    lines.push(palette.format_field("shell", &shell));
+    if let Some(terminal) = terminal {
+        lines.push(palette.format_field("terminal", &terminal));
+    }
+    if let Some(terminal_font) = terminal_font {
+        lines.push(palette.format_field("terminal font", &terminal_font));
+    }
+    if let Some(term_colors) = term_colors {
+        lines.push(palette.format_field("terminal colors", &term_colors));
+    }
+    lines.push(palette.format_field("editor", &editor));
+    for (toolchain, version) in toolchains {
+        lines.push(palette.format_field(&toolchain, &version));
+    }
+    lines.push(palette.format_field("distro", &distro));
+    if let Some(nixos) = nixos {
+        lines.push(palette.format_field("nixos", &nixos));
+    }
+    lines.push(palette.format_field("kernel", &kernel_version));
+    if let Some(init) = init {
+        lines.push(palette.format_field("init", &init));
+    }
+    if let Some(cmdline) = cmdline {
+        lines.push(palette.format_field("cmdline", &cmdline));
+    }
+    if let Some(taint) = taint {
+        lines.push(palette.format_field("tainted", &taint));
+    }
+    if let Some(modules) = modules {
+        lines.push(palette.format_field("modules", &modules));
+    }
+    if let Some(boot_time) = boot_time {
+        lines.push(palette.format_field("boot", &boot_time));
+    }
+    if let Some(failed_units) = failed_units {
+        let is_good = failed_units == 0;
+        lines.push(palette.format_status("failed units", &failed_units.to_string(), is_good));
+    }
+    if let Some(host) = host {
+        lines.push(palette.format_field("host", &host));
+    }
+    if let Some(chassis) = chassis {
+        lines.push(palette.format_field("chassis", &chassis));
+    }
+    if let Some(hypervisor) = hypervisor {
+        lines.push(palette.format_field("hypervisor", &hypervisor));
+    }
+    if let Some(container) = container {
+        lines.push(palette.format_field("container", &container));
+    }
+    if let Some(wsl) = wsl {
+        lines.push(palette.format_field("wsl", &wsl));
+    }
+    if let Some(firmware) = firmware {
+        lines.push(palette.format_field("firmware", &firmware));
+    }
+    lines.push(palette.format_field("secure boot", &secure_boot));
+    if let Some(tpm) = tpm {
+        lines.push(palette.format_field("tpm", &tpm));
+    }
+    lines.push(palette.format_field("display", &display));
+    lines.push(palette.format_field("desktop env.", &desk_env));
+    if let Some(wm) = &wm {
+        lines.push(palette.format_field("wm", wm));
+    }
+    // On Wayland, the compositor is the window manager itself
+    if let Some(compositor) = compositor.filter(|compositor| wm.as_ref() != Some(compositor)) {
+        lines.push(palette.format_field("compositor", &compositor));
+    }
+    if let Some(gtk_theme) = gtk_theme {
+        lines.push(palette.format_field("theme", &gtk_theme));
+    }
+    if let Some(qt_theme) = qt_theme {
+        lines.push(palette.format_field("qt theme", &qt_theme));
+    }
+    if let Some(icon_theme) = icon_theme {
+        lines.push(palette.format_field("icons", &icon_theme));
+    }
+    if let Some(cursor_theme) = cursor_theme {
+        lines.push(palette.format_field("cursor", &cursor_theme));
+    }
+    if let Some(monitor_res) = monitor_res {
+        lines.push(palette.format_field("monitor", &monitor_res));
+    }
+    if let Some(audio) = audio {
+        lines.push(palette.format_field("audio", &audio));
+    }
+    if let Some(keyboard) = keyboard {
+        lines.push(palette.format_field("keyboard", &keyboard));
+    }
+    if let Some(local_ip) = local_ip {
+        lines.push(palette.format_field("local ip", &local_ip));
+    }
+    if let Some(ipv6) = ipv6 {
+        lines.push(palette.format_field("ipv6", &ipv6));
+    }
+    if let Some(gateway) = gateway {
+        lines.push(palette.format_field("gateway", &gateway));
+    }
+    if let Some(vpn) = vpn {
+        lines.push(palette.format_field("vpn", &vpn));
+    }
+    if let Some(dns) = dns {
+        lines.push(palette.format_field("dns", &dns));
+    }
+    if let Some(domain) = domain {
+        lines.push(palette.format_field("domain", &domain));
+    }
+    if let Some(wifi) = wifi {
+        lines.push(palette.format_field("wifi", &wifi));
+    }
+    if let Some(link_speed) = link_speed {
+        lines.push(palette.format_field("link", &link_speed));
+    }
+    if let Some(mac) = mac {
+        lines.push(palette.format_field("mac", &mac));
+    }
+    if let Some(net_io) = net_io {
+        lines.push(palette.format_field("net i/o", &net_io));
+    }
+    if let Some(public_ip) = public_ip {
+        lines.push(palette.format_field("public ip", &public_ip));
+    }
+    lines.push(palette.format_field(
+        "memory usage",
+        &format!("{}{} / {}{}", used_memory, Fg(Reset), palette.value, total_memory),
+    ));
+    if let Some(memory_modules) = memory_modules {
+        lines.push(palette.format_field("ram", &memory_modules));
+    }
+    if let Some(hugepages) = hugepages {
+        lines.push(palette.format_field("hugepages", &hugepages));
+    }
+    if let Some(swap) = swap {
+        lines.push(palette.format_field("swap", &swap));
+    }
+    lines.push(palette.format_field("disk usage", &disk_usage));
+
+    // Fields below are only shown when available
+    if let Some(battery) = battery {
+        lines.push(palette.format_field("battery", &battery));
+    }
+    if let Some(brightness) = brightness {
+        lines.push(palette.format_field("brightness", &brightness));
+    }
+    if let Some(nvme_health) = nvme_health {
+        lines.push(palette.format_field("nvme health", &nvme_health));
+    }
+    for (mount_point, usage) in mounts {
+        lines.push(palette.format_field(&format!("disk ({})", mount_point), &usage));
+    }
+    if let Some(zfs_pools) = zfs_pools {
+        lines.push(palette.format_field("zfs pools", &zfs_pools));
+    }
+    if let Some(raid_arrays) = raid_arrays {
+        lines.push(palette.format_field("raid", &raid_arrays));
+    }
+    if let Some(disk_io) = disk_io {
+        lines.push(palette.format_field("disk i/o", &disk_io));
+    }
+    if let Some(cpu_usage) = cpu_usage {
+        lines.push(palette.format_field("cpu usage", &cpu_usage));
+    }
+    if let Some(power_mode) = power_mode {
+        lines.push(palette.format_field("power mode", &power_mode));
+    }
+    if let Some(cpu_temp) = cpu_temp {
+        lines.push(palette.format_field("cpu temp.", &cpu_temp));
+    }
+    if let Some(cpu_power) = cpu_power {
+        lines.push(palette.format_field("cpu power", &cpu_power));
+    }
+    if let Some(fans) = fans {
+        lines.push(palette.format_field("fans", &fans));
+    }
+    for (sensor, reading) in sensors {
+        lines.push(palette.format_field(&sensor, &reading));
+    }
+    for (resource, averages) in pressure {
+        lines.push(palette.format_field(&resource, &averages));
+    }
+    for device in usb_devices {
+        lines.push(palette.format_field("usb", &device));
+    }
+    if let Some(cameras) = cameras {
+        lines.push(palette.format_field("camera", &cameras));
+    }
+
+    let text = lines.join("\n");
+
+    let distro = distros::choose_distro(config.random_distro);
 
     display_information_and_logo(text, distro)
 }
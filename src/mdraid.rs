@@ -0,0 +1,96 @@
+use std::fs;
+
+/// A Linux software RAID array, as described in /proc/mdstat
+#[derive(Debug)]
+pub struct MdArray {
+    // e.g. "md0"
+    pub name:     String,
+    // e.g. "raid1"
+    pub level:    Option<String>,
+    pub active:   bool,
+    // Whether some member of the array is missing or failed
+    pub degraded: bool,
+    // Health of each member, e.g. "UU_" (the third one is missing)
+    pub members:  Option<String>,
+    // Ongoing recovery, resync, reshape or check, e.g. "recovery 12.6%"
+    pub progress: Option<String>,
+}
+
+/// Parses the status line of an array, e.g. "976630464 blocks super 1.2 [2/1] [U_]"
+fn parse_status(line: &str, array: &mut MdArray) {
+    let mut brackets = line
+        .split('[')
+        .skip(1)
+        .filter_map(|part| part.split(']').next());
+
+    // e.g. "[2/1]": the array should have 2 devices, but only 1 is working
+    let counts = brackets.find_map(|part| {
+        let (total, working) = part.split_once('/')?;
+        Some((total.parse::<u32>().ok()?, working.parse::<u32>().ok()?))
+    });
+
+    if let Some((total, working)) = counts {
+        array.degraded |= working < total;
+        array.members = brackets.next().map(ToString::to_string);
+    }
+}
+
+/// Parses a progress line, e.g. "[==>..................]  recovery = 12.6% (123/976630464)"
+fn parse_progress(line: &str) -> Option<String> {
+    let (action, rest) = line.split_once(" = ")?;
+    let action = action.split_whitespace().last()?;
+    let percent = rest.split_whitespace().next()?;
+
+    Some(format!("{} {}", action, percent))
+}
+
+pub fn get_arrays() -> Vec<MdArray> {
+    let mdstat = match fs::read_to_string("/proc/mdstat") {
+        Ok(mdstat) => mdstat,
+        Err(_) => return vec![],
+    };
+
+    let mut arrays: Vec<MdArray> = vec![];
+
+    for line in mdstat.lines() {
+        // e.g. "md0 : active raid1 sdb1[1] sda1[0]"
+        if let Some((name, description)) = line.split_once(" : ") {
+            if !name.starts_with("md") {
+                continue;
+            }
+
+            let mut words = description
+                .split_whitespace()
+                // Skip flags such as "(auto-read-only)"
+                .filter(|word| !word.starts_with('('));
+
+            let active = words.next() == Some("active");
+            let level = words.next().filter(|level| !level.contains('['));
+            // Failed members are marked with "(F)"
+            let has_failed_member = description.contains("(F)");
+
+            arrays.push(MdArray {
+                name: name.trim().to_string(),
+                level: level.map(ToString::to_string),
+                active,
+                degraded: has_failed_member,
+                members: None,
+                progress: None,
+            });
+            continue;
+        }
+
+        let array = match arrays.last_mut() {
+            Some(array) if line.starts_with(char::is_whitespace) => array,
+            _ => continue,
+        };
+
+        if line.contains(" blocks") {
+            parse_status(line, array);
+        } else if let Some(progress) = parse_progress(line) {
+            array.progress = Some(progress);
+        }
+    }
+
+    arrays
+}
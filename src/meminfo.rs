@@ -0,0 +1,61 @@
+use std::fs;
+
+/// Memory statistics from /proc/meminfo, in bytes
+#[derive(Debug, Default)]
+pub struct MemInfo {
+    pub mem_total:      u64,
+    // Estimate of the memory available for new programs without swapping,
+    // which counts reclaimable caches as free (kernel 3.14+)
+    pub mem_available:  Option<u64>,
+    pub swap_total:     u64,
+    // Swap space left unused
+    pub swap_free:      u64,
+    // Memory used by zswap (kernel 5.19+)
+    pub zswap:          Option<u64>,
+    // Size of the pages stored in zswap, before compression (kernel 5.19+)
+    pub zswapped:       Option<u64>,
+    // Number of persistent huge pages reserved, and how many of them are in use
+    pub hugepages:      u64,
+    pub free_hugepages: u64,
+    // Size of the default huge page
+    pub hugepage_size:  u64,
+    // Memory backed by transparent huge pages
+    pub anon_hugepages: u64,
+}
+
+impl MemInfo {
+    pub fn gather() -> Option<MemInfo> {
+        let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+        let mut mem_info = MemInfo::default();
+
+        // Lines look like "SwapTotal:       8388604 kB"
+        for line in meminfo.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            let kibibytes: u64 = match value.split_whitespace().next().and_then(|v| v.parse().ok()) {
+                Some(kibibytes) => kibibytes,
+                None => continue,
+            };
+
+            let bytes = kibibytes * 1024;
+            match key {
+                "MemTotal" => mem_info.mem_total = bytes,
+                "MemAvailable" => mem_info.mem_available = Some(bytes),
+                "SwapTotal" => mem_info.swap_total = bytes,
+                "SwapFree" => mem_info.swap_free = bytes,
+                "Zswap" => mem_info.zswap = Some(bytes),
+                "Zswapped" => mem_info.zswapped = Some(bytes),
+                // The huge page counts don't have a unit
+                "HugePages_Total" => mem_info.hugepages = kibibytes,
+                "HugePages_Free" => mem_info.free_hugepages = kibibytes,
+                "Hugepagesize" => mem_info.hugepage_size = bytes,
+                "AnonHugePages" => mem_info.anon_hugepages = bytes,
+                _ => {},
+            }
+        }
+
+        Some(mem_info)
+    }
+}
@@ -0,0 +1,35 @@
+use std::fs;
+
+/// Modules worth pointing out, as they're out-of-tree or change what the system can do
+const NOTABLE_MODULES: &[(&str, &str)] = &[
+    ("nvidia", "nvidia"),
+    ("zfs", "zfs"),
+    ("v4l2loopback", "v4l2loopback"),
+    ("vboxdrv", "VirtualBox"),
+    ("vmmon", "VMware"),
+    ("binder_linux", "binder"),
+    ("wireguard", "WireGuard"),
+    ("openrazer_driver", "OpenRazer"),
+];
+
+/// Counts the loaded kernel modules, pointing out the notable ones, e.g. "142 (nvidia, zfs)"
+pub fn get_modules() -> Option<String> {
+    let modules = fs::read_to_string("/proc/modules").ok()?;
+    // Each line starts with the name of a module, e.g. "nvidia 56823808 ..."
+    let names: Vec<&str> = modules
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+
+    let notable: Vec<&str> = NOTABLE_MODULES
+        .iter()
+        .filter(|(module, _)| names.contains(module))
+        .map(|(_, name)| *name)
+        .collect();
+
+    if notable.is_empty() {
+        Some(names.len().to_string())
+    } else {
+        Some(format!("{} ({})", names.len(), notable.join(", ")))
+    }
+}
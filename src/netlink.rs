@@ -0,0 +1,155 @@
+use libc::{
+    c_int, c_void, close, recv, send, setsockopt, socket, socklen_t, suseconds_t, timeval,
+    AF_NETLINK, CTRL_ATTR_FAMILY_ID, CTRL_ATTR_FAMILY_NAME, CTRL_CMD_GETFAMILY, GENL_ID_CTRL,
+    NETLINK_GENERIC, NLA_TYPE_MASK, NLMSG_DONE, NLMSG_ERROR, NLM_F_DUMP, NLM_F_REQUEST,
+    SOCK_CLOEXEC, SOCK_RAW, SOL_SOCKET, SO_RCVTIMEO,
+};
+
+use std::{convert::TryInto, ffi::CString, mem};
+
+/// Length of the headers of a netlink message and of a generic netlink one
+const NLMSG_HDRLEN: usize = 16;
+const GENL_HDRLEN: usize = 4;
+/// How long to wait for the kernel, which replies right away unless something is wrong
+const TIMEOUT_US: suseconds_t = 500_000;
+
+/// Rounds a length up to a multiple of 4, as netlink messages and attributes are aligned to it
+fn align(len: usize) -> usize {
+    len.div_ceil(4) * 4
+}
+
+/// Splits a stream of attributes, each made of its length, its type and its payload,
+/// into (type, payload) pairs
+pub fn parse_attributes(mut data: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attributes = vec![];
+    while data.len() >= 4 {
+        let len = u16::from_ne_bytes([data[0], data[1]]) as usize;
+        let kind = u16::from_ne_bytes([data[2], data[3]]) & NLA_TYPE_MASK as u16;
+        if len < 4 || len > data.len() {
+            break;
+        }
+        attributes.push((kind, &data[4..len]));
+        data = &data[align(len).min(data.len())..];
+    }
+
+    attributes
+}
+
+/// Finds an attribute by its type in a stream of them
+pub fn find_attribute(data: &[u8], kind: u16) -> Option<&[u8]> {
+    parse_attributes(data)
+        .into_iter()
+        .find(|(attribute_kind, _)| *attribute_kind == kind)
+        .map(|(_, payload)| payload)
+}
+
+/// A generic netlink socket, through which families such as nl80211 are reached
+pub struct Socket {
+    fd:  c_int,
+    seq: u32,
+}
+
+impl Socket {
+    pub fn open() -> Option<Socket> {
+        let fd = unsafe { socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, NETLINK_GENERIC) };
+        if fd < 0 {
+            return None;
+        }
+        let socket = Socket { fd, seq: 0 };
+
+        let timeout = timeval { tv_sec: 0, tv_usec: TIMEOUT_US };
+        let timeout_ptr = &timeout as *const timeval as *const c_void;
+        let timeout_len = mem::size_of::<timeval>() as socklen_t;
+        if unsafe { setsockopt(fd, SOL_SOCKET, SO_RCVTIMEO, timeout_ptr, timeout_len) } != 0 {
+            return None;
+        }
+
+        Some(socket)
+    }
+
+    /// Sends a command to a family, and returns the attributes of each message in the reply,
+    /// of which there's one unless `dump`ing
+    pub fn request(
+        &mut self, family: u16, command: u8, dump: bool, attributes: &[(u16, &[u8])],
+    ) -> Option<Vec<Vec<u8>>> {
+        self.seq += 1;
+        let flags = if dump { NLM_F_REQUEST | NLM_F_DUMP } else { NLM_F_REQUEST };
+
+        let mut message = vec![0; NLMSG_HDRLEN];
+        message[4..6].copy_from_slice(&family.to_ne_bytes());
+        message[6..8].copy_from_slice(&(flags as u16).to_ne_bytes());
+        message[8..12].copy_from_slice(&self.seq.to_ne_bytes());
+        // The version of the family's interface, which the kernel doesn't check
+        message.extend_from_slice(&[command, 1, 0, 0]);
+        for (kind, payload) in attributes {
+            message.extend_from_slice(&((4 + payload.len()) as u16).to_ne_bytes());
+            message.extend_from_slice(&kind.to_ne_bytes());
+            message.extend_from_slice(payload);
+            message.resize(align(message.len()), 0);
+        }
+        let len = message.len() as u32;
+        message[0..4].copy_from_slice(&len.to_ne_bytes());
+
+        let sent = unsafe { send(self.fd, message.as_ptr() as *const c_void, message.len(), 0) };
+        if sent < 0 {
+            return None;
+        }
+
+        let mut replies = vec![];
+        let mut buf = vec![0_u8; 32 * 1024];
+        loop {
+            let received = unsafe { recv(self.fd, buf.as_mut_ptr() as *mut c_void, buf.len(), 0) };
+            if received <= 0 {
+                return None;
+            }
+
+            // A datagram may hold several messages, e.g. the stations of a dump
+            let mut data = &buf[..received as usize];
+            while data.len() >= NLMSG_HDRLEN {
+                let len = u32::from_ne_bytes(data[0..4].try_into().ok()?) as usize;
+                let kind = u16::from_ne_bytes([data[4], data[5]]) as c_int;
+                let seq = u32::from_ne_bytes(data[8..12].try_into().ok()?);
+                if len < NLMSG_HDRLEN || len > data.len() {
+                    return None;
+                }
+                let payload = &data[NLMSG_HDRLEN..len];
+                data = &data[align(len).min(data.len())..];
+
+                if seq != self.seq {
+                    continue;
+                }
+                match kind {
+                    NLMSG_DONE => return Some(replies),
+                    // Errors are negated errno values, and 0 acknowledges the request
+                    NLMSG_ERROR => {
+                        let error = i32::from_ne_bytes(payload.get(0..4)?.try_into().ok()?);
+                        return if error == 0 { Some(replies) } else { None };
+                    },
+                    _ => {
+                        replies.push(payload.get(GENL_HDRLEN..)?.to_vec());
+                        if !dump {
+                            return Some(replies);
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    /// The ID the kernel gave to a generic netlink family, such as "nl80211"
+    pub fn resolve_family(&mut self, name: &str) -> Option<u16> {
+        let name = CString::new(name).ok()?;
+        let attributes = [(CTRL_ATTR_FAMILY_NAME as u16, name.as_bytes_with_nul())];
+        let command = CTRL_CMD_GETFAMILY as u8;
+        let replies = self.request(GENL_ID_CTRL as u16, command, false, &attributes)?;
+
+        let id = find_attribute(replies.first()?, CTRL_ATTR_FAMILY_ID as u16)?;
+        Some(u16::from_ne_bytes(id.try_into().ok()?))
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        unsafe { close(self.fd) };
+    }
+}
@@ -0,0 +1,484 @@
+use crate::util::char_ptr_to_string;
+
+use libc::{
+    addrinfo, freeaddrinfo, freeifaddrs, getaddrinfo, getifaddrs, ifaddrs, sockaddr_in,
+    AF_INET, AI_CANONNAME, IFF_LOOPBACK, IFF_RUNNING, IFF_UP, SOCK_DGRAM,
+};
+
+use std::{
+    ffi::{CStr, CString},
+    fs, mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+    process::{Command, Output, Stdio},
+    ptr, thread,
+    time::{Duration, Instant},
+};
+
+// Seconds to wait for the public address, including the DNS lookup and TLS handshake
+const PUBLIC_IP_TIMEOUT: u64 = 2;
+
+// Addresses systemd-resolved listens on, which forwards the queries to the actual servers
+const RESOLVED_STUBS: &[&str] = &["127.0.0.53", "127.0.0.54"];
+
+// Flags of the routes, as in <linux/route.h>
+const RTF_UP: u32 = 0x0001;
+const RTF_GATEWAY: u32 = 0x0002;
+
+// Scope and flags of the IPv6 addresses, as in /proc/net/if_inet6
+const SCOPE_GLOBAL: u32 = 0x00;
+const IFA_F_TEMPORARY: u32 = 0x01;
+const IFA_F_DEPRECATED: u32 = 0x20;
+const IFA_F_TENTATIVE: u32 = 0x40;
+
+/// A network interface, with the addresses assigned to it
+pub struct Interface {
+    pub name: String,
+    pub ipv4: Vec<Ipv4Addr>,
+}
+
+/// Lists the interfaces which are up and have a link, other than loopback,
+/// in the order the kernel gives them
+pub fn get_interfaces() -> Vec<Interface> {
+    let mut addresses: *mut ifaddrs = ptr::null_mut();
+    if unsafe { getifaddrs(&mut addresses) } != 0 {
+        return vec![];
+    }
+
+    // getifaddrs gives one entry per address, and one more for the link itself
+    let mut interfaces: Vec<Interface> = vec![];
+    let mut current = addresses;
+    while !current.is_null() {
+        let entry = unsafe { &*current };
+        current = entry.ifa_next;
+
+        let flags = entry.ifa_flags as i32;
+        if flags & IFF_UP == 0 || flags & IFF_RUNNING == 0 || flags & IFF_LOOPBACK != 0 {
+            continue;
+        }
+
+        let name = unsafe { CStr::from_ptr(entry.ifa_name) }.to_string_lossy().into_owned();
+        let index = match interfaces.iter().position(|interface| interface.name == name) {
+            Some(index) => index,
+            None => {
+                interfaces.push(Interface { name, ipv4: vec![] });
+                interfaces.len() - 1
+            },
+        };
+        let interface = &mut interfaces[index];
+
+        if entry.ifa_addr.is_null() {
+            continue;
+        }
+        if i32::from(unsafe { (*entry.ifa_addr).sa_family }) == AF_INET {
+            let address = unsafe { &*(entry.ifa_addr as *const sockaddr_in) };
+            interface.ipv4.push(Ipv4Addr::from(u32::from_be(address.sin_addr.s_addr)));
+        }
+    }
+
+    unsafe { freeifaddrs(addresses) };
+    interfaces
+}
+
+/// Bytes received and sent through the active interfaces since they came up
+pub struct NetStats {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+/// Whether an interface is a wireless card, which is linked to its radio in sysfs
+pub fn is_wireless(name: &str) -> bool {
+    let sysfs = Path::new("/sys/class/net").join(name);
+    sysfs.join("wireless").exists() || sysfs.join("phy80211").exists()
+}
+
+/// The first IPv4 address of each active interface, e.g. "192.168.0.12 (wlan0)"
+pub fn get_local_ip() -> Option<String> {
+    let addresses: Vec<String> = get_interfaces()
+        .iter()
+        .filter_map(|interface| {
+            let address = interface.ipv4.first()?;
+            Some(format!("{} ({})", address, interface.name))
+        })
+        .collect();
+
+    if addresses.is_empty() {
+        None
+    } else {
+        Some(addresses.join(", "))
+    }
+}
+
+/// A global IPv6 address, as listed in /proc/net/if_inet6
+struct Ipv6Entry {
+    interface:  String,
+    address:    Ipv6Addr,
+    prefix_len: u32,
+    temporary:  bool,
+}
+
+/// Parses a line of /proc/net/if_inet6, made of the address in hex, the interface index,
+/// the length of the prefix, the scope, the flags and the interface name, e.g.
+/// "20010db8000100020000000000000034 02 40 00 80 eth0", unless it's not global or usable
+fn parse_ipv6_entry(line: &str) -> Option<Ipv6Entry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (address, prefix_len, scope, flags, interface) = match fields.as_slice() {
+        [address, _, prefix_len, scope, flags, interface] => {
+            (address, prefix_len, scope, flags, interface)
+        },
+        _ => return None,
+    };
+    let parse_hex = |hex: &str| u32::from_str_radix(hex, 16).ok();
+
+    let flags = parse_hex(flags)?;
+    if parse_hex(scope)? != SCOPE_GLOBAL || flags & (IFA_F_DEPRECATED | IFA_F_TENTATIVE) != 0 {
+        return None;
+    }
+
+    Some(Ipv6Entry {
+        interface:  interface.to_string(),
+        address:    Ipv6Addr::from(u128::from_str_radix(address, 16).ok()?),
+        prefix_len: parse_hex(prefix_len)?,
+        temporary:  flags & IFA_F_TEMPORARY != 0,
+    })
+}
+
+/// The global IPv6 address of each interface, e.g. "2001:db8:1:2::34 (eth0)", or just
+/// its network if `prefix_only`. Stable addresses are preferred over the temporary ones,
+/// which privacy extensions rotate every day or so.
+pub fn get_ipv6(prefix_only: bool) -> Option<String> {
+    let if_inet6 = fs::read_to_string("/proc/net/if_inet6").ok()?;
+    let entries: Vec<Ipv6Entry> = if_inet6.lines().filter_map(parse_ipv6_entry).collect();
+
+    // One address per active interface, in the order the interfaces are listed
+    let addresses: Vec<String> = get_interfaces()
+        .iter()
+        .filter_map(|interface| {
+            let entry = entries
+                .iter()
+                .filter(|entry| entry.interface == interface.name)
+                .min_by_key(|entry| entry.temporary)?;
+
+            if prefix_only {
+                let mask = u128::MAX.checked_shl(128 - entry.prefix_len).unwrap_or(0);
+                let network = Ipv6Addr::from(u128::from(entry.address) & mask);
+                Some(format!("{}/{} ({})", network, entry.prefix_len, interface.name))
+            } else {
+                Some(format!("{} ({})", entry.address, interface.name))
+            }
+        })
+        .collect();
+
+    if addresses.is_empty() {
+        None
+    } else {
+        Some(addresses.join(", "))
+    }
+}
+
+/// Fetches `url` through wget, which is killed once PUBLIC_IP_TIMEOUT is over, since its
+/// own --timeout applies to the DNS lookup, the connection and each read separately
+fn fetch_with_wget(url: &str) -> Option<Output> {
+    // --max-redirect=0, as wget can't be told to only follow HTTPS redirects
+    let mut child = Command::new("wget")
+        .args(["--quiet", "--tries=1", "--max-redirect=0", "--output-document=-"])
+        .arg(format!("--timeout={}", PUBLIC_IP_TIMEOUT))
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    let deadline = Instant::now() + Duration::from_secs(PUBLIC_IP_TIMEOUT);
+    while child.try_wait().ok()?.is_none() {
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    child.wait_with_output().ok()
+}
+
+/// Asks an HTTPS endpoint such as "https://api.ipify.org" for the address it sees us
+/// connecting from, through curl or, if it's missing, wget
+pub fn get_public_ip(url: &str) -> Option<String> {
+    let max_time = PUBLIC_IP_TIMEOUT.to_string();
+    let curl = Command::new("curl")
+        .args(["--silent", "--fail", "--proto", "=https", "--max-time", &max_time, url])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+
+    let output = match curl {
+        Ok(output) => output,
+        Err(_) => fetch_with_wget(url)?,
+    };
+    if !output.status.success() {
+        return None;
+    }
+
+    // Anything other than an address, such as a captive portal's page, is left out
+    let reply = String::from_utf8_lossy(&output.stdout);
+    let address: IpAddr = reply.trim().parse().ok()?;
+    Some(address.to_string())
+}
+
+/// The MAC address of each active interface, e.g. "a4:83:e7:2f:10:9b (wlan0)", or just its
+/// first three bytes, which the IEEE assigns to the vendor, if `oui_only`
+pub fn get_mac(oui_only: bool) -> Option<String> {
+    let addresses: Vec<String> = get_interfaces()
+        .iter()
+        .filter_map(|interface| {
+            let path = Path::new("/sys/class/net").join(&interface.name).join("address");
+            let address = fs::read_to_string(path).ok()?;
+            let address = address.trim();
+
+            // Tunnels and the like have no address, or one made of zeroes
+            if address.chars().all(|ch| ch == '0' || ch == ':') {
+                return None;
+            }
+
+            if oui_only {
+                let masked: Vec<&str> = address
+                    .split(':')
+                    .enumerate()
+                    .map(|(i, byte)| if i < 3 { byte } else { "xx" })
+                    .collect();
+                Some(format!("{} ({})", masked.join(":"), interface.name))
+            } else {
+                Some(format!("{} ({})", address, interface.name))
+            }
+        })
+        .collect();
+
+    if addresses.is_empty() {
+        None
+    } else {
+        Some(addresses.join(", "))
+    }
+}
+
+/// Sums the statistics of the active interfaces, from /sys/class/net/*/statistics
+pub fn get_net_stats() -> Option<NetStats> {
+    let read_counter = |name: &str, counter: &str| -> Option<u64> {
+        let path = Path::new("/sys/class/net").join(name).join("statistics").join(counter);
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    };
+
+    let stats = get_interfaces().into_iter().filter_map(|interface| {
+        let rx_bytes = read_counter(&interface.name, "rx_bytes")?;
+        let tx_bytes = read_counter(&interface.name, "tx_bytes")?;
+        Some(NetStats { rx_bytes, tx_bytes })
+    });
+
+    stats.reduce(|total, stats| NetStats {
+        rx_bytes: total.rx_bytes + stats.rx_bytes,
+        tx_bytes: total.tx_bytes + stats.tx_bytes,
+    })
+}
+
+/// Formats a speed given in Mb/s, e.g. "2.5 Gb/s" for 2500
+fn format_speed(speed: u64) -> String {
+    if speed >= 1000 {
+        format!("{} Gb/s", speed as f64 / 1000.0)
+    } else {
+        format!("{} Mb/s", speed)
+    }
+}
+
+/// The speed and duplex each wired interface negotiated, e.g. "1 Gb/s full duplex (eth0)",
+/// leaving out the virtual ones, such as bridges and veths, whose speed is made up
+pub fn get_link_speed() -> Option<String> {
+    let links: Vec<String> = get_interfaces()
+        .iter()
+        .filter(|interface| !is_wireless(&interface.name))
+        .filter_map(|interface| {
+            let sysfs = Path::new("/sys/class/net").join(&interface.name);
+            if !sysfs.join("device").exists() {
+                return None;
+            }
+
+            // Which is -1 if the driver doesn't know it, e.g. for virtio
+            let speed: u64 = fs::read_to_string(sysfs.join("speed")).ok()?.trim().parse().ok()?;
+            if speed == 0 {
+                return None;
+            }
+
+            let duplex = fs::read_to_string(sysfs.join("duplex")).unwrap_or_default();
+            match duplex.trim() {
+                duplex @ ("full" | "half") => Some(format!(
+                    "{} {} duplex ({})",
+                    format_speed(speed),
+                    duplex,
+                    interface.name
+                )),
+                _ => Some(format!("{} ({})", format_speed(speed), interface.name)),
+            }
+        })
+        .collect();
+
+    if links.is_empty() {
+        None
+    } else {
+        Some(links.join(", "))
+    }
+}
+
+/// A route through a gateway, which is the default one if nothing more specific matches
+struct DefaultRoute {
+    gateway:   IpAddr,
+    interface: String,
+    metric:    u32,
+}
+
+/// Parses a line of a routing table, unless it's not a default route
+type ParseRoute = fn(&str) -> Option<DefaultRoute>;
+
+/// Whether the flags are those of a usable route through a gateway
+fn is_gateway_route(flags: u32) -> bool {
+    flags & (RTF_UP | RTF_GATEWAY) == RTF_UP | RTF_GATEWAY
+}
+
+/// Parses a line of /proc/net/route, made of the interface, the destination, the gateway,
+/// the flags, two counters, the metric and the mask, e.g.
+/// "eth0 00000000 0102A8C0 0003 0 0 100 00000000 0 0 0" but separated by tabs, with the
+/// addresses in the byte order of the CPU
+fn parse_ipv4_route(line: &str) -> Option<DefaultRoute> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let parse_hex = |i: usize| u32::from_str_radix(fields.get(i)?, 16).ok();
+
+    let (destination, gateway, flags) = (parse_hex(1)?, parse_hex(2)?, parse_hex(3)?);
+    if destination != 0 || parse_hex(7)? != 0 || !is_gateway_route(flags) {
+        return None;
+    }
+
+    Some(DefaultRoute {
+        gateway:   IpAddr::V4(Ipv4Addr::from(gateway.to_ne_bytes())),
+        interface: fields[0].to_string(),
+        metric:    fields.get(6)?.parse().ok()?,
+    })
+}
+
+/// Parses a line of /proc/net/ipv6_route, made of the destination and its prefix length,
+/// the source and its prefix length, the next hop, the metric, two counters, the flags
+/// and the interface, in hex
+fn parse_ipv6_route(line: &str) -> Option<DefaultRoute> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (destination, prefix_len, gateway, metric, flags, interface) = match fields.as_slice() {
+        [destination, prefix_len, _, _, gateway, metric, _, _, flags, interface] => {
+            (destination, prefix_len, gateway, metric, flags, interface)
+        },
+        _ => return None,
+    };
+    let parse_hex = |hex: &str| u32::from_str_radix(hex, 16).ok();
+
+    let destination = u128::from_str_radix(destination, 16).ok()?;
+    if destination != 0 || parse_hex(prefix_len)? != 0 || !is_gateway_route(parse_hex(flags)?) {
+        return None;
+    }
+
+    Some(DefaultRoute {
+        gateway:   IpAddr::V6(Ipv6Addr::from(u128::from_str_radix(gateway, 16).ok()?)),
+        interface: interface.to_string(),
+        metric:    parse_hex(metric)?,
+    })
+}
+
+/// The IPv4 and IPv6 default gateways, and the interface each is reached through,
+/// e.g. "192.168.0.1 (wlan0), fe80::1 (wlan0)"
+pub fn get_gateway() -> Option<String> {
+    // The header is skipped by the parsers, as it's not made of addresses
+    let tables: &[(&str, ParseRoute)] =
+        &[("/proc/net/route", parse_ipv4_route), ("/proc/net/ipv6_route", parse_ipv6_route)];
+
+    // Out of the default routes of each table, the kernel prefers the one with the
+    // lowest metric
+    let gateways: Vec<String> = tables
+        .iter()
+        .filter_map(|(path, parse_route)| {
+            let routes = fs::read_to_string(path).ok()?;
+            let route = routes.lines().filter_map(parse_route).min_by_key(|route| route.metric)?;
+            Some(format!("{} ({})", route.gateway, route.interface))
+        })
+        .collect();
+
+    if gateways.is_empty() {
+        None
+    } else {
+        Some(gateways.join(", "))
+    }
+}
+
+/// The servers listed in a resolv.conf, as lines such as "nameserver 192.168.0.1"
+fn read_nameservers(path: &str) -> Vec<String> {
+    let resolv_conf = fs::read_to_string(path).unwrap_or_default();
+
+    resolv_conf
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("nameserver") => words.next().map(ToString::to_string),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// The DNS servers the resolver queries, e.g. "1.1.1.1, 9.9.9.9". If it queries the
+/// stub of systemd-resolved instead, the servers resolved forwards to are listed.
+pub fn get_dns() -> Option<String> {
+    let nameservers = read_nameservers("/etc/resolv.conf");
+    if nameservers.is_empty() {
+        return None;
+    }
+
+    if nameservers.iter().all(|server| RESOLVED_STUBS.contains(&server.as_str())) {
+        // Where resolved lists the servers it was configured with, or got from DHCP
+        let upstream = read_nameservers("/run/systemd/resolve/resolv.conf");
+        let servers = if upstream.is_empty() { nameservers } else { upstream };
+        return Some(format!("{} (systemd-resolved)", servers.join(", ")));
+    }
+
+    Some(nameservers.join(", "))
+}
+
+/// Resolves the fully qualified name of the host, e.g. "node12.cluster.example.com", through
+/// /etc/hosts or DNS, as `hostname --fqdn` does
+pub fn get_fqdn(hostname: &str) -> Option<String> {
+    let name = CString::new(hostname).ok()?;
+    let mut hints: addrinfo = unsafe { mem::zeroed() };
+    hints.ai_flags = AI_CANONNAME;
+    // Otherwise, every address is listed once for each type of socket
+    hints.ai_socktype = SOCK_DGRAM;
+
+    let mut result = ptr::null_mut();
+    if unsafe { getaddrinfo(name.as_ptr(), ptr::null(), &hints, &mut result) } != 0 {
+        return None;
+    }
+
+    // Only the first entry holds the canonical name
+    let canonical_name = unsafe { (*result).ai_canonname };
+    let fqdn = if canonical_name.is_null() {
+        None
+    } else {
+        Some(unsafe { char_ptr_to_string(canonical_name) })
+    };
+    unsafe { freeaddrinfo(result) };
+
+    fqdn.filter(|fqdn| fqdn.contains('.'))
+}
+
+/// The NIS domain, as set by domainname(1) or ypbind
+pub fn get_nis_domain() -> Option<String> {
+    let domain = fs::read_to_string("/proc/sys/kernel/domainname").ok()?;
+
+    match domain.trim() {
+        "" | "(none)" => None,
+        domain => Some(domain.to_string()),
+    }
+}
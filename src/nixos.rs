@@ -0,0 +1,52 @@
+use std::{fs, path::Path};
+
+/// The system profile, which links to the current generation, e.g. "system-412-link"
+const SYSTEM_PROFILE: &str = "/nix/var/nix/profiles/system";
+/// The channels of root, which are the ones nixos-rebuild builds from, e.g. "nixos"
+const ROOT_CHANNELS: &str = "/nix/var/nix/profiles/per-user/root/channels";
+
+/// Number of the generation the system profile is at
+fn get_generation() -> Option<u64> {
+    let link = fs::read_link(SYSTEM_PROFILE).ok()?;
+    let link = link.file_name()?.to_string_lossy().into_owned();
+
+    link.strip_prefix("system-")?.strip_suffix("-link")?.parse().ok()
+}
+
+/// Tells how the system was built: from a flake, or from the nixos channel,
+/// whose revision is kept in .git-revision
+fn get_source() -> Option<String> {
+    if Path::new("/etc/nixos/flake.nix").exists() {
+        return Some("flake".to_string());
+    }
+
+    let channel = Path::new(ROOT_CHANNELS).join("nixos");
+    if !channel.exists() {
+        return None;
+    }
+
+    match fs::read_to_string(channel.join(".git-revision")) {
+        Ok(revision) if revision.trim().len() >= 7 => {
+            Some(format!("channel {}", &revision.trim()[..7]))
+        },
+        _ => Some("channel".to_string()),
+    }
+}
+
+/// Describes the running NixOS system, e.g. "generation 412, nixpkgs 24.05.20240312.b8dd8be
+/// (flake)", where the version of NixOS ends with the revision of nixpkgs it was built from
+pub fn get_nixos_info() -> Option<String> {
+    let version = fs::read_to_string("/run/current-system/nixos-version").ok()?;
+    let mut info = vec![];
+
+    if let Some(generation) = get_generation() {
+        info.push(format!("generation {}", generation));
+    }
+    info.push(format!("nixpkgs {}", version.trim()));
+
+    let info = info.join(", ");
+    match get_source() {
+        Some(source) => Some(format!("{} ({})", info, source)),
+        None => Some(info),
+    }
+}
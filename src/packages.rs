@@ -0,0 +1,190 @@
+use crate::{pacman, sqlite::Database};
+
+use std::{env, fs, path::PathBuf};
+
+/// Counts the packages installed through a package manager, if it's present
+pub type Counter = fn() -> Option<usize>;
+
+/// Counts the directories within a directory, e.g. one per package in /var/lib/pacman/local
+fn count_dirs(path: &str) -> Option<usize> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(entries.flatten().filter(|entry| entry.path().is_dir()).count())
+}
+
+/// dpkg's status file holds a stanza per package, including removed ones. Installed ones
+/// have a status such as "install ok installed", or "hold ok installed" if held back.
+fn count_dpkg() -> Option<usize> {
+    let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    let count = status
+        .lines()
+        .filter(|line| line.starts_with("Status: ") && line.ends_with(" ok installed"))
+        .count();
+
+    Some(count)
+}
+
+/// RPM keeps its database in SQLite since 4.16, with a row per package
+fn count_rpm() -> Option<usize> {
+    ["/var/lib/rpm/rpmdb.sqlite", "/usr/lib/sysimage/rpm/rpmdb.sqlite"]
+        .iter()
+        .find_map(|path| Database::open(path.as_ref())?.count_rows("Packages"))
+}
+
+/// apk's database has a "P:" line with the name of each package
+fn count_apk() -> Option<usize> {
+    let installed = fs::read_to_string("/lib/apk/db/installed").ok()?;
+    Some(installed.lines().filter(|line| line.starts_with("P:")).count())
+}
+
+/// xbps keeps a plist with a dictionary per package, each holding its "pkgver"
+fn count_xbps() -> Option<usize> {
+    let entries = fs::read_dir("/var/db/xbps").ok()?;
+    let pkgdb = entries.flatten().find(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        name.starts_with("pkgdb-") && name.ends_with(".plist")
+    })?;
+
+    let plist = fs::read_to_string(pkgdb.path()).ok()?;
+    Some(plist.matches("<key>pkgver</key>").count())
+}
+
+/// Portage has a directory per package, within a directory per category
+fn count_portage() -> Option<usize> {
+    let categories = fs::read_dir("/var/db/pkg").ok()?;
+    let count = categories
+        .flatten()
+        .filter_map(|category| count_dirs(&category.path().to_string_lossy()))
+        .sum();
+
+    Some(count)
+}
+
+/// Counts the packages installed in Nix profiles, either through `nix profile`,
+/// whose manifest.json lists the store paths of each, or through nix-env,
+/// whose manifest.nix holds a derivation per package
+fn count_nix() -> Option<usize> {
+    let mut profiles = vec![
+        PathBuf::from("/nix/var/nix/profiles/default"),
+        PathBuf::from("/run/current-system/sw"),
+    ];
+    if let Some(home) = env::var_os("HOME") {
+        let home = PathBuf::from(home);
+        profiles.push(home.join(".nix-profile"));
+        profiles.push(home.join(".local/state/nix/profile"));
+    }
+
+    // Profiles are often linked to one another
+    let mut profiles: Vec<PathBuf> = profiles
+        .into_iter()
+        .filter_map(|profile| fs::canonicalize(profile).ok())
+        .collect();
+    profiles.sort();
+    profiles.dedup();
+
+    let count: usize = profiles
+        .iter()
+        .filter_map(|profile| {
+            if let Ok(manifest) = fs::read_to_string(profile.join("manifest.json")) {
+                Some(manifest.matches("\"storePaths\"").count())
+            } else {
+                let manifest = fs::read_to_string(profile.join("manifest.nix")).ok()?;
+                Some(manifest.matches("type = \"derivation\";").count())
+            }
+        })
+        .sum();
+
+    if count > 0 {
+        Some(count)
+    } else {
+        None
+    }
+}
+
+/// Flatpak installs a ref per application or runtime, architecture and branch,
+/// e.g. app/org.mozilla.firefox/x86_64/stable, both system-wide and for the user.
+/// Applications also have a "current" link to the ref they run by default.
+fn count_flatpak() -> Option<usize> {
+    let mut installations = vec![PathBuf::from("/var/lib/flatpak")];
+    if let Some(home) = env::var_os("HOME") {
+        installations.push(PathBuf::from(home).join(".local/share/flatpak"));
+    }
+
+    let count = installations
+        .iter()
+        .flat_map(|installation| vec![installation.join("app"), installation.join("runtime")])
+        .filter_map(|kind| fs::read_dir(kind).ok())
+        .flat_map(|ids| ids.flatten())
+        .filter_map(|id| fs::read_dir(id.path()).ok())
+        .flat_map(|arches| arches.flatten())
+        // Unlike entry.path().is_dir(), file_type() doesn't follow links
+        .filter(|arch| arch.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|arch| count_dirs(&arch.path().to_string_lossy()))
+        .sum();
+
+    Some(count)
+}
+
+/// Each snap is mounted in a directory of /snap, which also holds the links in /snap/bin
+fn count_snap() -> Option<usize> {
+    let snaps = fs::read_dir("/snap").ok()?;
+    let count = snaps
+        .flatten()
+        .filter(|snap| snap.file_name() != "bin" && snap.path().join("current").exists())
+        .count();
+
+    Some(count)
+}
+
+/// AppImages aren't installed anywhere, but are usually kept in a few directories
+fn count_appimage() -> Option<usize> {
+    let home = PathBuf::from(env::var_os("HOME")?);
+    let dirs = ["Applications", "AppImages", "Apps", ".local/bin", "bin"];
+
+    let count = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(home.join(dir)).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            name.ends_with(".appimage")
+        })
+        .count();
+
+    Some(count)
+}
+
+/// Number of packages installed through each package manager, e.g. "1342 (pacman), 12 (nix)",
+/// with the foreign pacman packages on their own, e.g. "1285 (pacman), 57 (aur)"
+pub fn get_packages() -> Option<String> {
+    let managers: [(&str, Counter); 9] = [
+        ("dpkg", count_dpkg),
+        ("rpm", count_rpm),
+        ("apk", count_apk),
+        ("xbps", count_xbps),
+        ("portage", count_portage),
+        ("nix", count_nix),
+        ("flatpak", count_flatpak),
+        ("snap", count_snap),
+        ("appimage", count_appimage),
+    ];
+
+    let mut counts = vec![];
+    if let Some((repo, foreign)) = pacman::count_packages() {
+        counts.push(("pacman", repo));
+        counts.push(("aur", foreign));
+    }
+    counts.extend(managers.iter().filter_map(|(name, count)| Some((*name, count()?))));
+
+    let counts: Vec<String> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(name, count)| format!("{} ({})", count, name))
+        .collect();
+
+    if counts.is_empty() {
+        None
+    } else {
+        Some(counts.join(", "))
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{inflate::gunzip, util::get_cache_dir, vercmp::compare_rpm};
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fs,
+    os::unix::fs::MetadataExt,
+};
+
+const LOCAL_DB: &str = "/var/lib/pacman/local";
+const SYNC_DB: &str = "/var/lib/pacman/sync";
+
+/// Tar archives are made of 512-byte blocks, each entry starting with a header block
+const BLOCK_SIZE: usize = 512;
+
+/// Entries of the databases are named after the package, its version and its release,
+/// e.g. "linux-firmware-20240312.3f0b7b8-1", so the name is what's before the last two dashes
+fn split_entry(entry: &str) -> (&str, &str) {
+    let mut dashes = entry.rmatch_indices('-').map(|(index, _)| index);
+    match dashes.nth(1) {
+        Some(index) => (&entry[..index], &entry[index + 1..]),
+        None => (entry, ""),
+    }
+}
+
+/// The packages in a tar archive, given by the directory each of their files is in,
+/// e.g. "linux-6.8.1.arch1-1" for "linux-6.8.1.arch1-1/desc"
+fn read_tar(data: &[u8]) -> Option<HashSet<String>> {
+    let mut entries = HashSet::new();
+    let mut offset = 0;
+
+    while let Some(header) = data.get(offset..offset + BLOCK_SIZE) {
+        // The archive ends with zeroed blocks
+        if header[0] == 0 {
+            break;
+        }
+
+        let path = &header[..100];
+        let path = &path[..path.iter().position(|&byte| byte == 0).unwrap_or(100)];
+        let path = String::from_utf8_lossy(path);
+        if let Some(entry) = path.split('/').next() {
+            entries.insert(entry.to_string());
+        }
+
+        // The size is written in octal, padded with NULs or spaces
+        let size = String::from_utf8_lossy(&header[124..136]);
+        let size = usize::from_str_radix(size.trim_matches(['\0', ' ']), 8).ok()?;
+        offset += BLOCK_SIZE + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+
+    Some(entries)
+}
+
+/// The sync databases, one per repository, e.g. /var/lib/pacman/sync/core.db
+fn sync_databases() -> Option<Vec<fs::DirEntry>> {
+    let mut databases: Vec<_> = fs::read_dir(SYNC_DB)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".db"))
+        .collect();
+    databases.sort_by_key(|entry| entry.file_name());
+
+    Some(databases)
+}
+
+/// The packages available in the repositories, with their versions. As decompressing the
+/// databases takes a while, they're cached until the databases change, which is when pacman
+/// syncs them.
+fn read_sync_entries() -> Option<HashSet<String>> {
+    let databases = sync_databases()?;
+    if databases.is_empty() {
+        return None;
+    }
+
+    // Identifies the state of the databases, e.g. "core.db 1711234567 131209"
+    let key: Vec<String> = databases
+        .iter()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let name = entry.file_name();
+            Some(format!("{} {} {}", name.to_string_lossy(), metadata.mtime(), metadata.size()))
+        })
+        .collect();
+    let key = key.join(", ");
+
+    let cache = get_cache_dir().map(|dir| dir.join("pacman-sync"));
+    if let Some(text) = cache.as_ref().and_then(|cache| fs::read_to_string(cache).ok()) {
+        let mut lines = text.lines();
+        if lines.next() == Some(key.as_str()) {
+            return Some(lines.map(ToString::to_string).collect());
+        }
+    }
+
+    // Repositories may be compressed with something other than gzip, such as zstd, in which
+    // case foreign packages can't be told apart
+    let mut entries = HashSet::new();
+    for database in &databases {
+        let data = fs::read(database.path()).ok()?;
+        entries.extend(read_tar(&gunzip(&data)?)?);
+    }
+
+    if let Some(cache) = cache {
+        let mut text = key;
+        for entry in &entries {
+            text.push('\n');
+            text.push_str(entry);
+        }
+        let _ = cache.parent().map(fs::create_dir_all);
+        let _ = fs::write(cache, text);
+    }
+
+    Some(entries)
+}
+
+/// The installed packages, e.g. "linux-6.8.1.arch1-1"
+fn read_local_entries() -> Option<Vec<String>> {
+    let entries = fs::read_dir(LOCAL_DB).ok()?;
+    let entries = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+
+    Some(entries)
+}
+
+/// Counts the installed packages, split into the ones from the repositories and the foreign
+/// ones, which are mostly built from the AUR. e.g. (1285, 57)
+pub fn count_packages() -> Option<(usize, usize)> {
+    let local = read_local_entries()?;
+
+    let sync = match read_sync_entries() {
+        Some(sync) => sync,
+        None => return Some((local.len(), 0)),
+    };
+    let sync_names: HashSet<&str> = sync.iter().map(|entry| split_entry(entry).0).collect();
+
+    let foreign = local
+        .iter()
+        .filter(|entry| !sync_names.contains(split_entry(entry).0))
+        .count();
+    Some((local.len() - foreign, foreign))
+}
+
+/// Counts the installed packages with newer versions in the sync databases, as last synced
+pub fn count_updates() -> Option<usize> {
+    let local = read_local_entries()?;
+    let sync = read_sync_entries()?;
+
+    // A package may be in more than one repository, e.g. in [core] and [core-testing]
+    let mut newest: HashMap<&str, &str> = HashMap::new();
+    for (name, version) in sync.iter().map(|entry| split_entry(entry)) {
+        let newest = newest.entry(name).or_insert(version);
+        if compare_rpm(version, newest) == Ordering::Greater {
+            *newest = version;
+        }
+    }
+
+    let count = local
+        .iter()
+        .map(|entry| split_entry(entry))
+        .filter(|(name, version)| {
+            newest.get(name).is_some_and(|newest| compare_rpm(newest, version) == Ordering::Greater)
+        })
+        .count();
+
+    Some(count)
+}
@@ -0,0 +1,67 @@
+use crate::util::read_u64;
+
+use std::{fs, path::PathBuf, thread, time::Duration};
+
+/// RAPL domain of a CPU package, e.g. "/sys/class/powercap/intel-rapl:0".
+/// AMD CPUs are exposed through the same interface.
+struct RaplPackage {
+    energy_file:      PathBuf,
+    // Value after which the energy counter wraps around, in µJ
+    max_energy_range: u64,
+}
+
+fn get_rapl_packages() -> Vec<RaplPackage> {
+    let entries = match fs::read_dir("/sys/class/powercap/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // Subdomains, such as "intel-rapl:0:0" (cores), are already accounted for in
+            // their package
+            name.starts_with("intel-rapl:") && name.matches(':').count() == 1
+        })
+        .filter_map(|entry| {
+            let path = entry.path();
+            Some(RaplPackage {
+                energy_file:      path.join("energy_uj"),
+                max_energy_range: read_u64(&path.join("max_energy_range_uj"))?,
+            })
+        })
+        .collect()
+}
+
+/// Samples the RAPL energy counters twice and returns the average power draw of all
+/// CPU packages in watts.
+/// Note: the counters are usually only readable by root.
+pub fn get_package_power(interval: Duration) -> Option<f64> {
+    let packages = get_rapl_packages();
+
+    let before: Vec<u64> = packages
+        .iter()
+        .map(|package| read_u64(&package.energy_file))
+        .collect::<Option<_>>()?;
+
+    if before.is_empty() || interval.as_micros() == 0 {
+        return None;
+    }
+
+    thread::sleep(interval);
+
+    let mut microjoules = 0;
+    for (package, before) in packages.iter().zip(before) {
+        let after = read_u64(&package.energy_file)?;
+        microjoules += if after >= before {
+            after - before
+        } else {
+            // The counter wrapped around
+            package.max_energy_range - before + after
+        };
+    }
+
+    Some(microjoules as f64 / interval.as_micros() as f64)
+}
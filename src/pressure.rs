@@ -0,0 +1,32 @@
+use std::{fs, path::Path};
+
+/// Resources tracked by the pressure stall information (PSI) interface
+pub const RESOURCES: &[&str] = &["cpu", "memory", "io"];
+
+/// Share of time in which some task was stalled waiting for a resource, in percent
+#[derive(Debug)]
+pub struct Pressure {
+    pub avg10:  f64,
+    pub avg60:  f64,
+    pub avg300: f64,
+}
+
+/// Reads the "some" line of /proc/pressure/{resource}, which looks like
+/// "some avg10=4.42 avg60=3.16 avg300=2.58 total=29036950"
+pub fn get_pressure(resource: &str) -> Option<Pressure> {
+    let text = fs::read_to_string(Path::new("/proc/pressure/").join(resource)).ok()?;
+    let line = text.lines().find(|line| line.starts_with("some "))?;
+
+    let average = |key: &str| -> Option<f64> {
+        line.split_whitespace()
+            .find_map(|field| field.strip_prefix(key)?.strip_prefix('='))?
+            .parse()
+            .ok()
+    };
+
+    Some(Pressure {
+        avg10: average("avg10")?,
+        avg60: average("avg60")?,
+        avg300: average("avg300")?,
+    })
+}
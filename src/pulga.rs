@@ -1,15 +1,26 @@
-// TODO: /cpu/procinfo quirks
-//     * Intel usually puts an @ with the frequency in `model name`
-//     * AMD usually puts something like "Eight-Core Processor" in `model name`
-//       (at least in the Ryzen series)
-//     * `model nome` is really vague in Raspberry Pis. Getting `Hardware` would
-//       be a better fit.
-
 use crate::{
+    audio, backlight, battery, btrfs, camera, cmdline, compositor,
+    config::{Cmdline, Config, CpuFreq, Ipv6, Mac},
+    container,
+    cpu::{self, CpuTimes},
+    cursor, desktop, disk, dmi, efi, git, gpu, groups, gtk, history, icons, init, keyboard, logins,
+    lvm, mdraid,
+    meminfo::MemInfo,
+    modules, network, nixos, packages, power, pressure, qt, scale,
     screenres::get_screen_resolution,
+    screenreswayland,
+    sensors::{self, SensorKind},
+    session::{self, DisplayProtocol},
+    shell, state,
+    swap,
     sysinfo::SysInfo,
+    systemd, taint,
+    termcolors::{self, TermColors},
+    termfont, terminal, toolchains, tpm,
     uname::UnameData,
-    util::{char_ptr_to_string, os_str_to_string, get_base},
+    updates, usb,
+    util::{char_ptr_to_string, os_str_to_string, get_base, get_process_names, get_selected_option},
+    virt, vpn, wifi, wm, wsl, xserver, zfs,
 };
 
 #[cfg(feature = "use_xlib")]
@@ -19,25 +30,101 @@ use libc::{c_char, gethostname, getpwuid_r, getuid, passwd, sysconf};
 
 use smallvec::{smallvec, SmallVec};
 
-use std::{cmp, env, fs, mem, ptr};
+use std::{
+    cmp, env, fs, mem, ptr, thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Debug)]
 pub struct UserData {
     pub username:       String, // User's username
     pub hostname:       String, // User's hostname
     pub cpu_info:       String, // Some CPU info
-    pub cwd:            String, // User's current working directory. TODO: unneeded?
+    pub cpu_temp:       Option<String>, // CPU temperature, if available
+    pub cpu_usage:      Option<String>, // Share of time the CPUs were busy, if enabled
+    pub power_mode:     Option<String>, // cpufreq governor and platform power profile, if available
+    pub gpu_info:       String, // Model of the GPUs present
+    pub graphics:       String, // Versions of the graphics stack (Mesa, Vulkan)
+    pub x_server:       Option<String>, // Name and version of the X server, if there's a display
+    pub ssh_client:     Option<String>, // Address of the SSH client, in SSH sessions
+    pub cwd:            String, // User's current working directory
+    pub git:            Option<String>, // Branch and state of the git repository cwd is in, if any
     pub hmd:            String, // User's home directory
-    pub shell:          String, // User's standard shell
+    pub shell:          String, // User's standard shell and its version
+    pub terminal:       Option<String>, // Terminal emulator pulga runs in, if any
+    pub terminal_font:  Option<String>, // Font configured in the terminal, for the known ones
+    pub term_colors:    Option<String>, // Foreground and background colors of the terminal
+    pub light_bg:       bool, // Whether the terminal has a light background
+    pub display:        String, // Display protocol of the session: X11, Wayland or TTY
     pub desk_env:       String, // User's desktop environment
+    pub wm:             Option<String>, // Window manager, or Wayland compositor
+    pub compositor:     Option<String>, // Compositor of the session, if not part of the DE
+    pub gtk_theme:      Option<String>, // GTK theme, for GTK 3 and 4
+    pub qt_theme:       Option<String>, // Qt style and platform theme
+    pub icon_theme:     Option<String>, // Icon theme of GTK and KDE applications
+    pub cursor_theme:   Option<String>, // Cursor theme and size
     // pub distro_id:      String, // User's distro ID name
     pub distro:         String, // User's distro's pretty name
+    pub nixos:          Option<String>, // Generation and nixpkgs revision, on NixOS
     pub uptime:         String, // Time elapsed since boot
+    pub users:          Option<String>, // Who is logged in, and from where
+    pub last_login:     Option<String>, // When and from where the user last logged in
+    pub groups:         Option<String>, // Supplementary groups of the user, if enabled
+    pub processes:      Option<String>, // Number of processes and threads
+    pub packages:       Option<String>, // Number of installed packages, per package manager
+    pub updates:        Option<String>, // Number of pending updates per package manager, if enabled
+    pub last_update:    Option<String>, // How long ago packages were last upgraded
+    pub install_date:   Option<String>, // When the system was installed, as an estimate
     pub editor:         String, // User's default editor, as pointed by the EDITOR var env.
+    pub toolchains:     Vec<(String, String)>, // Versions of the compilers in $PATH, if enabled
     pub kernel_version: String, // User's current kernel version
+    pub init:           Option<String>, // Init system, if known
+    pub cmdline:        Option<String>, // Parameters the kernel was booted with, if enabled
+    pub taint:          Option<String>, // Why the kernel is tainted, if it is
+    pub modules:        Option<String>, // Number of loaded kernel modules
+    pub boot_time:      Option<String>, // How long the last boot took, as measured by systemd
+    pub failed_units:   Option<u64>, // Number of systemd units which failed
+    pub host:           Option<String>, // Model of the machine, if known
+    pub firmware:       Option<String>, // Boot mode and BIOS version, if known
+    pub chassis:        Option<String>, // Kind of machine, e.g. "Laptop"
+    pub hypervisor:     Option<String>, // Hypervisor the system runs under, if any
+    pub container:      Option<String>, // Container manager the system runs under, if any
+    pub wsl:            Option<String>, // WSL version and Windows host build, if under WSL
+    pub secure_boot:    String, // Whether UEFI Secure Boot is enabled
+    pub tpm:            Option<String>, // Version of the TPM, if there's one
+    pub audio:          Option<String>, // Sound server and the card of its default sink
+    pub keyboard:       Option<String>, // Active keyboard layouts and their variants
+    pub local_ip:       Option<String>, // IPv4 address of each active network interface
+    pub ipv6:           Option<String>, // Global IPv6 address of each interface, unless hidden
+    pub gateway:        Option<String>, // Default gateways, and their interfaces
+    pub vpn:            Option<String>, // Active VPN tunnels
+    pub dns:            Option<String>, // DNS servers the resolver queries
+    pub domain:         Option<String>, // DNS domain of the host, if resolved, and its NIS domain
+    pub wifi:           Option<String>, // SSID and signal strength of the wireless connection
+    pub link_speed:     Option<String>, // Negotiated speed and duplex of each wired interface
+    pub mac:            Option<String>, // MAC address of each interface, unless hidden
+    pub net_io:         Option<String>, // Throughput of the network interfaces, if enabled
+    pub public_ip:      Option<String>, // Address seen from the Internet, if enabled
     pub total_memory:   String, // Total memory in human-readable form
     pub used_memory:    String, // Used memory in human-readable form
-    pub monitor_res:    String, // Resolution of currently connected monitors.
+    pub memory_modules: Option<String>, // Size, type and speed of the RAM modules, if enabled
+    pub hugepages:      Option<String>, // Reserved and transparent huge pages, if in use
+    pub swap:           Option<String>, // Usage of the swap space and its compression, if there's any
+    pub disk_usage:     String, // Usage of the root filesystem and the disk holding it
+    pub nvme_health:    Option<String>, // Temperature and wear of the NVMe drives, if any
+    pub disk_io:        Option<String>, // I/O throughput of the root device, if enabled
+    pub mounts:         Vec<(String, String)>, // Usage of each mounted filesystem, if enabled
+    pub zfs_pools:      Option<String>, // Usage and health of the ZFS pools, if any
+    pub raid_arrays:    Option<String>, // Level and health of the mdadm RAID arrays, if any
+    pub monitor_res:    Option<String>, // Resolution of the connected monitors, outside of SSH
+    pub battery:        Option<String>, // Charge and state of the batteries, if any
+    pub brightness:     Option<String>, // Backlight brightness, on laptops
+    pub cpu_power:      Option<String>, // Power draw of the CPU packages, if enabled
+    pub sensors:        Vec<(String, String)>, // Readings of the hwmon sensors chosen by the user
+    pub fans:           Option<String>, // Speed of the fans, if any
+    pub pressure:       Vec<(String, String)>, // PSI averages of the CPU, memory and I/O, if enabled
+    pub usb_devices:    Vec<String>, // Names of the connected USB devices, if enabled
+    pub cameras:        Option<String>, // Names of the video capture devices, if enabled
 }
 
 /// The number of threads the CPU can handle at any given time
@@ -62,6 +149,78 @@ fn get_logical_cpus() -> usize {
     }
 }
 
+/// Physical cores and logical threads, e.g. "8c/16t", or just the threads
+/// (e.g. "16x") if the topology is unknown
+fn get_cpu_count() -> String {
+    let threads = get_logical_cpus();
+
+    match cpu::get_physical_cores() {
+        Some(cores) => format!("{}c/{}t", cores, threads),
+        None => format!("{}x", threads),
+    }
+}
+
+/// Describes CPUs with different kinds of cores,
+/// e.g. "4x Cortex-A76 @ 2.4 GHz + 4x Cortex-A55 @ 1.8 GHz"
+fn get_core_clusters() -> Option<String> {
+    let clusters = cpu::get_core_clusters();
+    if clusters.len() < 2 {
+        return None;
+    }
+
+    let clusters: Vec<String> = clusters
+        .iter()
+        .map(|cluster| {
+            let mut description = format!("{}x", cluster.cores);
+            if let Some(name) = &cluster.name {
+                description.push(' ');
+                description.push_str(name);
+            }
+            if let Some(max_freq) = cluster.max_freq {
+                description.push_str(&format!(" @ {:.1} GHz", max_freq / 1000000.0));
+            }
+            description
+        })
+        .collect();
+
+    Some(clusters.join(" + "))
+}
+
+/// Sizes of the L2 and L3 caches, e.g. " (L2 8.00 MB, L3 32.00 MB)"
+fn get_cpu_cache() -> String {
+    let caches: Vec<String> = cpu::get_cache_sizes()
+        .into_iter()
+        .filter(|&(level, _)| level >= 2)
+        .map(|(level, size)| format!("L{} {}", level, pretty_bytes(size as f64)))
+        .collect();
+
+    if caches.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", caches.join(", "))
+    }
+}
+
+pub fn get_cpu_freq(mode: CpuFreq) -> Option<String> {
+    match mode {
+        CpuFreq::Max => get_cpu_max_freq(),
+        CpuFreq::Current => {
+            let freq_ghz = cpu::get_current_freq()? / 1000000.0;
+            Some(format!("{:.2} GHz", freq_ghz))
+        },
+        CpuFreq::Range => {
+            let (min_freq, max_freq) = cpu::get_freq_range()?;
+            let (min_ghz, max_ghz) = (min_freq / 1000000.0, max_freq / 1000000.0);
+
+            if format!("{:.1}", min_ghz) == format!("{:.1}", max_ghz) {
+                Some(format!("{:.1} GHz", max_ghz))
+            } else {
+                Some(format!("{:.1}–{:.1} GHz", min_ghz, max_ghz))
+            }
+        },
+    }
+}
+
 pub fn get_cpu_max_freq() -> Option<String> {
     let scaling_max_freq_str =
         match std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_max_freq") {
@@ -94,7 +253,7 @@ fn pretty_bytes(num: f64) -> String {
 }
 
 /// get_user_data returns a new UserData structure
-pub fn get_user_data() -> UserData {
+pub fn get_user_data(config: &Config) -> UserData {
     let (username, home_dir, shell) = if let Some(res) = get_username_home_dir_and_shell() {
         res
     } else {
@@ -102,45 +261,253 @@ pub fn get_user_data() -> UserData {
         (unknown.clone(), unknown.clone(), unknown)
     };
 
+    let last_login = logins::get_last_login(&username).map(|(timestamp, source)| {
+        format!("{} ({}) {}", get_date(timestamp), get_time_ago(timestamp), source)
+    });
+
     // Current working directory
     let cwd: String = os_str_to_string(env::current_dir().unwrap().as_ref());
 
     let uname_data = UnameData::gather();
 
     let hostname = get_hostname().unwrap_or_else(|| "Unknown".to_string());
+    // Only looked up if asked to, as it may wait on a DNS server
+    let fqdn = if config.fqdn { network::get_fqdn(&hostname) } else { None };
+    let domain = get_domain(fqdn.as_deref());
+    let hostname = fqdn.unwrap_or(hostname);
     let distro = get_distro().unwrap_or_else(|| "Linux".to_string());
 
     let sys_info = SysInfo::gather();
+    let chassis = dmi::get_chassis();
+    let hypervisor = virt::get_hypervisor();
+    let virtualized = hypervisor.is_some();
+    let container = container::get_container();
+    // Containers share the kernel (and so the uptime) of their host
+    let host_note = if container.is_some() { " (host)" } else { "" };
+    // Batteries and backlights are only relevant when the machine may be portable
+    let portable = match chassis {
+        Some(chassis) => chassis.is_portable(),
+        None => true,
+    };
+
+    let display_protocol = session::get_display_protocol();
+    // Over SSH, the display, if any, is forwarded from the client and isn't this machine's
+    let ssh_client = session::get_ssh_client();
+    let graphical = ssh_client.is_none();
+    let terminal = terminal::find_terminal();
+    let term_colors = termcolors::query_colors();
 
     #[cfg(feature = "use_xlib")]
-    let resolution = unsafe { screenresx11::get_screen_resolution().join(" ") };
+    let x11_resolution = |scale| {
+        let resolutions = unsafe { screenresx11::get_screen_resolution(scale) };
+        if resolutions.is_empty() {
+            None
+        } else {
+            Some(resolutions.join(", "))
+        }
+    };
 
     #[cfg(not(feature = "use_xlib"))]
-    let resolution = get_screen_resolution().unwrap_or_else(|| "Unknown".to_string());
+    let x11_resolution = |_| None;
+
+    // Xwayland only knows about the outputs through the compositor, so ask it instead
+    let wayland_resolution = if graphical && display_protocol == DisplayProtocol::Wayland {
+        screenreswayland::get_screen_resolution()
+    } else {
+        None
+    };
+
+    let resolution = graphical.then(|| {
+        wayland_resolution
+            .or_else(|| {
+                // Outside of Wayland, the outputs share one scale factor
+                let desktop_scale = scale::get_desktop_scale();
+                let x11_resolution = if display_protocol == DisplayProtocol::X11 {
+                    x11_resolution(desktop_scale)
+                } else {
+                    None
+                };
+                // Without a display server, e.g. on a TTY, DRM still knows the modes
+                x11_resolution.or_else(|| get_screen_resolution(desktop_scale))
+            })
+            .unwrap_or_else(|| "Unknown".to_string())
+    });
 
     UserData {
         username,
         hostname,
         cpu_info: format!(
-            "{} - {}x {}",
+            "{} - {}{}",
             get_cpu_model().unwrap_or_else(|| "Unknown".to_string()),
-            get_logical_cpus(),
-            get_cpu_max_freq().unwrap_or_else(|| "Unknown Freq.".to_string()),
+            get_core_clusters().unwrap_or_else(|| format!(
+                "{} {}",
+                get_cpu_count(),
+                get_cpu_freq(config.cpu_freq).unwrap_or_else(|| "Unknown Freq.".to_string())
+            )),
+            if config.cpu_cache { get_cpu_cache() } else { String::new() },
         ),
+        cpu_usage: if config.cpu_usage {
+            get_cpu_usage(config.sample_interval)
+        } else {
+            None
+        },
+        power_mode: get_power_mode(),
+        cpu_temp: sensors::get_cpu_temp().map(|temp| format!("{:.0}°C", temp)),
+        gpu_info: get_gpu_info().unwrap_or_else(|| "Unknown".to_string()),
+        x_server: if graphical { xserver::get_x_server() } else { None },
+        ssh_client,
+        graphics: get_graphics_stack().unwrap_or_else(|| "Unknown".to_string()),
+        git: git::get_git_info(cwd.as_ref()),
         cwd,
         hmd: home_dir,
-        shell,
+        shell: shell::get_shell(&shell),
+        terminal: terminal::get_terminal(terminal),
+        terminal_font: if graphical {
+            terminal.and_then(termfont::get_terminal_font)
+        } else {
+            None
+        },
+        light_bg: term_colors.as_ref().is_some_and(TermColors::is_light),
+        term_colors: term_colors.as_ref().map(TermColors::describe),
         editor: get_default_editor().unwrap_or_else(|| "Unknown".to_string()),
-        kernel_version: uname_data.release,
-        desk_env: get_desktop_environment(),
-        distro: format!("{} ({})", distro, uname_data.machine),
-        uptime: get_uptime(
-            // We pass to get_uptime the amount obtained with libc::sysinfo
-            sys_info.uptime,
+        toolchains: if config.toolchains { toolchains::get_toolchains() } else { vec![] },
+        init: init::get_init_system(),
+        cmdline: match config.cmdline {
+            Cmdline::Hidden => None,
+            Cmdline::Interesting => cmdline::get_cmdline(false),
+            Cmdline::Full => cmdline::get_cmdline(true),
+        },
+        taint: taint::get_taint(),
+        modules: modules::get_modules(),
+        boot_time: systemd::get_boot_time(),
+        failed_units: systemd::get_failed_units(),
+        kernel_version: format!("{}{}", uname_data.release, host_note),
+        host: get_host(),
+        firmware: get_firmware(),
+        chassis: chassis.map(|chassis| chassis.name().to_string()),
+        hypervisor,
+        container,
+        wsl: wsl::get_wsl_info(),
+        secure_boot: efi::get_secure_boot().name().to_string(),
+        audio: get_audio(),
+        keyboard: keyboard::get_keyboard_layout(),
+        local_ip: network::get_local_ip(),
+        ipv6: match config.ipv6 {
+            Ipv6::Hidden => None,
+            Ipv6::Prefix => network::get_ipv6(true),
+            Ipv6::Full => network::get_ipv6(false),
+        },
+        gateway: network::get_gateway(),
+        vpn: get_vpn(),
+        dns: network::get_dns(),
+        domain,
+        wifi: wifi::get_wifi(),
+        link_speed: network::get_link_speed(),
+        mac: match config.mac {
+            Mac::Hidden => None,
+            Mac::Oui => network::get_mac(true),
+            Mac::Full => network::get_mac(false),
+        },
+        net_io: if config.net_io {
+            get_net_io(config.sample_interval)
+        } else {
+            None
+        },
+        public_ip: if config.public_ip {
+            network::get_public_ip(&config.public_ip_url)
+        } else {
+            None
+        },
+        tpm: tpm::get_tpm_version().map(|version| format!("TPM {}", version)),
+        display: display_protocol.name().to_string(),
+        desk_env: desktop::get_desktop_environment(),
+        wm: if graphical { wm::get_window_manager() } else { None },
+        compositor: if graphical {
+            compositor::get_compositor().map(ToString::to_string)
+        } else {
+            None
+        },
+        // Over SSH, these would be the settings of a desktop the user isn't looking at
+        gtk_theme: if graphical { gtk::get_gtk_theme() } else { None },
+        qt_theme: if graphical { qt::get_qt_theme() } else { None },
+        icon_theme: if graphical { icons::get_icon_theme() } else { None },
+        cursor_theme: if graphical { cursor::get_cursor_theme() } else { None },
+        distro: if virtualized {
+            format!("{} ({}, virtualized)", distro, uname_data.machine)
+        } else {
+            format!("{} ({})", distro, uname_data.machine)
+        },
+        nixos: nixos::get_nixos_info(),
+        uptime: format!(
+            "{}{}{}",
+            get_uptime(
+                // We pass to get_uptime the amount obtained with libc::sysinfo
+                sys_info.uptime,
+            ),
+            host_note,
+            if config.uptime_record { get_uptime_record(sys_info.uptime) } else { String::new() },
         ),
         total_memory: pretty_bytes(sys_info.total_ram as f64),
-        used_memory: pretty_bytes((sys_info.total_ram - sys_info.free_ram) as f64),
+        used_memory: pretty_bytes(get_used_memory(&sys_info, config.cache_as_used) as f64),
+        memory_modules: if config.memory_modules {
+            get_memory_modules()
+        } else {
+            None
+        },
+        hugepages: get_hugepages(),
+        swap: get_swap_usage(),
+        users: logins::get_users(),
+        last_login,
+        groups: if config.groups { groups::get_groups() } else { None },
+        processes: get_processes(),
+        packages: packages::get_packages(),
+        updates: if config.updates { updates::get_updates() } else { None },
+        last_update: history::get_last_update().map(get_time_ago),
+        install_date: history::get_install_date()
+            .map(|timestamp| format!("{} ({})", get_date(timestamp), get_time_ago(timestamp))),
         monitor_res: resolution,
+        disk_usage: get_disk_usage().unwrap_or_else(|| "Unknown".to_string()),
+        nvme_health: get_nvme_health(),
+        disk_io: if config.disk_io {
+            get_disk_io(config.sample_interval)
+        } else {
+            None
+        },
+        mounts: if config.mounts { get_mounts_usage() } else { vec![] },
+        zfs_pools: get_zfs_pools(),
+        raid_arrays: get_raid_arrays(),
+        battery: if portable { get_battery_info() } else { None },
+        brightness: if portable {
+            backlight::get_brightness().map(|brightness| format!("{:.0}%", brightness))
+        } else {
+            None
+        },
+        cpu_power: if config.power {
+            power::get_package_power(Duration::from_millis(config.sample_interval))
+                .map(|watts| format!("{:.1} W", watts))
+        } else {
+            None
+        },
+        sensors: get_sensor_readings(&config.sensors),
+        fans: get_fan_speeds(),
+        pressure: if config.pressure { get_pressure() } else { vec![] },
+        usb_devices: if config.usb { usb::get_usb_devices() } else { vec![] },
+        cameras: if config.camera { get_cameras() } else { None },
+    }
+}
+
+/// The DNS domain the host is in, from its FQDN, and its NIS domain,
+/// e.g. "cluster.example.com (NIS: hpc)"
+pub fn get_domain(fqdn: Option<&str>) -> Option<String> {
+    let dns_domain = fqdn.and_then(|fqdn| fqdn.split_once('.')).map(|(_, domain)| domain);
+
+    match (dns_domain, network::get_nis_domain()) {
+        (Some(dns_domain), Some(nis_domain)) if dns_domain != nis_domain => {
+            Some(format!("{} (NIS: {})", dns_domain, nis_domain))
+        },
+        (Some(dns_domain), _) => Some(dns_domain.to_string()),
+        (None, Some(nis_domain)) => Some(format!("{} (NIS)", nis_domain)),
+        (None, None) => None,
     }
 }
 
@@ -198,8 +565,6 @@ pub fn get_username_home_dir_and_shell() -> Option<(String, String, String)> {
         let home_dir = unsafe { char_ptr_to_string(passwd.pw_dir) };
         
         let shell = unsafe { char_ptr_to_string(passwd.pw_shell) };
-        // From "/usr/bin/shell" to just "shell"
-        let shell = get_base(&shell);
 
         Some((username, home_dir, shell))
     } else {
@@ -207,18 +572,698 @@ pub fn get_username_home_dir_and_shell() -> Option<(String, String, String)> {
     }
 }
 
+/// Strips vendor boilerplate from a model name, so that e.g.
+/// "AMD Ryzen 7 5800H with Radeon Graphics" becomes "AMD Ryzen 7 5800H" and
+/// "Intel(R) Core(TM) i7-8700K CPU @ 3.70GHz" becomes "Intel Core i7-8700K".
+/// The core count and frequency are shown separately anyway.
+fn normalize_cpu_model(model: &str) -> String {
+    // Intel usually puts an @ with the frequency in the end
+    let model = model.split('@').next().unwrap_or(model);
+    let model = model.replace("(R)", "").replace("(TM)", "").replace("(tm)", "");
+
+    // AMD APUs end with e.g. "with Radeon Graphics" or "w/ Radeon 780M Graphics"
+    let model = match model.find(" with Radeon").or_else(|| model.find(" w/ Radeon")) {
+        Some(index) => &model[..index],
+        None => &model,
+    };
+
+    model
+        .split_whitespace()
+        // e.g. "Eight-Core" or "12-Core", which AMD puts in `model name`
+        .filter(|word| !word.ends_with("-Core"))
+        .filter(|word| !matches!(*word, "CPU" | "Processor"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 pub fn get_cpu_model() -> Option<String> {
     let data = fs::read_to_string("/proc/cpuinfo").ok()?;
-    for line in data.lines() {
-        if line.len() < 11 {
-            continue;
+    let model_name = cpu::cpuinfo_field(&data, "model name");
+
+    // On 32-bit ARM, `model name` is as vague as "ARMv7 Processor rev 3 (v7l)",
+    // and it's usually missing on 64-bit ARM and RISC-V
+    match model_name {
+        Some(model) if !model.starts_with("ARMv") => Some(normalize_cpu_model(model)),
+        _ => cpu::get_riscv_model(&data)
+            .or_else(|| cpu::get_board_and_soc(&data))
+            .or_else(|| model_name.map(normalize_cpu_model)),
+    }
+}
+
+/// Model of the machine from DMI or, on ARM boards, from the device tree
+pub fn get_host() -> Option<String> {
+    dmi::get_host_model().or_else(|| cpu::read_device_tree_strings("model").into_iter().next())
+}
+
+/// e.g. "UEFI, N32ET86W (1.62) from 2023-03-15"
+pub fn get_firmware() -> Option<String> {
+    let firmware = dmi::get_firmware()?;
+    let mut info = if firmware.uefi { "UEFI" } else { "Legacy BIOS" }.to_string();
+
+    if let Some(version) = firmware.version {
+        info.push_str(&format!(", {}", version));
+        if let Some(date) = firmware.date {
+            info.push_str(&format!(" from {}", date));
         }
-        if let "model name" = &line[..10] {
-            return Some(line[12..].splitn(2, '@').next().unwrap().trim().to_string());
+    }
+
+    Some(info)
+}
+
+pub fn get_gpu_info() -> Option<String> {
+    let gpus = gpu::get_gpus();
+    if gpus.is_empty() {
+        return None;
+    }
+
+    let names: Vec<String> = gpus
+        .iter()
+        .map(|gpu| {
+            let mut details: SmallVec<[String; 4]> = smallvec![];
+
+            match (gpu.vram_used, gpu.vram_total) {
+                (Some(used), Some(total)) if total > 0 => details.push(format!(
+                    "{} / {}",
+                    pretty_bytes(used as f64),
+                    pretty_bytes(total as f64)
+                )),
+                (None, Some(total)) if total > 0 => details.push(pretty_bytes(total as f64)),
+                _ => {},
+            }
+            if let Some(busy) = gpu.busy {
+                details.push(format!("{}%", busy));
+            }
+            if let Some(temp) = gpu.temp {
+                details.push(format!("{:.0}°C", temp));
+            }
+            if let Some(driver) = &gpu.driver {
+                details.push(format!("driver {}", driver));
+            }
+
+            if details.is_empty() {
+                gpu.name.clone()
+            } else {
+                format!("{} ({})", gpu.name, details.join(", "))
+            }
+        })
+        .collect();
+
+    Some(names.join(", "))
+}
+
+pub fn get_graphics_stack() -> Option<String> {
+    let mut stack: SmallVec<[String; 2]> = smallvec![];
+
+    if let Some(mesa_version) = gpu::get_mesa_version() {
+        stack.push(format!("Mesa {}", mesa_version));
+    }
+
+    let icds = gpu::get_vulkan_icds();
+    if !icds.is_empty() {
+        let api_version = icds
+            .iter()
+            .filter_map(|icd| icd.api_version.as_deref())
+            .max_by_key(|version| {
+                version
+                    .split('.')
+                    .map(|number| number.parse::<u32>().unwrap_or(0))
+                    .collect::<SmallVec<[u32; 4]>>()
+            })
+            .map(|version| format!(" {}", version))
+            .unwrap_or_default();
+        let names: Vec<&str> = icds.iter().map(|icd| icd.name.as_str()).collect();
+
+        stack.push(format!("Vulkan{} ({})", api_version, names.join(", ")));
+    }
+
+    if stack.is_empty() {
+        None
+    } else {
+        Some(stack.join(", "))
+    }
+}
+
+pub fn get_battery_info() -> Option<String> {
+    let batteries = battery::get_batteries();
+
+    let infos: Vec<String> = batteries
+        .iter()
+        .map(|battery| {
+            let status = match battery.rate {
+                Some(rate) => format!("{} at {:.1} W", battery.status, rate),
+                None => battery.status.clone(),
+            };
+
+            let mut details: SmallVec<[String; 3]> = smallvec![status];
+            if let Some(health) = battery.health {
+                details.push(format!("{:.0}% health", health));
+            }
+            if let Some(cycles) = battery.cycles {
+                details.push(format!("{} cycles", cycles));
+            }
+
+            let info = format!("{}% ({})", battery.capacity, details.join(", "));
+            if batteries.len() > 1 {
+                format!("{}: {}", battery.name, info)
+            } else {
+                info
+            }
+        })
+        .collect();
+
+    if infos.is_empty() {
+        return None;
+    }
+
+    match battery::ac_online() {
+        Some(true) => Some(format!("{} - AC connected", infos.join(", "))),
+        Some(false) => Some(format!("{} - AC disconnected", infos.join(", "))),
+        None => Some(infos.join(", ")),
+    }
+}
+
+/// Reads the hwmon sensors selected by the user, as (name, reading) pairs
+pub fn get_sensor_readings(selected: &[String]) -> Vec<(String, String)> {
+    if selected.is_empty() {
+        return vec![];
+    }
+
+    sensors::get_sensors()
+        .into_iter()
+        .filter(|sensor| selected.iter().any(|pattern| sensor.matches(pattern)))
+        .map(|sensor| {
+            let reading = match sensor.kind {
+                SensorKind::Temperature => format!("{:.0}°C", sensor.value),
+                SensorKind::Voltage => format!("{:.2} V", sensor.value),
+                SensorKind::Fan => format!("{:.0} RPM", sensor.value),
+            };
+            (format!("{} {}", sensor.chip, sensor.label), reading)
+        })
+        .collect()
+}
+
+/// Lists the speed of the fans which are spinning
+pub fn get_fan_speeds() -> Option<String> {
+    let fans: Vec<String> = sensors::get_sensors()
+        .into_iter()
+        .filter(|sensor| sensor.kind == SensorKind::Fan && sensor.value > 0.0)
+        .map(|fan| format!("{} {:.0} RPM", fan.label, fan.value))
+        .collect();
+
+    if fans.is_empty() {
+        None
+    } else {
+        Some(fans.join(", "))
+    }
+}
+
+/// Used memory in bytes. Unless `cache_as_used` is set, memory which the kernel
+/// can reclaim (such as the page cache) isn't counted, as in `free(1)`.
+pub fn get_used_memory(sys_info: &SysInfo, cache_as_used: bool) -> usize {
+    let mem_available = MemInfo::gather().and_then(|mem_info| {
+        Some((mem_info.mem_total, mem_info.mem_available?))
+    });
+
+    match mem_available {
+        Some((mem_total, mem_available)) if !cache_as_used => (mem_total - mem_available) as usize,
+        _ => sys_info.total_ram - sys_info.free_ram,
+    }
+}
+
+/// Groups identical memory modules, e.g. "2x16GB DDR4-3200"
+pub fn get_memory_modules() -> Option<String> {
+    let mut groups: Vec<(dmi::MemoryDevice, usize)> = vec![];
+
+    for device in dmi::get_memory_devices() {
+        let same_group = groups.iter_mut().find(|(other, _)| {
+            other.size == device.size && other.kind == device.kind && other.speed == device.speed
+        });
+
+        match same_group {
+            Some((_, count)) => *count += 1,
+            None => groups.push((device, 1)),
+        }
+    }
+
+    if groups.is_empty() {
+        return None;
+    }
+
+    let modules: Vec<String> = groups
+        .iter()
+        .map(|(device, count)| {
+            let mut module = if device.size % 1024 == 0 {
+                format!("{}x{}GB", count, device.size / 1024)
+            } else {
+                format!("{}x{}MB", count, device.size)
+            };
+
+            if let Some(kind) = device.kind {
+                module.push(' ');
+                module.push_str(kind);
+                if let Some(speed) = device.speed {
+                    module.push_str(&format!("-{}", speed));
+                }
+            } else if let Some(speed) = device.speed {
+                module.push_str(&format!(" {} MT/s", speed));
+            }
+
+            module
+        })
+        .collect();
+
+    Some(modules.join(", "))
+}
+
+/// Usage of the persistent huge pages and mode of the transparent ones,
+/// e.g. "512/1024 used (2.00 MB pages), THP madvise (1.50 GB)"
+pub fn get_hugepages() -> Option<String> {
+    let mem_info = MemInfo::gather()?;
+
+    // Only worth showing on systems set up to use them
+    if mem_info.hugepages == 0 && mem_info.anon_hugepages == 0 {
+        return None;
+    }
+
+    let mut info: SmallVec<[String; 2]> = smallvec![];
+
+    if mem_info.hugepages > 0 {
+        info.push(format!(
+            "{}/{} used ({} pages)",
+            mem_info.hugepages - mem_info.free_hugepages,
+            mem_info.hugepages,
+            pretty_bytes(mem_info.hugepage_size as f64)
+        ));
+    }
+
+    let thp_mode = fs::read_to_string("/sys/kernel/mm/transparent_hugepage/enabled").ok();
+    if let Some(thp_mode) = thp_mode.as_deref().and_then(get_selected_option) {
+        info.push(format!(
+            "THP {} ({})",
+            thp_mode,
+            pretty_bytes(mem_info.anon_hugepages as f64)
+        ));
+    }
+
+    Some(info.join(", "))
+}
+
+/// Pressure stall averages over 10 s, 1 min and 5 min of each resource,
+/// e.g. ("cpu pressure", "4.42% / 3.16% / 2.58%")
+pub fn get_pressure() -> Vec<(String, String)> {
+    pressure::RESOURCES
+        .iter()
+        .filter_map(|resource| {
+            let pressure = pressure::get_pressure(resource)?;
+            Some((
+                format!("{} pressure", resource),
+                format!(
+                    "{:.2}% / {:.2}% / {:.2}%",
+                    pressure.avg10, pressure.avg60, pressure.avg300
+                ),
+            ))
+        })
+        .collect()
+}
+
+pub fn get_swap_usage() -> Option<String> {
+    let mem_info = MemInfo::gather()?;
+
+    if mem_info.swap_total == 0 {
+        return None;
+    }
+
+    let zram_devices = swap::get_zram_swap_devices();
+    let zram_size: u64 = zram_devices.iter().map(|zram| zram.disk_size).sum();
+
+    let mut details: SmallVec<[String; 3]> = smallvec![];
+
+    // When all of the swap lives in zram, how much it compresses is more telling than its usage
+    let usage = if !zram_devices.is_empty() && zram_size >= mem_info.swap_total {
+        let original: u64 = zram_devices.iter().map(|zram| zram.orig_data_size).sum();
+        let compressed: u64 = zram_devices.iter().map(|zram| zram.compr_data_size).sum();
+
+        details.push("zram".to_string());
+        details.extend(zram_devices[0].algorithm.clone());
+        if compressed > 0 {
+            details.push(format!("{:.1}x", original as f64 / compressed as f64));
+        }
+
+        format!(
+            "{} compressed to {} / {}",
+            pretty_bytes(original as f64),
+            pretty_bytes(compressed as f64),
+            pretty_bytes(zram_size as f64)
+        )
+    } else {
+        let used = mem_info.swap_total - mem_info.swap_free;
+        format!(
+            "{} / {}",
+            pretty_bytes(used as f64),
+            pretty_bytes(mem_info.swap_total as f64)
+        )
+    };
+
+    if swap::zswap_enabled() {
+        let page_size = unsafe { sysconf(libc::_SC_PAGESIZE) } as u64;
+        let zswap = match (mem_info.zswapped, mem_info.zswap) {
+            (Some(stored), Some(compressed)) => Some(swap::Zswap { stored, compressed }),
+            _ => swap::get_zswap_from_debugfs(page_size),
         };
+
+        details.push(match zswap {
+            Some(zswap) => format!(
+                "zswap {} compressed to {}",
+                pretty_bytes(zswap.stored as f64),
+                pretty_bytes(zswap.compressed as f64)
+            ),
+            None => "zswap".to_string(),
+        });
+    }
+
+    if details.is_empty() {
+        Some(usage)
+    } else {
+        Some(format!("{} ({})", usage, details.join(", ")))
+    }
+}
+
+/// Usage of the root filesystem along with its type and the model of its disk,
+/// e.g. "120.50 GB / 476.94 GB (ext4, Samsung SSD 970 EVO 500GB, NVMe SSD)" or
+/// "50.20 GB / 476.94 GB (btrfs, single, subvolume /@, Samsung SSD 970 EVO 500GB, NVMe SSD)"
+pub fn get_disk_usage() -> Option<String> {
+    let root_mount = disk::get_root_mount();
+
+    let zfs_pool = root_mount.as_ref().and_then(zfs::get_pool_of);
+    let btrfs_usage = root_mount.as_ref().and_then(btrfs::get_usage);
+
+    // statvfs misreports the usage of ZFS datasets and btrfs filesystems
+    let (used, total) = match (&zfs_pool, &btrfs_usage) {
+        (Some(pool), _) => (pool.used, pool.total),
+        (_, Some(btrfs_usage)) => (btrfs_usage.used, btrfs_usage.total),
+        _ => disk::get_usage("/")?,
+    };
+    let usage = format!("{} / {}", pretty_bytes(used as f64), pretty_bytes(total as f64));
+
+    let mut details: SmallVec<[String; 6]> = smallvec![];
+
+    if let Some(root_mount) = root_mount {
+        let disk_dir = disk::get_disk_dir(&root_mount);
+        let logical_volume = disk::get_block_dir(&root_mount)
+            .and_then(|block_dir| lvm::get_logical_volume(&block_dir));
+        details.push(root_mount.fs_type);
+
+        if let Some(btrfs_usage) = btrfs_usage {
+            details.extend(btrfs_usage.data_profile);
+            if root_mount.root != "/" {
+                details.push(format!("subvolume {}", root_mount.root));
+            }
+        }
+
+        if let Some(lv) = logical_volume {
+            let mut lv_info = format!("LV {}/{} {}", lv.vg, lv.lv, pretty_bytes(lv.size as f64));
+            if lv.vg_size > 0 {
+                lv_info.push_str(&format!(" of {}", pretty_bytes(lv.vg_size as f64)));
+            }
+            details.push(lv_info);
+        }
+
+        if let Some(disk_dir) = disk_dir {
+            details.extend(disk::get_disk_model(&disk_dir));
+            details.extend(disk::get_media_type(&disk_dir).map(ToString::to_string));
+        }
+    }
+
+    if details.is_empty() {
+        Some(usage)
+    } else {
+        Some(format!("{} ({})", usage, details.join(", ")))
+    }
+}
+
+pub fn get_zfs_pools() -> Option<String> {
+    let pools: Vec<String> = zfs::get_pools()
+        .into_iter()
+        .map(|pool| {
+            let mut info = format!(
+                "{} {} / {}",
+                pool.name,
+                pretty_bytes(pool.used as f64),
+                pretty_bytes(pool.total as f64)
+            );
+            if let Some(state) = pool.state {
+                info.push_str(&format!(" ({})", state));
+            }
+            info
+        })
+        .collect();
+
+    if pools.is_empty() {
+        None
+    } else {
+        Some(pools.join(", "))
+    }
+}
+
+pub fn get_raid_arrays() -> Option<String> {
+    let arrays: Vec<String> = mdraid::get_arrays()
+        .into_iter()
+        .map(|array| {
+            let mut info = array.name;
+            if let Some(level) = array.level {
+                info.push_str(&format!(" {}", level));
+            }
+            if !array.active {
+                info.push_str(" inactive");
+            }
+            if array.degraded {
+                info.push_str(" DEGRADED");
+            }
+            if let Some(members) = array.members {
+                info.push_str(&format!(" [{}]", members));
+            }
+            if let Some(progress) = array.progress {
+                info.push_str(&format!(" ({})", progress));
+            }
+            info
+        })
+        .collect();
+
+    if arrays.is_empty() {
+        None
+    } else {
+        Some(arrays.join(", "))
+    }
+}
+
+/// Usage of each mounted filesystem, as (mount point, usage) pairs
+pub fn get_mounts_usage() -> Vec<(String, String)> {
+    disk::get_real_mounts()
+        .into_iter()
+        .filter_map(|mount| {
+            let (used, total) = disk::get_usage(&mount.mount_point)?;
+            // e.g. empty autofs-like mounts
+            if total == 0 {
+                return None;
+            }
+
+            let usage = format!(
+                "{} / {} ({})",
+                pretty_bytes(used as f64),
+                pretty_bytes(total as f64),
+                mount.fs_type
+            );
+            Some((mount.mount_point, usage))
+        })
+        .collect()
+}
+
+/// Counts the processes, alongside the threads and how many of them are runnable,
+/// e.g. "245 (1032 threads, 3 running)"
+pub fn get_processes() -> Option<String> {
+    let processes = get_process_names().len();
+
+    // The fourth field of /proc/loadavg holds the runnable and total
+    // scheduling entities (i.e. threads), e.g. "3/1032"
+    let loadavg = fs::read_to_string("/proc/loadavg").ok();
+    let threads = loadavg
+        .as_deref()
+        .and_then(|loadavg| loadavg.split_whitespace().nth(3))
+        .and_then(|field| field.split_once('/'));
+
+    match threads {
+        Some((running, total)) => Some(format!(
+            "{} ({} threads, {} running)",
+            processes, total, running
+        )),
+        None => Some(processes.to_string()),
+    }
+}
+
+/// e.g. "Integrated Camera, Logitech BRIO"
+pub fn get_cameras() -> Option<String> {
+    let cameras = camera::get_cameras();
+
+    if cameras.is_empty() {
+        None
+    } else {
+        Some(cameras.join(", "))
+    }
+}
+
+/// e.g. "PipeWire (HDA Intel PCH)"
+pub fn get_audio() -> Option<String> {
+    let server = audio::get_sound_server()?;
+
+    match audio::get_default_card(server) {
+        Some(card) => Some(format!("{} ({})", server.name(), card)),
+        None => Some(server.name().to_string()),
+    }
+}
+
+/// e.g. "governor schedutil, profile balanced"
+pub fn get_power_mode() -> Option<String> {
+    let governors = cpu::get_governors();
+    let mut info: SmallVec<[String; 2]> = smallvec![];
+
+    if !governors.is_empty() {
+        info.push(format!("governor {}", governors.join("/")));
+    }
+    if let Some(profile) = cpu::get_platform_profile() {
+        info.push(format!("profile {}", profile));
+    }
+
+    if info.is_empty() {
+        None
+    } else {
+        Some(info.join(", "))
     }
+}
 
-    None
+/// CPU utilization over the sample interval, or since boot if it's 0
+pub fn get_cpu_usage(sample_interval: u64) -> Option<String> {
+    let boot = CpuTimes { busy: 0, total: 0 };
+    let before = cpu::get_cpu_times()?;
+
+    if sample_interval == 0 {
+        return cpu::get_usage(boot, before).map(|usage| format!("{:.1}% (since boot)", usage));
+    }
+
+    thread::sleep(Duration::from_millis(sample_interval));
+    let after = cpu::get_cpu_times()?;
+
+    cpu::get_usage(before, after).map(|usage| format!("{:.1}%", usage))
+}
+
+/// I/O of the root device, either sampled over `sample_interval` milliseconds or
+/// since boot if it's 0
+pub fn get_disk_io(sample_interval: u64) -> Option<String> {
+    let block_dir = disk::get_root_mount().and_then(|mount| disk::get_block_dir(&mount))?;
+    let device_name = block_dir.file_name()?.to_str()?;
+
+    let before = disk::get_disk_stats(device_name)?;
+
+    if sample_interval == 0 {
+        return Some(format!(
+            "{} read, {} written",
+            pretty_bytes(before.read_bytes as f64),
+            pretty_bytes(before.written_bytes as f64)
+        ));
+    }
+
+    thread::sleep(Duration::from_millis(sample_interval));
+    let after = disk::get_disk_stats(device_name)?;
+
+    let seconds = sample_interval as f64 / 1000.0;
+    let read_rate = after.read_bytes.saturating_sub(before.read_bytes) as f64 / seconds;
+    let write_rate = after.written_bytes.saturating_sub(before.written_bytes) as f64 / seconds;
+
+    Some(format!(
+        "{}/s read, {}/s write",
+        pretty_bytes(read_rate),
+        pretty_bytes(write_rate)
+    ))
+}
+
+/// Traffic of the active network interfaces, either sampled over `sample_interval`
+/// milliseconds or since they came up if it's 0
+pub fn get_net_io(sample_interval: u64) -> Option<String> {
+    let before = network::get_net_stats()?;
+
+    if sample_interval == 0 {
+        return Some(format!(
+            "{} received, {} sent",
+            pretty_bytes(before.rx_bytes as f64),
+            pretty_bytes(before.tx_bytes as f64)
+        ));
+    }
+
+    thread::sleep(Duration::from_millis(sample_interval));
+    let after = network::get_net_stats()?;
+
+    let seconds = sample_interval as f64 / 1000.0;
+    let rx_rate = after.rx_bytes.saturating_sub(before.rx_bytes) as f64 / seconds;
+    let tx_rate = after.tx_bytes.saturating_sub(before.tx_bytes) as f64 / seconds;
+
+    Some(format!("{}/s down, {}/s up", pretty_bytes(rx_rate), pretty_bytes(tx_rate)))
+}
+
+/// The active VPN tunnels, e.g. "WireGuard (wg0, handshake 2 minutes ago), Tailscale
+/// (tailscale0)"
+pub fn get_vpn() -> Option<String> {
+    let vpns: Vec<String> = vpn::get_vpns()
+        .iter()
+        .map(|vpn| match vpn.last_handshake {
+            Some(timestamp) => format!(
+                "{} ({}, handshake {})",
+                vpn.kind,
+                vpn.interface,
+                get_time_ago(timestamp)
+            ),
+            None => format!("{} ({})", vpn.kind, vpn.interface),
+        })
+        .collect();
+
+    if vpns.is_empty() {
+        None
+    } else {
+        Some(vpns.join(", "))
+    }
+}
+
+pub fn get_nvme_health() -> Option<String> {
+    let drives = disk::get_nvme_health();
+
+    let infos: Vec<String> = drives
+        .iter()
+        .filter_map(|drive| {
+            let mut details: SmallVec<[String; 2]> = smallvec![];
+            if let Some(temp) = drive.temp {
+                details.push(format!("{:.0}°C", temp));
+            }
+            if let Some(percent_used) = drive.percent_used {
+                details.push(format!("{}% used", percent_used));
+            }
+
+            if details.is_empty() {
+                return None;
+            }
+
+            let details = details.join(", ");
+            if drives.len() > 1 {
+                let name = drive.model.as_deref().unwrap_or(&drive.name);
+                Some(format!("{}: {}", name, details))
+            } else {
+                Some(details)
+            }
+        })
+        .collect();
+
+    if infos.is_empty() {
+        None
+    } else {
+        Some(infos.join(", "))
+    }
 }
 
 pub fn get_uptime(uptime_in_centiseconds: usize) -> String {
@@ -262,6 +1307,45 @@ pub fn get_uptime(uptime_in_centiseconds: usize) -> String {
     uptime
 }
 
+/// The local date of a Unix timestamp, e.g. "2023-04-02"
+pub fn get_date(timestamp: i64) -> String {
+    let mut tm: libc::tm = unsafe { mem::zeroed() };
+    unsafe { libc::localtime_r(&(timestamp as libc::time_t), &mut tm) };
+
+    format!("{}-{:02}-{:02}", tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday)
+}
+
+/// The longest uptime seen so far, e.g. " (record: 42 days)", which is kept in the state
+/// directory. Empty while the current uptime is the record.
+fn get_uptime_record(uptime: usize) -> String {
+    let record: usize = state::load("uptime-record")
+        .and_then(|record| record.trim().parse().ok())
+        .unwrap_or(0);
+
+    if uptime >= record {
+        state::store("uptime-record", &uptime.to_string());
+        return String::new();
+    }
+
+    match get_uptime(record).split(' ').collect::<Vec<_>>().as_slice() {
+        [count, period, ..] => format!(" (record: {} {})", count, period),
+        _ => String::new(),
+    }
+}
+
+/// How long ago a Unix timestamp was, in its largest period only, e.g. "3 days ago"
+pub fn get_time_ago(timestamp: i64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64);
+    let elapsed = get_uptime(now.saturating_sub(timestamp).max(0) as usize);
+
+    match elapsed.split(' ').collect::<Vec<_>>().as_slice() {
+        [count, period, ..] => format!("{} {} ago", count, period),
+        _ => "just now".to_string(),
+    }
+}
+
 pub fn get_default_editor() -> Option<String> {
     let def_editor_path = std::env::var_os("EDITOR")?;
     let def_editor_path = def_editor_path.to_string_lossy();
@@ -271,22 +1355,3 @@ pub fn get_default_editor() -> Option<String> {
         get_base(&def_editor_path)
     )
 }
-
-pub fn get_desktop_environment() -> String {
-    std::env::var_os("DESKTOP_SESSION")
-        .map(|env| {
-            let env = get_base(env.to_str().unwrap()).to_lowercase();
-            match env {
-                _ if env.contains("gnome")   => "Gnome",
-                _ if env.contains("lxde")    => "LXDE",
-                _ if env.contains("openbox") => "OpenBox",
-                _ if env.contains("i3")      => "i3",
-                _ if env.contains("ubuntu")  => "Ubuntu",
-                _ if env.contains("plasma")  => "KDE",
-                _ if env.contains("mate")    => "MATE",
-                _ => env.as_str(),
-            }
-            .into()
-        })
-        .unwrap_or_else(|| "Unknown".to_string())
-}
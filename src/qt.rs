@@ -0,0 +1,80 @@
+use crate::util::{get_config_dir, nonempty_var, read_ini};
+
+use smallvec::{smallvec, SmallVec};
+
+use std::{env, path::Path};
+
+/// Reads the style and color scheme chosen in qt5ct or qt6ct, e.g. ("Fusion", Some("darker"))
+fn read_qtct(config_dir: &Path, name: &str) -> Option<(String, Option<String>)> {
+    let path = config_dir.join(name).join(format!("{}.conf", name));
+    let style = read_ini(&path, "Appearance", "style")?;
+
+    // e.g. "/usr/share/qt5ct/colors/darker.conf", only used with a custom palette
+    let custom_palette = read_ini(&path, "Appearance", "custom_palette").as_deref() == Some("true");
+    let color_scheme = if custom_palette {
+        read_ini(&path, "Appearance", "color_scheme_path").and_then(|path| {
+            let file_name = Path::new(&path).file_stem()?;
+            Some(file_name.to_string_lossy().into_owned())
+        })
+    } else {
+        None
+    };
+
+    Some((style, color_scheme))
+}
+
+/// e.g. "Breeze (KDE, BreezeDark)"
+fn format_theme(style: &str, details: &[&str]) -> String {
+    let details: SmallVec<[&str; 2]> =
+        details.iter().copied().filter(|detail| !detail.is_empty()).collect();
+
+    if details.is_empty() {
+        style.to_string()
+    } else {
+        format!("{} ({})", style, details.join(", "))
+    }
+}
+
+/// Qt style and the platform theme applying it, e.g. "Breeze (KDE, BreezeDark)" or
+/// "Fusion (qt5ct)"
+pub fn get_qt_theme() -> Option<String> {
+    let config_dir = get_config_dir()?;
+    let desktop = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+
+    // Plasma loads its own platform theme without being told to
+    let platform = nonempty_var("QT_QPA_PLATFORMTHEME")
+        .or_else(|| if desktop.contains("kde") { Some("kde".to_string()) } else { None })?;
+
+    // Overrides the style of every platform theme
+    if let Some(style) = nonempty_var("QT_STYLE_OVERRIDE") {
+        return Some(format_theme(&style, &[&platform]));
+    }
+
+    let mut themes: SmallVec<[String; 2]> = smallvec![];
+    match platform.as_str() {
+        "qt5ct" | "qt6ct" => {
+            // Qt 5 and Qt 6 applications read their own tool's settings
+            for name in &["qt5ct", "qt6ct"] {
+                if let Some((style, color_scheme)) = read_qtct(&config_dir, name) {
+                    let color_scheme = color_scheme.unwrap_or_default();
+                    themes.push(format_theme(&style, &[name, &color_scheme]));
+                }
+            }
+        },
+        "kde" => {
+            let kdeglobals = config_dir.join("kdeglobals");
+            let style = read_ini(&kdeglobals, "KDE", "widgetStyle")
+                .unwrap_or_else(|| "Breeze".to_string());
+            let color_scheme = read_ini(&kdeglobals, "General", "ColorScheme").unwrap_or_default();
+            themes.push(format_theme(&style, &["KDE", &color_scheme]));
+        },
+        // Styles which follow the GTK theme, e.g. "gtk3", or some other platform theme
+        other => themes.push(other.to_string()),
+    }
+
+    if themes.is_empty() {
+        Some(platform)
+    } else {
+        Some(themes.join(", "))
+    }
+}
@@ -0,0 +1,78 @@
+use crate::util::get_config_dir;
+
+use std::{env, fs, path::PathBuf};
+
+/// DPI at which X applications render at 100%
+const BASE_DPI: f64 = 96.0;
+
+fn scale_from_env(var: &str) -> Option<f64> {
+    env::var(var).ok()?.trim().parse().ok().filter(|scale: &f64| *scale > 0.0)
+}
+
+/// Plasma keeps the scale of X11 sessions in kdeglobals, e.g.
+/// ```ini
+/// [KScreen]
+/// ScaleFactor=1.5
+/// ```
+fn kde_scale() -> Option<f64> {
+    let kdeglobals = fs::read_to_string(get_config_dir()?.join("kdeglobals")).ok()?;
+
+    kdeglobals
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("ScaleFactor="))
+        .and_then(|scale| scale.trim().parse().ok())
+}
+
+/// GNOME keeps the scale of each logical monitor in monitors.xml, e.g.
+/// ```xml
+/// <logicalmonitor>
+///   <scale>2</scale>
+///   <primary>yes</primary>
+///   ...
+/// ```
+fn gnome_scale() -> Option<f64> {
+    let monitors = fs::read_to_string(get_config_dir()?.join("monitors.xml")).ok()?;
+
+    // Only the first configuration is the current one
+    let configuration = monitors.split("</configuration>").next()?;
+    let logical_monitors: Vec<&str> = configuration.split("<logicalmonitor>").skip(1).collect();
+
+    let primary = logical_monitors
+        .iter()
+        .find(|monitor| monitor.contains("<primary>yes</primary>"))
+        .or_else(|| logical_monitors.first())?;
+
+    let scale = primary.split("<scale>").nth(1)?.split("</scale>").next()?;
+    scale.trim().parse().ok()
+}
+
+/// Reads Xft.dpi from ~/.Xresources, for when the X resources can't be queried
+fn xresources_dpi() -> Option<f64> {
+    let xresources = fs::read_to_string(PathBuf::from(env::var_os("HOME")?).join(".Xresources"));
+
+    xresources
+        .ok()?
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Xft.dpi:"))
+        .and_then(|dpi| dpi.trim().parse().ok())
+}
+
+fn xft_scale() -> Option<f64> {
+    #[cfg(feature = "use_xlib")]
+    let dpi = unsafe { crate::screenresx11::get_xft_dpi() }.or_else(xresources_dpi);
+
+    #[cfg(not(feature = "use_xlib"))]
+    let dpi = xresources_dpi();
+
+    dpi.filter(|dpi| *dpi > 0.0).map(|dpi| dpi / BASE_DPI)
+}
+
+/// Scale factor shared by every output of the session, e.g. 1.5.
+/// Wayland compositors scale each output on their own instead.
+pub fn get_desktop_scale() -> Option<f64> {
+    scale_from_env("GDK_SCALE")
+        .or_else(|| scale_from_env("QT_SCALE_FACTOR"))
+        .or_else(kde_scale)
+        .or_else(gnome_scale)
+        .or_else(xft_scale)
+}
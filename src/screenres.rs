@@ -1,12 +1,66 @@
+use crate::{drm, edid};
+
+use smallvec::{smallvec, SmallVec};
+
 use std::{fs, path::PathBuf, vec};
 
-/// Scans through cat /sys/class/drm/*/modes looking for used resolutions.
-/// Should work on both X11 and Wayland.
-pub fn get_screen_resolution() -> Option<String> {
+/// e.g. "3840x2160 @ 60 Hz (150%, Dell U2720Q)"
+pub fn format_mode(
+    width: u32,
+    height: u32,
+    refresh: f64,
+    scale: Option<f64>,
+    monitor: Option<String>,
+) -> String {
+    let mut mode = format!("{}x{}", width, height);
+
+    if refresh > 0.0 {
+        mode.push_str(&format!(" @ {:.0} Hz", refresh));
+    }
+
+    let mut details: SmallVec<[String; 2]> = smallvec![];
+    if let Some(scale) = scale.filter(|scale| (scale - 1.0).abs() > 0.01) {
+        details.push(format!("{:.0}%", scale * 100.0));
+    }
+    if let Some(monitor) = monitor {
+        details.push(monitor);
+    }
+
+    if !details.is_empty() {
+        mode.push_str(&format!(" ({})", details.join(", ")));
+    }
+
+    mode
+}
+
+/// Reads the mode of every output through DRM, falling back to sysfs when
+/// the cards can't be opened. Works without a display server, e.g. on a TTY.
+pub fn get_screen_resolution(scale: Option<f64>) -> Option<String> {
+    let outputs = drm::get_outputs();
+
+    if outputs.is_empty() {
+        return get_preferred_modes(scale);
+    }
+
+    let modes: Vec<String> = outputs
+        .iter()
+        .map(|output| {
+            let monitor = edid::read_monitor_name(&output.connector);
+            let (width, height) = (output.width as u32, output.height as u32);
+            format_mode(width, height, output.refresh, scale, monitor)
+        })
+        .collect();
+
+    Some(modes.join(", "))
+}
+
+/// Scans through cat /sys/class/drm/*/modes looking for the preferred modes,
+/// which sysfs lists first. It doesn't know about refresh rates, though.
+fn get_preferred_modes(scale: Option<f64>) -> Option<String> {
     let mut resolutions = vec![];
 
     // Read all entries  "/sys/class/drm/"
-    'outer: for entry in fs::read_dir("/sys/class/drm/").ok()? {
+    for entry in fs::read_dir("/sys/class/drm/").ok()? {
         let entry = entry.ok()?;
 
         let metadata = entry.metadata().ok()?;
@@ -14,20 +68,18 @@ pub fn get_screen_resolution() -> Option<String> {
             continue;
         }
 
-        // Path '/sys/class/drm/{entry}/enabled'
-        let enabled_path = PathBuf::from("/sys/class/drm/")
+        // Path '/sys/class/drm/{entry}/status'
+        let status_path = PathBuf::from("/sys/class/drm/")
             .join(entry.file_name())
-            .join("enabled");
-
-        // Check for the 'enabled' file
-        let _enabled = match fs::read_to_string(&enabled_path) {
-            Ok(file_text)
-                // The `enabled` file contains either "enabled" or "disabled"
-                // If the monitor is disabled then we don't have to bother reading it
-                if file_text.starts_with('e') => {},
-            // Ignore errors and disabled monitors
+            .join("status");
+
+        // The `status` file contains "connected", "disconnected" or "unknown".
+        // Connected displays count even when no CRTC drives them, e.g. on headless machines.
+        match fs::read_to_string(&status_path) {
+            Ok(file_text) if file_text.trim() == "connected" => {},
+            // Ignore errors and disconnected monitors
             _ => continue,
-        };
+        }
 
         // Path '/sys/class/drm/{entry}/modes'
         let file_path = PathBuf::from("/sys/class/drm/")
@@ -40,31 +92,31 @@ pub fn get_screen_resolution() -> Option<String> {
             _ => continue,
         };
 
-        for resolution in file_text.lines() {
-            // Given a string like "1366x768", we want to return "768p".
-            match resolution.split('x').nth(1) {
-                Some(resolution_height) => match resolution_height.parse::<i32>() {
-                    Ok(number) => {
-                        resolutions.push(number);
-                        continue 'outer;
-                    },
-                    Err(_) => unreachable!("Bad number :( bad file :("),
-                },
-                // TODO: Is this really unreacheable?
-                None => unreachable!("There's some weird stuff inside of your files mate"),
-            }
+        // Given a string like "1366x768", we want (1366, 768)
+        let mode = file_text.lines().next().and_then(|mode| {
+            let (width, height) = mode.split_once('x')?;
+            // Interlaced modes end with an 'i', e.g. "1920x1080i"
+            let height = height.trim_end_matches('i');
+            Some((width.parse::<u32>().ok()?, height.parse::<u32>().ok()?))
+        });
+
+        if let Some((width, height)) = mode {
+            let monitor = edid::read_monitor_name(&entry.file_name().to_string_lossy());
+            resolutions.push((width, height, monitor));
         }
     }
 
-    resolutions.sort_unstable();
+    // Largest first
+    resolutions.sort_unstable_by(|a, b| b.cmp(a));
 
     if resolutions.is_empty() {
         None
     } else {
-        Some(
-            resolutions
-                .into_iter()
-                .rfold("".to_string(), |res, val| format!("{} {}p", res, val)),
-        )
+        let modes: Vec<String> = resolutions
+            .into_iter()
+            .map(|(width, height, monitor)| format_mode(width, height, 0.0, scale, monitor))
+            .collect();
+
+        Some(modes.join(", "))
     }
 }
@@ -0,0 +1,337 @@
+use crate::{edid, screenres::format_mode};
+
+use std::{
+    env, fs,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// The wl_display singleton always has the first object ID
+const DISPLAY_ID: u32 = 1;
+
+// Requests
+const DISPLAY_SYNC: u16 = 0;
+const DISPLAY_GET_REGISTRY: u16 = 1;
+const REGISTRY_BIND: u16 = 0;
+const XDG_OUTPUT_MANAGER_GET_XDG_OUTPUT: u16 = 1;
+
+// Events
+const DISPLAY_ERROR: u16 = 0;
+const REGISTRY_GLOBAL: u16 = 0;
+const CALLBACK_DONE: u16 = 0;
+const OUTPUT_GEOMETRY: u16 = 0;
+const OUTPUT_MODE: u16 = 1;
+const OUTPUT_SCALE: u16 = 3;
+const OUTPUT_NAME: u16 = 4;
+const XDG_OUTPUT_LOGICAL_SIZE: u16 = 1;
+const XDG_OUTPUT_NAME: u16 = 3;
+
+/// Set in the flags of wl_output.mode for the mode in use
+const MODE_CURRENT: u32 = 0x1;
+
+/// Arguments of a request, as encoded on the wire
+enum Arg<'a> {
+    Uint(u32),
+    Str(&'a str),
+}
+
+struct Event {
+    object: u32,
+    opcode: u16,
+    body:   Vec<u8>,
+}
+
+/// Reads the arguments of an event, which are 32-bit words in native byte order
+struct ArgReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ArgReader<'a> {
+    fn uint(&mut self) -> Option<u32> {
+        let word = self.bytes.get(..4)?;
+        self.bytes = &self.bytes[4..];
+        Some(u32::from_ne_bytes([word[0], word[1], word[2], word[3]]))
+    }
+
+    fn int(&mut self) -> Option<i32> {
+        self.uint().map(|word| word as i32)
+    }
+
+    /// Strings are prefixed by their length, which includes the NUL, and padded to 32 bits
+    fn string(&mut self) -> Option<String> {
+        let len = self.uint()? as usize;
+        let padded_len = (len + 3) & !3;
+        let bytes = self.bytes.get(..padded_len)?;
+        self.bytes = &self.bytes[padded_len..];
+
+        let text = bytes[..len.saturating_sub(1)].to_vec();
+        Some(String::from_utf8_lossy(&text).into_owned())
+    }
+}
+
+#[derive(Default)]
+struct Output {
+    id:             u32,
+    // Connector name, e.g. "DP-1", from wl_output v4 or xdg_output v2
+    name:           Option<String>,
+    make:           String,
+    model:          String,
+    width:          i32,
+    height:         i32,
+    // Refresh rate, in mHz
+    refresh:        i32,
+    // Integer scale factor, which clients render their buffers at
+    scale:          i32,
+    // Size of the output in the compositor's coordinates, from xdg_output
+    logical_width:  i32,
+    logical_height: i32,
+}
+
+impl Output {
+    /// Fractional scaling is only seen by comparing the mode to the logical size
+    fn scale(&self) -> f64 {
+        // Rotated outputs swap their logical width and height
+        let size = self.width.max(self.height);
+        let logical_size = self.logical_width.max(self.logical_height);
+
+        if logical_size > 0 {
+            size as f64 / logical_size as f64
+        } else {
+            self.scale.max(1) as f64
+        }
+    }
+}
+
+struct Connection {
+    stream:  UnixStream,
+    last_id: u32,
+}
+
+impl Connection {
+    fn connect() -> Option<Connection> {
+        let display = env::var_os("WAYLAND_DISPLAY")?;
+        let display = PathBuf::from(display);
+
+        // WAYLAND_DISPLAY is either a path or a socket name within XDG_RUNTIME_DIR
+        let socket = if display.is_absolute() {
+            display
+        } else {
+            PathBuf::from(env::var_os("XDG_RUNTIME_DIR")?).join(display)
+        };
+
+        let stream = UnixStream::connect(socket).ok()?;
+        // Don't hang forever on a compositor which stopped responding
+        stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+
+        Some(Connection { stream, last_id: DISPLAY_ID })
+    }
+
+    fn new_id(&mut self) -> u32 {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    fn send(&mut self, object: u32, opcode: u16, args: &[Arg]) -> Option<()> {
+        let mut body = vec![];
+        for arg in args {
+            match arg {
+                Arg::Uint(value) => body.extend_from_slice(&value.to_ne_bytes()),
+                Arg::Str(text) => {
+                    body.extend_from_slice(&(text.len() as u32 + 1).to_ne_bytes());
+                    body.extend_from_slice(text.as_bytes());
+                    body.push(0);
+                    while body.len() % 4 != 0 {
+                        body.push(0);
+                    }
+                },
+            }
+        }
+
+        // The header holds the object, then the message size and the opcode
+        let size = (body.len() + 8) as u32;
+        let mut message = object.to_ne_bytes().to_vec();
+        message.extend_from_slice(&((size << 16) | opcode as u32).to_ne_bytes());
+        message.extend_from_slice(&body);
+
+        self.stream.write_all(&message).ok()
+    }
+
+    fn read_event(&mut self) -> Option<Event> {
+        let mut header = [0_u8; 8];
+        self.stream.read_exact(&mut header).ok()?;
+
+        let object = u32::from_ne_bytes([header[0], header[1], header[2], header[3]]);
+        let size_and_opcode = u32::from_ne_bytes([header[4], header[5], header[6], header[7]]);
+        let size = (size_and_opcode >> 16) as usize;
+
+        let mut body = vec![0_u8; size.checked_sub(8)?];
+        self.stream.read_exact(&mut body).ok()?;
+
+        Some(Event { object, opcode: size_and_opcode as u16, body })
+    }
+
+    /// Handles every event until the compositor has processed all requests sent so far
+    fn roundtrip(&mut self, mut handle: impl FnMut(&Event)) -> Option<()> {
+        let callback = self.new_id();
+        self.send(DISPLAY_ID, DISPLAY_SYNC, &[Arg::Uint(callback)])?;
+
+        loop {
+            let event = self.read_event()?;
+            if event.object == callback && event.opcode == CALLBACK_DONE {
+                return Some(());
+            }
+            // The compositor closes the connection after a protocol error
+            if event.object == DISPLAY_ID && event.opcode == DISPLAY_ERROR {
+                return None;
+            }
+            handle(&event);
+        }
+    }
+}
+
+/// Names the monitor from its EDID, which compositors don't relay in full,
+/// or from the make and model announced by the compositor
+fn monitor_name(output: &Output) -> Option<String> {
+    let from_edid = output.name.as_ref().and_then(|name| {
+        fs::read_dir("/sys/class/drm/")
+            .ok()?
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            // Connectors are prefixed by their card, e.g. "card0-DP-1"
+            .find(|connector| connector.split_once('-').is_some_and(|(_, rest)| rest == name))
+            .and_then(|connector| edid::read_monitor_name(&connector))
+    });
+
+    from_edid.or_else(|| {
+        let model = output.model.trim();
+        // e.g. "Dell Inc.", of which the model usually repeats the first word
+        let brand = output.make.split_whitespace().next().unwrap_or_default();
+
+        if model.is_empty() || model == "unknown" {
+            None
+        } else if model.to_lowercase().starts_with(&brand.to_lowercase()) {
+            Some(model.to_string())
+        } else {
+            Some(format!("{} {}", output.make.trim(), model))
+        }
+    })
+}
+
+/// Asks the compositor for the current mode of each output,
+/// e.g. "2560x1440 @ 165 Hz (Dell S2721DGF)"
+pub fn get_screen_resolution() -> Option<String> {
+    let mut connection = Connection::connect()?;
+
+    let registry = connection.new_id();
+    connection.send(DISPLAY_ID, DISPLAY_GET_REGISTRY, &[Arg::Uint(registry)])?;
+
+    // (global name, interface, version)
+    let mut globals: Vec<(u32, String, u32)> = vec![];
+    connection.roundtrip(|event| {
+        if event.object == registry && event.opcode == REGISTRY_GLOBAL {
+            let mut args = ArgReader { bytes: &event.body };
+            if let (Some(name), Some(interface), Some(version)) =
+                (args.uint(), args.string(), args.uint())
+            {
+                globals.push((name, interface, version));
+            }
+        }
+    })?;
+
+    // Binding a global gives it an ID, and must state the interface and version to use
+    let bind = |connection: &mut Connection, name: u32, interface: &str, version: u32| {
+        let id = connection.new_id();
+        let args = [Arg::Uint(name), Arg::Str(interface), Arg::Uint(version), Arg::Uint(id)];
+        connection.send(registry, REGISTRY_BIND, &args).map(|_| id)
+    };
+
+    let mut outputs: Vec<Output> = vec![];
+    for (name, interface, version) in globals.iter().filter(|global| global.1 == "wl_output") {
+        let version = (*version).min(4);
+        let id = bind(&mut connection, *name, interface, version)?;
+        outputs.push(Output { id, ..Default::default() });
+    }
+
+    // xdg_output names the outputs on compositors which only implement wl_output v3
+    let mut xdg_outputs: Vec<(u32, usize)> = vec![];
+    let manager = globals.iter().find(|global| global.1 == "zxdg_output_manager_v1");
+    if let Some((name, interface, version)) = manager {
+        let manager = bind(&mut connection, *name, interface, (*version).min(3))?;
+
+        for (index, output) in outputs.iter().enumerate() {
+            let xdg_output = connection.new_id();
+            let args = [Arg::Uint(xdg_output), Arg::Uint(output.id)];
+            connection.send(manager, XDG_OUTPUT_MANAGER_GET_XDG_OUTPUT, &args)?;
+            xdg_outputs.push((xdg_output, index));
+        }
+    }
+
+    connection.roundtrip(|event| {
+        let mut args = ArgReader { bytes: &event.body };
+
+        if let Some((_, index)) = xdg_outputs.iter().find(|(id, _)| *id == event.object) {
+            let output = &mut outputs[*index];
+            match event.opcode {
+                XDG_OUTPUT_LOGICAL_SIZE => {
+                    output.logical_width = args.int().unwrap_or_default();
+                    output.logical_height = args.int().unwrap_or_default();
+                },
+                XDG_OUTPUT_NAME => output.name = output.name.take().or_else(|| args.string()),
+                _ => {},
+            }
+            return;
+        }
+
+        let output = match outputs.iter_mut().find(|output| output.id == event.object) {
+            Some(output) => output,
+            None => return,
+        };
+
+        match event.opcode {
+            OUTPUT_GEOMETRY => {
+                // x, y, physical width, physical height and subpixel come first
+                for _ in 0..5 {
+                    args.int();
+                }
+                output.make = args.string().unwrap_or_default();
+                output.model = args.string().unwrap_or_default();
+            },
+            OUTPUT_MODE => {
+                if let (Some(flags), Some(width), Some(height), Some(refresh)) =
+                    (args.uint(), args.int(), args.int(), args.int())
+                {
+                    if flags & MODE_CURRENT != 0 {
+                        output.width = width;
+                        output.height = height;
+                        output.refresh = refresh;
+                    }
+                }
+            },
+            OUTPUT_SCALE => output.scale = args.int().unwrap_or(1),
+            OUTPUT_NAME => output.name = args.string(),
+            _ => {},
+        }
+    })?;
+
+    let modes: Vec<String> = outputs
+        .iter()
+        .filter(|output| output.width > 0 && output.height > 0)
+        .map(|output| {
+            format_mode(
+                output.width as u32,
+                output.height as u32,
+                output.refresh as f64 / 1000.0,
+                Some(output.scale()),
+                monitor_name(output),
+            )
+        })
+        .collect();
+
+    if modes.is_empty() {
+        None
+    } else {
+        Some(modes.join(", "))
+    }
+}
@@ -1,28 +1,116 @@
+use crate::{edid, screenres::format_mode};
+
 use x11::{
-    xlib::{Display, XDefaultRootWindow, XOpenDisplay},
+    xlib::{
+        Atom, Display, XCloseDisplay, XDefaultRootWindow, XFree, XInternAtom, XOpenDisplay,
+        XResourceManagerString,
+    },
     xrandr::{
-        XRRCrtcInfo, XRRFreeCrtcInfo, XRRFreeScreenResources, XRRGetCrtcInfo,
-        XRRGetScreenResources, XRRScreenResources,
+        RROutput, XRRCrtcInfo, XRRFreeCrtcInfo, XRRFreeScreenResources, XRRGetCrtcInfo,
+        XRRGetOutputProperty, XRRGetScreenResources, XRRModeInfo, XRRScreenResources,
     },
 };
 
-use std::{ptr, vec::Vec};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_int, c_uchar, c_ulong},
+    ptr, slice,
+    vec::Vec,
+};
+
+const RR_INTERLACE: u64 = 0x10;
+const RR_DOUBLE_SCAN: u64 = 0x20;
+
+/// Computes the refresh rate of a mode from its pixel clock
+fn refresh_rate(mode: &XRRModeInfo) -> f64 {
+    if mode.hTotal == 0 || mode.vTotal == 0 {
+        return 0.0;
+    }
+
+    let mut refresh = mode.dotClock as f64 / (mode.hTotal as f64 * mode.vTotal as f64);
+    if mode.modeFlags as u64 & RR_INTERLACE != 0 {
+        refresh *= 2.0;
+    }
+    if mode.modeFlags as u64 & RR_DOUBLE_SCAN != 0 {
+        refresh /= 2.0;
+    }
+
+    refresh
+}
+
+/// Reads the name of the monitor from the EDID property of an output
+unsafe fn monitor_name(display: *mut Display, output: RROutput) -> Option<String> {
+    let property_name = CString::new("EDID").ok()?;
+    let property = XInternAtom(display, property_name.as_ptr(), 1);
+    if property == 0 {
+        return None;
+    }
+
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut items: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut value: *mut c_uchar = ptr::null_mut();
 
-pub unsafe fn get_screen_resolution() -> Vec<String> {
+    // The length is given in 32-bit units, enough for the base block and an extension
+    let status = XRRGetOutputProperty(
+        display,
+        output,
+        property,
+        0,
+        64,
+        0,
+        0,
+        0,
+        &mut actual_type,
+        &mut actual_format,
+        &mut items,
+        &mut bytes_after,
+        &mut value,
+    );
+
+    if value.is_null() {
+        return None;
+    }
+
+    let name = if status == 0 && actual_format == 8 {
+        edid::monitor_name(slice::from_raw_parts(value, items as usize))
+    } else {
+        None
+    };
+    XFree(value as *mut _);
+
+    name
+}
+
+pub unsafe fn get_screen_resolution(scale: Option<f64>) -> Vec<String> {
     let mut resolutions = vec![];
 
     let display: *mut Display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return resolutions;
+    }
 
     let screens: *mut XRRScreenResources =
         XRRGetScreenResources(display, XDefaultRootWindow(display));
 
     let screens_no = (*screens).ncrtc as isize;
+    let modes: &[XRRModeInfo] = slice::from_raw_parts((*screens).modes, (*screens).nmode as usize);
 
     for i in 0..screens_no {
         let info: *mut XRRCrtcInfo = XRRGetCrtcInfo(display, screens, *(*screens).crtcs.offset(i));
         match ((*info).width, (*info).height) {
             (wdt, hgt) if wdt != 0 && hgt != 0 => {
-                resolutions.push(format!("{}p", hgt));
+                let refresh = modes
+                    .iter()
+                    .find(|mode| mode.id == (*info).mode)
+                    .map_or(0.0, refresh_rate);
+                let monitor = if (*info).noutput > 0 {
+                    monitor_name(display, *(*info).outputs)
+                } else {
+                    None
+                };
+                resolutions.push(format_mode(wdt, hgt, refresh, scale, monitor));
             },
             (_, _) => {},
         };
@@ -30,6 +118,30 @@ pub unsafe fn get_screen_resolution() -> Vec<String> {
     }
 
     XRRFreeScreenResources(screens);
+    XCloseDisplay(display);
 
     resolutions
 }
+
+/// Reads the DPI set for Xft in the X resources, e.g. "Xft.dpi:\t192"
+pub unsafe fn get_xft_dpi() -> Option<f64> {
+    let display: *mut Display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return None;
+    }
+
+    let resources = XResourceManagerString(display);
+    let dpi = if resources.is_null() {
+        None
+    } else {
+        CStr::from_ptr(resources)
+            .to_string_lossy()
+            .lines()
+            .find_map(|line| line.strip_prefix("Xft.dpi:"))
+            .and_then(|dpi| dpi.trim().parse().ok())
+    };
+
+    XCloseDisplay(display);
+
+    dpi
+}
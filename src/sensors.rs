@@ -0,0 +1,199 @@
+use crate::util::read_trimmed;
+
+use std::{fs, path::PathBuf};
+
+/// Drivers of the chips which measure the CPU temperature:
+/// Intel, AMD (k10temp and the out-of-tree zenpower) and ARM SoCs
+const CPU_CHIPS: &[&str] = &["coretemp", "k10temp", "zenpower", "cpu_thermal", "cpu-thermal"];
+
+/// Labels of the sensor which best represents the whole CPU, in order of preference
+const CPU_LABELS: &[&str] = &["Package id 0", "Tdie", "Tctl"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SensorKind {
+    // In °C
+    Temperature,
+    // In volts
+    Voltage,
+    // In RPM
+    Fan,
+}
+
+/// A single reading of a hwmon chip
+#[derive(Debug)]
+pub struct Sensor {
+    // Name of the chip's driver, e.g. "k10temp"
+    pub chip:  String,
+    // Label of the sensor, e.g. "Tctl", or its file name ("temp1") if it has none
+    pub label: String,
+    pub kind:  SensorKind,
+    pub value: f64,
+}
+
+impl Sensor {
+    /// Identifier used to select the sensor in the configuration, e.g. "k10temp/Tctl"
+    pub fn id(&self) -> String {
+        format!("{}/{}", self.chip, self.label)
+    }
+
+    /// Checks if the sensor is selected by an entry such as "k10temp/Tctl", "nvme/*" or "*"
+    pub fn matches(&self, pattern: &str) -> bool {
+        match pattern.strip_suffix("/*") {
+            Some(chip) => chip == self.chip,
+            None => pattern == "*" || pattern == self.id(),
+        }
+    }
+}
+
+/// A hardware monitoring chip, as exposed in /sys/class/hwmon
+#[derive(Debug)]
+pub struct HwmonChip {
+    // Name of the driver, e.g. "coretemp"
+    pub name: String,
+    pub dir:  PathBuf,
+}
+
+pub fn get_hwmon_chips() -> Vec<HwmonChip> {
+    let entries = match fs::read_dir("/sys/class/hwmon/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut chips: Vec<HwmonChip> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let dir = entry.path();
+            Some(HwmonChip {
+                name: read_trimmed(&dir.join("name"))?,
+                dir,
+            })
+        })
+        .collect();
+
+    chips.sort_unstable_by(|a, b| a.dir.cmp(&b.dir));
+    chips
+}
+
+impl HwmonChip {
+    /// Reads the temperature of the `temp{index}` sensor in °C
+    pub fn temp(&self, index: usize) -> Option<f64> {
+        let millidegrees: i64 = read_trimmed(&self.dir.join(format!("temp{}_input", index)))?
+            .parse()
+            .ok()?;
+
+        Some(millidegrees as f64 / 1000.0)
+    }
+
+    /// Reads all temperature, voltage and fan sensors of the chip
+    pub fn sensors(&self) -> Vec<Sensor> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        let mut sensors: Vec<Sensor> = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let file_name = entry.file_name();
+                // e.g. "temp1" out of "temp1_input"
+                let sensor = file_name.to_str()?.strip_suffix("_input")?;
+
+                let kind = if sensor.starts_with("temp") {
+                    SensorKind::Temperature
+                } else if sensor.starts_with("in") {
+                    SensorKind::Voltage
+                } else if sensor.starts_with("fan") {
+                    SensorKind::Fan
+                } else {
+                    return None;
+                };
+
+                let raw_value: i64 = read_trimmed(&entry.path())?.parse().ok()?;
+                // Temperatures are given in millidegrees Celsius, voltages in millivolts
+                // and fan speeds in RPM
+                let value = match kind {
+                    SensorKind::Temperature | SensorKind::Voltage => raw_value as f64 / 1000.0,
+                    SensorKind::Fan => raw_value as f64,
+                };
+                let label = read_trimmed(&self.dir.join(format!("{}_label", sensor)))
+                    .unwrap_or_else(|| sensor.to_string());
+
+                Some(Sensor {
+                    chip: self.name.clone(),
+                    label,
+                    kind,
+                    value,
+                })
+            })
+            .collect();
+
+        sensors.sort_unstable_by(|a, b| a.label.cmp(&b.label));
+        sensors
+    }
+
+    /// Finds the index of the temperature sensor with the given label
+    pub fn temp_index(&self, label: &str) -> Option<usize> {
+        fs::read_dir(&self.dir)
+            .ok()?
+            .filter_map(Result::ok)
+            .find_map(|entry| {
+                let file_name = entry.file_name();
+                let index = file_name
+                    .to_str()?
+                    .strip_prefix("temp")?
+                    .strip_suffix("_label")?
+                    .parse()
+                    .ok()?;
+
+                if read_trimmed(&entry.path())? == label {
+                    Some(index)
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Reads the sensors of every hwmon chip
+pub fn get_sensors() -> Vec<Sensor> {
+    get_hwmon_chips()
+        .iter()
+        .flat_map(HwmonChip::sensors)
+        .collect()
+}
+
+/// Thermal zones are the fallback for SoCs whose CPU sensor isn't exposed through hwmon
+fn get_cpu_thermal_zone_temp() -> Option<f64> {
+    fs::read_dir("/sys/class/thermal/")
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("thermal_zone"))
+        .find_map(|entry| {
+            let zone_type = read_trimmed(&entry.path().join("type"))?;
+            if !zone_type.contains("cpu") && zone_type != "x86_pkg_temp" {
+                return None;
+            }
+
+            let millidegrees: i64 = read_trimmed(&entry.path().join("temp"))?.parse().ok()?;
+            Some(millidegrees as f64 / 1000.0)
+        })
+}
+
+/// Gets the CPU temperature in °C
+pub fn get_cpu_temp() -> Option<f64> {
+    let chips = get_hwmon_chips();
+
+    let cpu_chip = chips
+        .iter()
+        .find(|chip| CPU_CHIPS.contains(&chip.name.as_str()));
+
+    let temp = cpu_chip.and_then(|chip| {
+        let index = CPU_LABELS
+            .iter()
+            .find_map(|label| chip.temp_index(label))
+            .unwrap_or(1);
+        chip.temp(index)
+    });
+
+    temp.or_else(get_cpu_thermal_zone_temp)
+}
@@ -0,0 +1,48 @@
+use crate::util::is_set;
+
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayProtocol {
+    X11,
+    Wayland,
+    Tty,
+}
+
+impl DisplayProtocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayProtocol::X11 => "X11",
+            DisplayProtocol::Wayland => "Wayland",
+            DisplayProtocol::Tty => "TTY",
+        }
+    }
+}
+
+/// Address of the client, if pulga runs in an SSH session. SSH_CONNECTION holds the
+/// addresses and ports of both ends, e.g. "192.168.0.12 51234 192.168.0.2 22"
+pub fn get_ssh_client() -> Option<String> {
+    let connection = env::var("SSH_CONNECTION").or_else(|_| env::var("SSH_CLIENT"));
+    match connection {
+        Ok(connection) => connection.split_whitespace().next().map(ToString::to_string),
+        // Only SSH_TTY survives e.g. sudo
+        Err(_) if is_set("SSH_TTY") => Some("Unknown".to_string()),
+        Err(_) => None,
+    }
+}
+
+/// Finds out which display server the session talks to. XDG_SESSION_TYPE is trusted
+/// only when its server is reachable, since it's inherited by e.g. SSH sessions and
+/// nested X servers.
+pub fn get_display_protocol() -> DisplayProtocol {
+    let has_wayland = is_set("WAYLAND_DISPLAY");
+    let has_x11 = is_set("DISPLAY");
+
+    match env::var("XDG_SESSION_TYPE").as_deref() {
+        Ok("wayland") if has_wayland => DisplayProtocol::Wayland,
+        Ok("x11") if has_x11 => DisplayProtocol::X11,
+        _ if has_wayland => DisplayProtocol::Wayland,
+        _ if has_x11 => DisplayProtocol::X11,
+        _ => DisplayProtocol::Tty,
+    }
+}
@@ -0,0 +1,91 @@
+use crate::util::{get_base, get_cache_dir};
+
+use std::{
+    fs,
+    os::unix::fs::MetadataExt,
+    process::{Command, Stdio},
+};
+
+/// Shells whose version Pulga knows how to find, by the name of their binary, with the
+/// flag printing it. Their version variables, such as $BASH_VERSION, aren't exported.
+const SHELLS: &[(&str, &str)] = &[
+    ("bash", "--version"),
+    ("zsh", "--version"),
+    ("fish", "--version"),
+    ("nu", "--version"),
+    ("tcsh", "--version"),
+    ("xonsh", "--version"),
+    ("elvish", "-version"),
+];
+
+/// Finds the version within e.g. "GNU bash, version 5.2.15(1)-release", "xonsh/0.14.0"
+/// or "v20.11.1"
+pub fn parse_version(text: &str) -> Option<String> {
+    let starts_with_digit = |word: &str| word.starts_with(|ch: char| ch.is_ascii_digit());
+    let version = text
+        .split(|ch: char| ch.is_whitespace() || ch == ',' || ch == '/')
+        .map(|word| word.strip_prefix('v').filter(|word| starts_with_digit(word)).unwrap_or(word))
+        .find(|word| starts_with_digit(word))?;
+
+    // Drops suffixes such as "(1)-release"
+    let version: String =
+        version.chars().take_while(|ch| ch.is_ascii_digit() || *ch == '.').collect();
+    Some(version.trim_end_matches('.').to_string())
+}
+
+/// Asks a program for its version, e.g. the shell
+pub fn probe_version(path: &str, flag: &str) -> Option<String> {
+    let output = Command::new(path)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    parse_version(output.lines().next()?)
+}
+
+/// The version of a shell, as it prints it. As running it takes a while, the version is
+/// cached until the binary changes, which is when the shell is upgraded.
+fn shell_version(path: &str, flag: &str) -> Option<String> {
+    // Identifies the binary, e.g. "/usr/bin/zsh 1711234567 950144"
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return probe_version(path, flag),
+    };
+    let key = format!("{} {} {}", path, metadata.mtime(), metadata.size());
+
+    let cache = get_cache_dir().map(|dir| dir.join("shell-version"));
+    if let Some(text) = cache.as_ref().and_then(|cache| fs::read_to_string(cache).ok()) {
+        if let Some((cached_key, version)) = text.split_once('\n') {
+            if cached_key == key {
+                return Some(version.to_string());
+            }
+        }
+    }
+
+    let version = probe_version(path, flag)?;
+    if let Some(cache) = cache {
+        let _ = cache.parent().map(fs::create_dir_all);
+        let _ = fs::write(cache, format!("{}\n{}", key, version));
+    }
+
+    Some(version)
+}
+
+/// Name and version of the user's login shell, e.g. "zsh 5.9", given its path
+pub fn get_shell(path: &str) -> String {
+    // From "/usr/bin/shell" to just "shell"
+    let name = get_base(path);
+
+    let version = SHELLS
+        .iter()
+        .find(|(shell, _)| *shell == name)
+        .and_then(|(_, flag)| shell_version(path, flag));
+
+    match version {
+        Some(version) => format!("{} {}", name, version),
+        None => name,
+    }
+}
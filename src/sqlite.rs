@@ -0,0 +1,283 @@
+use std::{fs::File, os::unix::fs::FileExt, path::Path};
+
+const MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// The file header, which comes before the b-tree header of the first page
+const HEADER_SIZE: usize = 100;
+
+// Kinds of b-tree pages
+const INTERIOR_TABLE: u8 = 0x05;
+const LEAF_TABLE: u8 = 0x0D;
+
+/// Interior pages nest at most this deep, in files that aren't corrupt
+const MAX_DEPTH: usize = 32;
+
+/// A read-only view of an SQLite database, which only reads the pages it needs,
+/// as databases such as the RPM one hold hundreds of megabytes of package headers
+pub struct Database {
+    file:        File,
+    page_size:   usize,
+    // The page size minus the bytes some extensions reserve at the end of each page
+    usable_size: usize,
+}
+
+/// A value of a record, where text is left as bytes, as are blobs.
+/// Floats are read as NULLs, as nothing reads them.
+#[derive(Debug, Clone, Copy)]
+pub enum Value<'a> {
+    Null,
+    Integer(i64),
+    Bytes(&'a [u8]),
+}
+
+/// Decodes a variable-length integer, returning it and its length
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0_u64;
+    for (index, &byte) in bytes.iter().take(9).enumerate() {
+        // The ninth byte contributes all of its bits
+        if index == 8 {
+            return Some(((value << 8) | byte as u64, 9));
+        }
+        value = (value << 7) | (byte & 0x7F) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+    }
+
+    None
+}
+
+/// Size of a value in a record, given its serial type
+fn serial_type_size(serial_type: u64) -> usize {
+    match serial_type {
+        0 | 8 | 9 => 0,
+        1..=4 => serial_type as usize,
+        5 => 6,
+        6 | 7 => 8,
+        // Blobs and strings, respectively
+        _ if serial_type >= 12 => (serial_type as usize - 12) / 2,
+        _ => 0,
+    }
+}
+
+fn read_be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |value, &byte| (value << 8) | byte as u64)
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Option<Database> {
+        let file = File::open(path).ok()?;
+
+        let mut header = [0_u8; HEADER_SIZE];
+        file.read_exact_at(&mut header, 0).ok()?;
+        if &header[..16] != MAGIC {
+            return None;
+        }
+
+        // A page size of 1 stands for 65536, which doesn't fit in 16 bits
+        let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+            1 => 65536,
+            size => size as usize,
+        };
+
+        let usable_size = page_size.checked_sub(header[20] as usize)?;
+
+        Some(Database { file, page_size, usable_size })
+    }
+
+    fn read_page(&self, number: u64) -> Option<Vec<u8>> {
+        let mut page = vec![0_u8; self.page_size];
+        let offset = number.checked_sub(1)? * self.page_size as u64;
+        self.file.read_exact_at(&mut page, offset).ok()?;
+        Some(page)
+    }
+
+    /// Calls `visit` with every leaf page of a table b-tree, and the offset of its header
+    fn visit_leaves(
+        &self,
+        number: u64,
+        depth: usize,
+        visit: &mut impl FnMut(&[u8], usize),
+    ) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let page = self.read_page(number)?;
+        // Only the first page holds the file header
+        let header = if number == 1 { HEADER_SIZE } else { 0 };
+        let cells = u16::from_be_bytes([*page.get(header + 3)?, *page.get(header + 4)?]) as usize;
+
+        match page[header] {
+            LEAF_TABLE => visit(&page, header),
+            INTERIOR_TABLE => {
+                // Each cell starts with the page number of its left child
+                for cell in 0..cells {
+                    let pointer = header + 12 + cell * 2;
+                    let offset = read_be(page.get(pointer..pointer + 2)?) as usize;
+                    let child = read_be(page.get(offset..offset + 4)?);
+                    self.visit_leaves(child, depth + 1, visit)?;
+                }
+
+                let rightmost = read_be(page.get(header + 8..header + 12)?);
+                self.visit_leaves(rightmost, depth + 1, visit)?;
+            },
+            _ => return None,
+        }
+
+        Some(())
+    }
+
+    /// Reads the payload of a cell of a leaf page, whose end may spill into a chain of
+    /// overflow pages, as the RPM package headers do
+    fn read_payload(&self, page: &[u8], offset: usize) -> Option<Vec<u8>> {
+        // The cell starts with the size of its payload and its row ID
+        let cell = page.get(offset..)?;
+        let (payload_size, payload_size_len) = read_varint(cell)?;
+        let (_, rowid_len) = read_varint(cell.get(payload_size_len..)?)?;
+        let payload_start = payload_size_len + rowid_len;
+
+        // How much of the payload stays in the leaf page, as defined by the file format
+        let payload_size = payload_size as usize;
+        let max_local = self.usable_size - 35;
+        let min_local = (self.usable_size - 12) * 32 / 255 - 23;
+        let local_size = if payload_size <= max_local {
+            payload_size
+        } else {
+            let size = min_local + (payload_size - min_local) % (self.usable_size - 4);
+            if size <= max_local {
+                size
+            } else {
+                min_local
+            }
+        };
+
+        let mut payload = cell.get(payload_start..payload_start + local_size)?.to_vec();
+        if local_size == payload_size {
+            return Some(payload);
+        }
+
+        // Each overflow page starts with the number of the next one
+        let pointer = payload_start + local_size;
+        let mut next = read_be(cell.get(pointer..pointer + 4)?);
+        while payload.len() < payload_size {
+            let page = self.read_page(next)?;
+            let len = (payload_size - payload.len()).min(self.usable_size - 4);
+            payload.extend_from_slice(page.get(4..4 + len)?);
+            next = read_be(&page[..4]);
+        }
+
+        Some(payload)
+    }
+
+    /// Calls `visit` with the payload of every row of a table
+    fn visit_rows(&self, root_page: u64, visit: &mut impl FnMut(&[u8])) -> Option<()> {
+        self.visit_leaves(root_page, 0, &mut |page, header| {
+            let cells = read_be(&page[header + 3..header + 5]) as usize;
+            for cell in 0..cells {
+                let pointer = header + 8 + cell * 2;
+                let payload = page
+                    .get(pointer..pointer + 2)
+                    .and_then(|pointer| self.read_payload(page, read_be(pointer) as usize));
+                if let Some(payload) = payload {
+                    visit(&payload);
+                }
+            }
+        })
+    }
+
+    /// Finds the root page of a table in the schema, whose rows are
+    /// (type, name, tbl_name, rootpage, sql)
+    fn find_root_page(&self, table: &str) -> Option<u64> {
+        let mut root_page = None;
+
+        self.visit_rows(1, &mut |payload| {
+            if let Some((name, root)) = read_schema_row(payload) {
+                if name == table {
+                    root_page = Some(root);
+                }
+            }
+        })?;
+
+        root_page
+    }
+
+    /// Counts the rows of a table, e.g. the packages of the RPM database
+    pub fn count_rows(&self, table: &str) -> Option<usize> {
+        let root_page = self.find_root_page(table)?;
+
+        let mut rows = 0;
+        self.visit_leaves(root_page, 0, &mut |page, header| {
+            rows += read_be(&page[header + 3..header + 5]) as usize;
+        })?;
+
+        Some(rows)
+    }
+
+    /// Calls `visit` with the values of every row of a table, e.g. the headers kept in
+    /// the second column of the RPM "Packages" table
+    pub fn visit_table(&self, table: &str, visit: &mut impl FnMut(&[Value])) {
+        let root_page = match self.find_root_page(table) {
+            Some(root_page) => root_page,
+            None => return,
+        };
+
+        self.visit_rows(root_page, &mut |payload| {
+            if let Some(values) = read_record(payload) {
+                visit(&values);
+            }
+        });
+    }
+}
+
+/// Reads the values of a record
+fn read_record(record: &[u8]) -> Option<Vec<Value<'_>>> {
+    // The record starts with the serial types of its values
+    let (header_size, mut offset) = read_varint(record)?;
+    let mut serial_types = vec![];
+    while offset < header_size as usize {
+        let (serial_type, len) = read_varint(record.get(offset..)?)?;
+        serial_types.push(serial_type);
+        offset += len;
+    }
+
+    let mut values = vec![];
+    let mut value_offset = header_size as usize;
+    for serial_type in serial_types {
+        let size = serial_type_size(serial_type);
+        let bytes = record.get(value_offset..value_offset + size)?;
+        value_offset += size;
+
+        values.push(match serial_type {
+            1..=6 => {
+                // Integers are signed, so their first bit is extended
+                let shift = 64 - 8 * size as u32;
+                Value::Integer(((read_be(bytes) << shift) as i64) >> shift)
+            },
+            8 => Value::Integer(0),
+            9 => Value::Integer(1),
+            _ if serial_type >= 12 => Value::Bytes(bytes),
+            _ => Value::Null,
+        });
+    }
+
+    Some(values)
+}
+
+/// Reads the name and root page of a row of the schema
+fn read_schema_row(record: &[u8]) -> Option<(String, u64)> {
+    let values = read_record(record)?;
+    if values.len() < 4 {
+        return None;
+    }
+
+    let name = match values[1] {
+        Value::Bytes(name) => String::from_utf8_lossy(name).into_owned(),
+        _ => return None,
+    };
+    let root_page = match values[3] {
+        Value::Integer(root_page) => root_page as u64,
+        _ => return None,
+    };
+
+    Some((name, root_page))
+}
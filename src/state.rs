@@ -0,0 +1,19 @@
+use crate::util::get_state_dir;
+
+use std::fs;
+
+/// Reads what an earlier run of pulga kept under `name`, e.g. "uptime-record"
+pub fn load(name: &str) -> Option<String> {
+    fs::read_to_string(get_state_dir()?.join("newfetch").join(name)).ok()
+}
+
+/// Keeps `contents` under `name` for later runs. The file is replaced at once, so that
+/// runs at the same time never read it half-written.
+pub fn store(name: &str, contents: &str) -> Option<()> {
+    let dir = get_state_dir()?.join("newfetch");
+    fs::create_dir_all(&dir).ok()?;
+
+    let temporary = dir.join(format!(".{}.{}", name, std::process::id()));
+    fs::write(&temporary, contents).ok()?;
+    fs::rename(&temporary, dir.join(name)).ok()
+}
@@ -0,0 +1,92 @@
+use crate::util::{get_selected_option, read_u64};
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A zram device used as swap
+#[derive(Debug)]
+pub struct ZramDevice {
+    // Size of the device as seen by the swap subsystem, in bytes
+    pub disk_size:       u64,
+    // Size of the data stored, before compression
+    pub orig_data_size:  u64,
+    // Size of the data stored, after compression
+    pub compr_data_size: u64,
+    // e.g. "zstd"
+    pub algorithm:       Option<String>,
+}
+
+/// Statistics of zswap, the compressed cache for swap pages, in bytes
+#[derive(Debug)]
+pub struct Zswap {
+    // Size of the pages held in the cache, before compression
+    pub stored:     u64,
+    // Memory used by the cache
+    pub compressed: u64,
+}
+
+/// Paths of the devices and files currently used as swap, from /proc/swaps
+pub fn get_swap_areas() -> Vec<PathBuf> {
+    let swaps = match fs::read_to_string("/proc/swaps") {
+        Ok(swaps) => swaps,
+        Err(_) => return vec![],
+    };
+
+    swaps
+        .lines()
+        // The first line is a header
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// The selected algorithm is the one between brackets, e.g. "lzo lz4 [zstd]"
+fn read_algorithm(block_dir: &Path) -> Option<String> {
+    let algorithms = fs::read_to_string(block_dir.join("comp_algorithm")).ok()?;
+
+    get_selected_option(&algorithms).map(ToString::to_string)
+}
+
+fn get_zram_device(name: &str) -> Option<ZramDevice> {
+    let block_dir = Path::new("/sys/block/").join(name);
+
+    // mm_stat starts with "orig_data_size compr_data_size mem_used_total ..."
+    let mm_stat = fs::read_to_string(block_dir.join("mm_stat")).ok()?;
+    let mut columns = mm_stat.split_whitespace().map(|column| column.parse::<u64>().ok());
+
+    Some(ZramDevice {
+        disk_size: read_u64(&block_dir.join("disksize"))?,
+        orig_data_size: columns.next()??,
+        compr_data_size: columns.next()??,
+        algorithm: read_algorithm(&block_dir),
+    })
+}
+
+/// Lists the zram devices being used as swap
+pub fn get_zram_swap_devices() -> Vec<ZramDevice> {
+    get_swap_areas()
+        .iter()
+        .filter_map(|path| path.file_name()?.to_str())
+        .filter(|name| name.starts_with("zram"))
+        .filter_map(get_zram_device)
+        .collect()
+}
+
+pub fn zswap_enabled() -> bool {
+    fs::read_to_string("/sys/module/zswap/parameters/enabled")
+        .is_ok_and(|enabled| enabled.trim() == "Y")
+}
+
+/// Reads the zswap statistics from debugfs, which is usually only readable by root.
+/// Newer kernels (5.19+) report them in /proc/meminfo instead.
+pub fn get_zswap_from_debugfs(page_size: u64) -> Option<Zswap> {
+    let debugfs_dir = Path::new("/sys/kernel/debug/zswap/");
+
+    Some(Zswap {
+        stored: read_u64(&debugfs_dir.join("stored_pages"))? * page_size,
+        compressed: read_u64(&debugfs_dir.join("pool_total_size"))?,
+    })
+}
@@ -0,0 +1,56 @@
+use crate::dbus::Connection;
+
+const SYSTEMD: &str = "org.freedesktop.systemd1";
+const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER: &str = "org.freedesktop.systemd1.Manager";
+
+fn format_seconds(microseconds: u64) -> String {
+    format!("{:.1}s", microseconds as f64 / 1_000_000.0)
+}
+
+/// How long the last boot took, as systemd-analyze reports it, e.g.
+/// "4.2s (firmware 1.8s + loader 0.9s + kernel 1.5s)". systemd keeps the monotonic time at
+/// which each stage ended, counting the firmware and the boot loader back from the kernel.
+pub fn get_boot_time() -> Option<String> {
+    let mut bus = Connection::system()?;
+    let mut get = |property| bus.get_unsigned(SYSTEMD, MANAGER_PATH, MANAGER, property);
+
+    let firmware = get("FirmwareTimestampMonotonic")?;
+    let loader = get("LoaderTimestampMonotonic")?;
+    let initrd = get("InitRDTimestampMonotonic")?;
+    let userspace = get("UserspaceTimestampMonotonic")?;
+    let finish = get("FinishTimestampMonotonic")?;
+
+    // The system is still booting
+    if finish == 0 {
+        return None;
+    }
+
+    let (kernel, initrd) = if initrd > 0 {
+        (initrd, userspace.saturating_sub(initrd))
+    } else {
+        (userspace, 0)
+    };
+    let stages = [
+        ("firmware", firmware.saturating_sub(loader)),
+        ("loader", loader),
+        ("kernel", kernel),
+        ("initrd", initrd),
+        ("userspace", finish.saturating_sub(userspace)),
+    ];
+
+    let total = stages.iter().map(|(_, time)| time).sum();
+    let stages: Vec<String> = stages
+        .iter()
+        .filter(|(_, time)| *time > 0)
+        .map(|(stage, time)| format!("{} {}", stage, format_seconds(*time)))
+        .collect();
+
+    Some(format!("{} ({})", format_seconds(total), stages.join(" + ")))
+}
+
+/// Number of units which failed, as `systemctl --failed` lists them
+pub fn get_failed_units() -> Option<u64> {
+    let mut bus = Connection::system()?;
+    bus.get_unsigned(SYSTEMD, MANAGER_PATH, MANAGER, "NFailedUnits")
+}
@@ -0,0 +1,51 @@
+use std::fs;
+
+/// What each bit of the taint mask means, with the letter the kernel shows for it in oopses,
+/// as documented in Documentation/admin-guide/tainted-kernels.rst
+const TAINT_FLAGS: &[(char, &str)] = &[
+    ('P', "proprietary module"),
+    ('F', "force-loaded module"),
+    ('S', "out-of-spec system"),
+    ('R', "force-unloaded module"),
+    ('M', "machine check exception"),
+    ('B', "bad page"),
+    ('U', "tainted by userspace"),
+    ('D', "recent oops"),
+    ('A', "ACPI table overridden"),
+    ('W', "kernel warning"),
+    ('C', "staging driver"),
+    ('I', "firmware bug workaround"),
+    ('O', "out-of-tree module"),
+    ('E', "unsigned module"),
+    ('L', "soft lockup"),
+    ('K', "live patched"),
+    ('X', "auxiliary taint"),
+    ('T', "randomized struct layout"),
+    ('N', "in-kernel test"),
+    ('J', "fwctl debug operation"),
+];
+
+/// Explains why the kernel is tainted, if it is, e.g.
+/// "proprietary module, out-of-tree module (PO)"
+pub fn get_taint() -> Option<String> {
+    let mask: u64 = fs::read_to_string("/proc/sys/kernel/tainted").ok()?.trim().parse().ok()?;
+    if mask == 0 {
+        return None;
+    }
+
+    let (mut letters, mut reasons) = (String::new(), vec![]);
+    for (bit, (letter, reason)) in TAINT_FLAGS.iter().enumerate() {
+        if mask & (1 << bit) != 0 {
+            letters.push(*letter);
+            reasons.push(*reason);
+        }
+    }
+
+    // Bits that newer kernels may define
+    let unknown = mask >> TAINT_FLAGS.len();
+    if unknown != 0 {
+        reasons.push("unknown reason");
+    }
+
+    Some(format!("{} ({})", reasons.join(", "), letters))
+}
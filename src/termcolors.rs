@@ -0,0 +1,191 @@
+use crate::session;
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    mem,
+    os::unix::io::{AsRawFd, RawFd},
+    time::{Duration, Instant},
+};
+
+/// How long to wait for the terminal to answer, as some never do
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Asks for the foreground (OSC 10) and background (OSC 11) colors, then for the
+/// device attributes (DA1), which every terminal answers, to know when to stop reading
+const QUERY: &[u8] = b"\x1b]10;?\x1b\\\x1b]11;?\x1b\\\x1b[c";
+
+/// Well-known color schemes, by their background and foreground colors
+const SCHEMES: &[(&str, &str, &str)] = &[
+    ("Catppuccin Latte", "#eff1f5", "#4c4f69"),
+    ("Catppuccin Mocha", "#1e1e2e", "#cdd6f4"),
+    ("Dracula", "#282a36", "#f8f8f2"),
+    ("Everforest", "#2d353b", "#d3c6aa"),
+    ("Gruvbox Dark", "#282828", "#ebdbb2"),
+    ("Gruvbox Light", "#fbf1c7", "#3c3836"),
+    ("Nord", "#2e3440", "#d8dee9"),
+    ("One Dark", "#282c34", "#abb2bf"),
+    ("Rosé Pine", "#191724", "#e0def4"),
+    ("Solarized Dark", "#002b36", "#839496"),
+    ("Solarized Light", "#fdf6e3", "#657b83"),
+    ("Tokyo Night", "#1a1b26", "#c0caf5"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(u8, u8, u8);
+
+impl Rgb {
+    /// Parses the colors reported by terminals, e.g. "rgb:2e2e/3434/4040",
+    /// whose components may have from 1 to 4 hex digits
+    fn parse(spec: &str) -> Option<Rgb> {
+        let components = spec.strip_prefix("rgb:").or_else(|| spec.strip_prefix("rgba:"))?;
+
+        let mut components = components.split('/').map(|component| {
+            let digits = component.len() as u32;
+            if digits == 0 || digits > 4 {
+                return None;
+            }
+            let value = u32::from_str_radix(component, 16).ok()?;
+            let max = 16_u32.pow(digits) - 1;
+            Some((value * 255 / max) as u8)
+        });
+
+        Some(Rgb(components.next()??, components.next()??, components.next()??))
+    }
+
+    /// Relative luminance, from 0 for black to 1 for white
+    fn luminance(self) -> f64 {
+        let linear = |component: u8| {
+            let value = component as f64 / 255.0;
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[derive(Debug)]
+pub struct TermColors {
+    pub foreground: Rgb,
+    pub background: Rgb,
+}
+
+impl TermColors {
+    /// Whether the text is darker than the background, as with light themes
+    pub fn is_light(&self) -> bool {
+        self.background.luminance() > self.foreground.luminance()
+    }
+
+    /// Names the color scheme if it's a known one, e.g. "Nord (#d8dee9 on #2e3440)"
+    pub fn describe(&self) -> String {
+        let (foreground, background) = (self.foreground.hex(), self.background.hex());
+        let colors = format!("{} on {}", foreground, background);
+
+        match SCHEMES.iter().find(|(_, bg, fg)| *bg == background && *fg == foreground) {
+            Some((name, _, _)) => format!("{} ({})", name, colors),
+            None => colors,
+        }
+    }
+}
+
+/// Turns off the line buffering and echoing of the terminal, so that its answers
+/// can be read as they come without showing up on screen. Restored when dropped.
+struct RawMode {
+    fd:       RawFd,
+    original: libc::termios,
+}
+
+impl RawMode {
+    fn enable(fd: RawFd) -> Option<RawMode> {
+        let mut original: libc::termios = unsafe { mem::zeroed() };
+        if unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+            return None;
+        }
+
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+
+        Some(RawMode { fd, original })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // Answers which came in too late would otherwise be read by the shell
+        unsafe { libc::tcflush(self.fd, libc::TCIFLUSH) };
+        unsafe { libc::tcsetattr(self.fd, libc::TCSANOW, &self.original) };
+    }
+}
+
+/// Finds the color answered to an OSC query, e.g. "\x1b]11;rgb:2e2e/3434/4040\x1b\\",
+/// which may also be terminated by a BEL
+fn find_color(reply: &str, osc: &str) -> Option<Rgb> {
+    let start = reply.find(osc)? + osc.len();
+    let spec = reply[start..].split(['\x1b', '\x07']).next()?;
+    Rgb::parse(spec)
+}
+
+/// Reads the answers of the terminal until the DA1 one, e.g. "\x1b[?62;22c", comes in
+fn read_reply(tty: &mut File) -> String {
+    let mut reply = vec![];
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let mut poll_fd = libc::pollfd { fd: tty.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            break;
+        }
+
+        let mut buffer = [0_u8; 256];
+        match tty.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => reply.extend_from_slice(&buffer[..read]),
+        }
+
+        let text = String::from_utf8_lossy(&reply);
+        if text.rfind("\x1b[?").is_some_and(|start| text[start..].contains('c')) {
+            break;
+        }
+    }
+
+    String::from_utf8_lossy(&reply).into_owned()
+}
+
+/// Asks the terminal pulga runs in for its foreground and background colors
+pub fn query_colors() -> Option<TermColors> {
+    // Only query an actual terminal, and not e.g. when the output is piped
+    let is_terminal =
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && libc::isatty(libc::STDOUT_FILENO) == 1 };
+    if !is_terminal {
+        return None;
+    }
+    // Over SSH, the answers may take longer than TIMEOUT to come back
+    if session::get_ssh_client().is_some() {
+        return None;
+    }
+
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let raw_mode = RawMode::enable(tty.as_raw_fd())?;
+
+    tty.write_all(QUERY).ok()?;
+    let reply = read_reply(&mut tty);
+    drop(raw_mode);
+
+    Some(TermColors {
+        foreground: find_color(&reply, "]10;")?,
+        background: find_color(&reply, "]11;")?,
+    })
+}
@@ -0,0 +1,235 @@
+use crate::util::get_config_dir;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// A font family and its size, e.g. ("JetBrains Mono", "11pt")
+type Font = (String, String);
+
+/// Reads the first of the given files that exists
+fn read_first(paths: &[PathBuf]) -> Option<String> {
+    paths.iter().find_map(|path| fs::read_to_string(path).ok())
+}
+
+/// The first string within single or double quotes, e.g. `"Hack"` in `{ family = "Hack" }`
+fn quoted(text: &str) -> Option<&str> {
+    let start = text.find(['"', '\''])?;
+    let quote = text[start..].chars().next()?;
+    let rest = &text[start + 1..];
+
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+/// Sizes are written as floats, but usually hold whole numbers
+fn format_size(size: f64, unit: &str) -> String {
+    format!("{}{}", size, unit)
+}
+
+/// Alacritty has read TOML since 0.13, e.g. `[font.normal]` then `family = "Hack"`
+fn alacritty_toml_font(text: &str) -> Font {
+    let (mut family, mut size) = (None, None);
+    let mut section = String::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line.trim_matches(|ch| ch == '[' || ch == ']').trim().to_string();
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+
+        match (section.as_str(), key) {
+            ("font", "size") => size = value.parse::<f64>().ok(),
+            ("font.normal", "family") => family = quoted(value).map(ToString::to_string),
+            // An inline table, e.g. `normal = { family = "Hack", style = "Regular" }`
+            ("font", "normal") => {
+                family = value
+                    .split_once("family")
+                    .and_then(|(_, rest)| quoted(rest))
+                    .map(ToString::to_string)
+            },
+            _ => {},
+        }
+    }
+
+    (
+        family.unwrap_or_else(|| "monospace".to_string()),
+        format_size(size.unwrap_or(11.25), "pt"),
+    )
+}
+
+/// Older versions of Alacritty read YAML, where `family` is nested under `font.normal`
+fn alacritty_yaml_font(text: &str) -> Font {
+    let (mut family, mut size) = (None, None);
+    // Indentation of the keys right under `font`, and the last of them, e.g. "normal"
+    let mut font_indent: Option<usize> = None;
+    let mut font_key = String::new();
+    let mut in_font = false;
+
+    for line in text.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let (key, value) = match line.trim().split_once(':') {
+            Some((key, value)) if !key.starts_with('#') => (key.trim(), value.trim()),
+            _ => continue,
+        };
+
+        if indent == 0 {
+            in_font = key == "font";
+            continue;
+        }
+        if !in_font {
+            continue;
+        }
+
+        if *font_indent.get_or_insert(indent) == indent {
+            font_key = key.to_string();
+            if key == "size" {
+                size = value.parse::<f64>().ok();
+            }
+        } else if font_key == "normal" && key == "family" {
+            family = Some(quoted(value).unwrap_or(value).to_string());
+        }
+    }
+
+    (
+        family.unwrap_or_else(|| "monospace".to_string()),
+        format_size(size.unwrap_or(11.25), "pt"),
+    )
+}
+
+fn alacritty_font(config_dir: &Path, home: &Path) -> Font {
+    let toml = read_first(&[
+        config_dir.join("alacritty").join("alacritty.toml"),
+        home.join(".alacritty.toml"),
+    ]);
+    if let Some(text) = toml {
+        return alacritty_toml_font(&text);
+    }
+
+    let yaml = read_first(&[
+        config_dir.join("alacritty").join("alacritty.yml"),
+        home.join(".alacritty.yml"),
+    ]);
+    alacritty_yaml_font(&yaml.unwrap_or_default())
+}
+
+/// kitty.conf has lines such as `font_family JetBrains Mono` and `font_size 11.0`
+fn kitty_font(config_dir: &Path) -> Font {
+    let text = fs::read_to_string(config_dir.join("kitty").join("kitty.conf")).unwrap_or_default();
+    let (mut family, mut size) = (None, None);
+
+    for line in text.lines().map(str::trim) {
+        let (key, value) = match line.split_once(char::is_whitespace) {
+            Some((key, value)) => (key, value.trim()),
+            None => continue,
+        };
+
+        match key {
+            // Since 0.36, the family may come with its style, e.g. `family="Fira Code" style=Bold`
+            "font_family" if value.starts_with("family=") => {
+                family = quoted(value).map(ToString::to_string)
+            },
+            "font_family" => family = Some(value.to_string()),
+            "font_size" => size = value.parse::<f64>().ok(),
+            _ => {},
+        }
+    }
+
+    (
+        family.unwrap_or_else(|| "monospace".to_string()),
+        format_size(size.unwrap_or(11.0), "pt"),
+    )
+}
+
+/// foot.ini takes fontconfig patterns with fallbacks,
+/// e.g. `font=Fira Code:size=11, Noto Color Emoji`
+fn foot_font(config_dir: &Path) -> Font {
+    let text = fs::read_to_string(config_dir.join("foot").join("foot.ini")).unwrap_or_default();
+
+    // The font is set before any section, or within [main]
+    let mut section = "main".to_string();
+    let mut pattern = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line.trim_matches(|ch| ch == '[' || ch == ']').trim().to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            if section == "main" && key.trim() == "font" {
+                pattern = Some(value.trim().to_string());
+            }
+        }
+    }
+
+    let pattern = pattern.unwrap_or_default();
+    let mut properties = pattern.split(',').next().unwrap_or_default().split(':');
+    let family = properties.next().map(str::trim).filter(|family| !family.is_empty());
+
+    let size = properties.find_map(|property| {
+        let (key, value) = property.split_once('=')?;
+        let value = value.trim().parse::<f64>().ok()?;
+        match key.trim() {
+            "size" => Some(format_size(value, "pt")),
+            "pixelsize" => Some(format_size(value, "px")),
+            _ => None,
+        }
+    });
+
+    (
+        family.unwrap_or("monospace").to_string(),
+        size.unwrap_or_else(|| format_size(8.0, "pt")),
+    )
+}
+
+/// wezterm.lua is a Lua script, which usually sets e.g.
+/// `config.font = wezterm.font("JetBrains Mono")` and `config.font_size = 12.0`
+fn wezterm_font(config_dir: &Path, home: &Path) -> Font {
+    let mut paths = vec![
+        config_dir.join("wezterm").join("wezterm.lua"),
+        home.join(".wezterm.lua"),
+    ];
+    if let Some(path) = env::var_os("WEZTERM_CONFIG_FILE") {
+        paths.insert(0, PathBuf::from(path));
+    }
+
+    let text = read_first(&paths).unwrap_or_default();
+    let (mut family, mut size) = (None, None);
+
+    // Lua comments start with "--"
+    for line in text.lines().map(|line| line.split("--").next().unwrap_or_default()) {
+        if let Some((_, rest)) = line.split_once("wezterm.font") {
+            // Also matches wezterm.font_with_fallback, whose first font is the primary one
+            if family.is_none() {
+                family = quoted(rest).map(ToString::to_string);
+            }
+        } else if let Some((_, rest)) = line.split_once("font_size") {
+            let value = rest.trim_start().strip_prefix('=').unwrap_or_default();
+            size = value.trim().trim_end_matches(',').parse::<f64>().ok().or(size);
+        }
+    }
+
+    (
+        family.unwrap_or_else(|| "JetBrains Mono".to_string()),
+        format_size(size.unwrap_or(12.0), "pt"),
+    )
+}
+
+/// Reads the font configured for the given terminal, as named by `terminal::find_terminal`,
+/// falling back to the terminal's default. e.g. "JetBrains Mono (11pt)"
+pub fn get_terminal_font(terminal: &str) -> Option<String> {
+    let home = PathBuf::from(env::var_os("HOME")?);
+    let config_dir = get_config_dir()?;
+
+    let (family, size) = match terminal {
+        "Alacritty" => alacritty_font(&config_dir, &home),
+        "kitty" => kitty_font(&config_dir),
+        "foot" => foot_font(&config_dir),
+        "WezTerm" => wezterm_font(&config_dir, &home),
+        _ => return None,
+    };
+
+    Some(format!("{} ({})", family, size))
+}
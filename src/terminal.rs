@@ -0,0 +1,94 @@
+use std::{env, fs};
+
+/// Terminal emulators, by the name of their process, which the kernel cuts at 15 characters
+const TERMINALS: &[(&str, &str)] = &[
+    ("alacritty", "Alacritty"),
+    ("kitty", "kitty"),
+    ("foot", "foot"),
+    ("footclient", "foot"),
+    ("wezterm-gui", "WezTerm"),
+    ("ghostty", "Ghostty"),
+    ("gnome-terminal-", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("ptyxis-agent", "Ptyxis"),
+    ("konsole", "Konsole"),
+    ("yakuake", "Yakuake"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("lxterminal", "LXTerminal"),
+    ("qterminal", "QTerminal"),
+    ("mate-terminal", "MATE Terminal"),
+    ("tilix", "Tilix"),
+    ("terminator", "Terminator"),
+    ("guake", "Guake"),
+    ("sakura", "Sakura"),
+    ("termite", "Termite"),
+    ("terminology", "Terminology"),
+    ("cool-retro-term", "cool-retro-term"),
+    ("urxvt", "URxvt"),
+    ("urxvtd", "URxvt"),
+    ("xterm", "XTerm"),
+    ("st", "st"),
+    ("rio", "Rio"),
+    ("contour", "Contour"),
+    ("tabby", "Tabby"),
+    ("code", "VS Code"),
+];
+
+/// Processes past which the terminal can't be found, e.g. over SSH or within tmux,
+/// whose server is detached from the terminal it was started from
+const STOP_PROCESSES: &[&str] = &[
+    "systemd", "init", "login", "sshd", "sshd-session", "tmux: server", "screen", "SCREEN",
+];
+
+/// Reads the name and parent of a process from /proc/<pid>/stat,
+/// e.g. "1234 (bash) S 1200 ..."
+fn read_process(pid: u32) -> Option<(String, u32)> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+
+    // The name is within parentheses and may itself contain spaces or parentheses
+    let (start, end) = (stat.find('(')?, stat.rfind(')')?);
+    let name = stat.get(start + 1..end)?.to_string();
+    let ppid = stat.get(end + 1..)?.split_whitespace().nth(1)?.parse().ok()?;
+
+    Some((name, ppid))
+}
+
+/// Walks up the ancestors of pulga until reaching a known terminal emulator
+pub fn find_terminal() -> Option<&'static str> {
+    let mut pid = unsafe { libc::getppid() } as u32;
+
+    while pid > 1 {
+        let (name, ppid) = read_process(pid)?;
+
+        if STOP_PROCESSES.contains(&name.as_str()) {
+            return None;
+        }
+        if let Some((_, pretty_name)) = TERMINALS.iter().find(|(process, _)| *process == name) {
+            return Some(pretty_name);
+        }
+
+        // Shells, sudo and the like are skipped over
+        pid = ppid;
+    }
+
+    None
+}
+
+/// Name of the terminal emulator found by `find_terminal`, e.g. "Alacritty", falling back to
+/// what the terminal announces through TERM_PROGRAM or TERM, e.g. "xterm-256color"
+pub fn get_terminal(found: Option<&str>) -> Option<String> {
+    if let Some(terminal) = found {
+        return Some(terminal.to_string());
+    }
+
+    let nonempty_var = |var| env::var(var).ok().filter(|value| !value.is_empty());
+
+    if let Some(program) = nonempty_var("TERM_PROGRAM") {
+        return match nonempty_var("TERM_PROGRAM_VERSION") {
+            Some(version) => Some(format!("{} {}", program, version)),
+            None => Some(program),
+        };
+    }
+
+    nonempty_var("TERM")
+}
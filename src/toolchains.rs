@@ -0,0 +1,230 @@
+use crate::{
+    shell::{parse_version, probe_version},
+    vercmp::compare_rpm,
+};
+
+use std::{
+    env, fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+/// Finds the version of a toolchain, if it's installed
+type GetVersion = fn() -> Option<String>;
+
+/// Finds a program in $PATH, following symlinks to where it's really installed,
+/// e.g. "/usr/lib/llvm-17/bin/clang" for "clang"
+fn find_program(name: &str) -> Option<(PathBuf, PathBuf)> {
+    let path = env::var_os("PATH")?;
+    let found = env::split_paths(&path).map(|dir| dir.join(name)).find(|path| {
+        fs::metadata(path)
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    })?;
+    let resolved = fs::canonicalize(&found).ok()?;
+
+    Some((found, resolved))
+}
+
+/// The directory a program was installed into, e.g. "/usr" for "/usr/bin/gcc"
+fn get_prefix(program: &Path) -> Option<&Path> {
+    program.parent()?.parent()
+}
+
+/// The highest version among the names of the directories in `dir`, e.g. "14.2.1" within
+/// "/usr/lib/gcc/x86_64-pc-linux-gnu"
+fn highest_version_dir(dir: &Path) -> Option<String> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(|ch: char| ch.is_ascii_digit()))
+        .max_by(|a, b| compare_rpm(a, b))
+}
+
+/// Walks up from the current directory, looking for a file such as ".python-version"
+fn find_upwards(name: &str) -> Option<PathBuf> {
+    let cwd = env::current_dir().ok()?;
+    cwd.ancestors().map(|dir| dir.join(name)).find(|path| path.is_file())
+}
+
+/// Reads the version of the toolchain rustup picks, from the manifest of its channel:
+/// "[pkg.rustc]" is followed by e.g. `version = "1.77.0 (aedd173a2 2024-03-17)"`
+fn rustup_version() -> Option<String> {
+    let rustup_home = match env::var_os("RUSTUP_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".rustup"),
+    };
+
+    let toolchain = match env::var("RUSTUP_TOOLCHAIN") {
+        Ok(toolchain) => toolchain,
+        Err(_) => {
+            let settings = fs::read_to_string(rustup_home.join("settings.toml")).ok()?;
+            let line = settings.lines().find(|line| line.starts_with("default_toolchain"))?;
+            line.split('"').nth(1)?.to_string()
+        },
+    };
+
+    // Toolchains may be named without the host, e.g. "stable"
+    let dir = fs::read_dir(rustup_home.join("toolchains"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|dir| {
+            let name = dir.file_name().unwrap_or_default().to_string_lossy();
+            name == toolchain || name.starts_with(&format!("{}-", toolchain))
+        })?;
+
+    let manifest = dir.join("lib/rustlib/multirust-channel-manifest.toml");
+    let manifest = fs::read_to_string(manifest).ok()?;
+    let rustc = manifest.split("[pkg.rustc]").nth(1)?;
+    let line = rustc.lines().find(|line| line.starts_with("version"))?;
+    parse_version(line.split('"').nth(1)?)
+}
+
+fn get_rustc_version() -> Option<String> {
+    let (found, resolved) = find_program("rustc")?;
+
+    // rustup may install a toolchain when asked, so it's never run
+    if resolved.file_name()? == "rustup" {
+        return rustup_version();
+    }
+    probe_version(found.to_str()?, "--version")
+}
+
+/// GCC keeps its internal files in e.g. "/usr/lib/gcc/x86_64-linux-gnu/13", named after
+/// its version or, on Debian and derivatives, after its major version. Several versions
+/// may be installed side by side, so the one of the binary found is told apart by its
+/// name, e.g. "x86_64-linux-gnu-gcc-12", or else by running it
+fn get_gcc_version() -> Option<String> {
+    let (found, resolved) = find_program("gcc")?;
+
+    let gcc_dir = get_prefix(&resolved)?.join("lib/gcc");
+    let mut versions: Vec<String> = fs::read_dir(gcc_dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|target| fs::read_dir(target.path()).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(|ch: char| ch.is_ascii_digit()))
+        .collect();
+    versions.sort();
+    versions.dedup();
+
+    let name = resolved.file_name()?.to_string_lossy();
+    let major = name.rsplit('-').next().filter(|major| major.parse::<u32>().is_ok());
+    let version = match major {
+        Some(major) => versions
+            .into_iter()
+            .find(|version| version == major || version.starts_with(&format!("{}.", major))),
+        None if versions.len() == 1 => versions.pop(),
+        None => None,
+    };
+
+    version.or_else(|| probe_version(found.to_str()?, "-dumpfullversion"))
+}
+
+/// Clang keeps its headers in e.g. "/usr/lib/clang/17" or "/usr/lib/llvm-17/lib/clang/17"
+fn get_clang_version() -> Option<String> {
+    let (found, resolved) = find_program("clang")?;
+
+    highest_version_dir(&get_prefix(&resolved)?.join("lib/clang"))
+        .or_else(|| probe_version(found.to_str()?, "--version"))
+}
+
+/// The version pyenv picks, as set in $PYENV_VERSION, ".python-version" or its global
+/// version file
+fn pyenv_version() -> Option<String> {
+    if let Ok(version) = env::var("PYENV_VERSION") {
+        return Some(version);
+    }
+
+    let pyenv_root = match env::var_os("PYENV_ROOT") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".pyenv"),
+    };
+    let file = find_upwards(".python-version").unwrap_or_else(|| pyenv_root.join("version"));
+    let version = fs::read_to_string(file).ok()?;
+    version.lines().next().map(|line| line.trim().to_string())
+}
+
+/// Reads e.g. `#define PY_VERSION "3.12.2"` from "/usr/include/python3.12/patchlevel.h",
+/// which comes with the headers, or just "3.12" from the name of "/usr/bin/python3.12"
+fn get_python_version() -> Option<String> {
+    let (found, resolved) = find_program("python3")?;
+
+    if resolved.to_string_lossy().contains("/.pyenv/shims/") {
+        match pyenv_version() {
+            Some(version) if version != "system" => return Some(version),
+            _ => return probe_version(found.to_str()?, "--version"),
+        }
+    }
+
+    let name = resolved.file_name()?.to_string_lossy().into_owned();
+    if let Some(minor) = name.strip_prefix("python").filter(|version| version.contains('.')) {
+        let header = get_prefix(&resolved)?
+            .join("include")
+            .join(&name)
+            .join("patchlevel.h");
+        let defined = fs::read_to_string(header).ok().and_then(|header| {
+            let line = header.lines().find(|line| line.starts_with("#define PY_VERSION "))?;
+            Some(line.split('"').nth(1)?.to_string())
+        });
+        return defined.or_else(|| Some(minor.to_string()));
+    }
+
+    probe_version(found.to_str()?, "--version")
+}
+
+/// Reads the version from where nvm installs node, e.g. "~/.nvm/versions/node/v20.11.1",
+/// or from "/usr/include/node/node_version.h", which defines its every part
+fn get_node_version() -> Option<String> {
+    let (found, resolved) = find_program("node")?;
+    let prefix = get_prefix(&resolved)?;
+
+    let prefix_name = prefix.file_name()?.to_string_lossy();
+    if let Some(version) = prefix_name.strip_prefix('v').and_then(parse_version) {
+        return Some(version);
+    }
+
+    let header = fs::read_to_string(prefix.join("include/node/node_version.h")).ok();
+    let part = |name: &str| {
+        let header = header.as_ref()?;
+        let line = header.lines().find(|line| line.starts_with(&format!("#define {} ", name)))?;
+        line.split_whitespace().nth(2).map(ToString::to_string)
+    };
+
+    match (part("NODE_MAJOR_VERSION"), part("NODE_MINOR_VERSION"), part("NODE_PATCH_VERSION")) {
+        (Some(major), Some(minor), Some(patch)) => Some(format!("{}.{}.{}", major, minor, patch)),
+        // node prints e.g. "v20.11.1"
+        _ => probe_version(found.to_str()?, "--version"),
+    }
+}
+
+/// Go keeps its version in the VERSION file of its root, e.g. "go1.22.1"
+fn get_go_version() -> Option<String> {
+    let (_, resolved) = find_program("go")?;
+
+    let version = fs::read_to_string(get_prefix(&resolved)?.join("VERSION")).ok()?;
+    version.lines().next()?.strip_prefix("go").map(ToString::to_string)
+}
+
+/// Versions of the compilers and interpreters found in $PATH, as (name, version) pairs,
+/// e.g. ("rustc", "1.77.0")
+pub fn get_toolchains() -> Vec<(String, String)> {
+    let toolchains: &[(&str, GetVersion)] = &[
+        ("rustc", get_rustc_version),
+        ("gcc", get_gcc_version),
+        ("clang", get_clang_version),
+        ("python", get_python_version),
+        ("node", get_node_version),
+        ("go", get_go_version),
+    ];
+
+    toolchains
+        .iter()
+        .filter_map(|(name, get_version)| Some((name.to_string(), get_version()?)))
+        .collect()
+}
@@ -0,0 +1,38 @@
+use std::{fs, path::Path};
+
+/// Version of the first TPM, e.g. "2.0" or "1.2"
+pub fn get_tpm_version() -> Option<String> {
+    let tpm_dir = Path::new("/sys/class/tpm/tpm0/");
+    if !tpm_dir.exists() {
+        return None;
+    }
+
+    // Linux 5.6+
+    if let Ok(major) = fs::read_to_string(tpm_dir.join("tpm_version_major")) {
+        return match major.trim() {
+            "2" => Some("2.0".to_string()),
+            "1" => Some("1.2".to_string()),
+            major => Some(major.to_string()),
+        };
+    }
+
+    // TPM 1.2 chips list their capabilities, e.g. "TCG version: 1.2"
+    for caps in &[tpm_dir.join("caps"), tpm_dir.join("device").join("caps")] {
+        if let Ok(caps) = fs::read_to_string(caps) {
+            let version = caps
+                .lines()
+                .find_map(|line| line.strip_prefix("TCG version:"))
+                .map(|version| version.trim().to_string());
+            if version.is_some() {
+                return version;
+            }
+        }
+    }
+
+    // Only TPM 2.0 devices get an in-kernel resource manager
+    if Path::new("/dev/tpmrm0").exists() {
+        Some("2.0".to_string())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    inflate::gunzip,
+    lz4,
+    packages::Counter,
+    pacman,
+    sqlite::{Database, Value},
+    util::read_be_u32,
+    vercmp::{compare_deb, compare_rpm},
+};
+
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+/// apt keeps the package lists of each repository, and their Release files, in here
+const APT_LISTS: &str = "/var/lib/apt/lists";
+/// dnf keeps the metadata of each repository in a subdirectory, e.g. fedora-<hash>/repodata
+const DNF_CACHES: &[&str] = &["/var/cache/libdnf5", "/var/cache/dnf"];
+
+// Tags of the RPM header
+const RPMTAG_NAME: u32 = 1000;
+const RPMTAG_VERSION: u32 = 1001;
+const RPMTAG_RELEASE: u32 = 1002;
+const RPMTAG_EPOCH: u32 = 1003;
+const RPMTAG_ARCH: u32 = 1022;
+
+/// A package, by its name and architecture, e.g. ("bash", "amd64")
+type Package = (String, String);
+
+/// Keeps the newest version of each package, as some may be installed or
+/// available in more than one version, e.g. kernels
+fn insert_newest(
+    versions: &mut HashMap<Package, String>,
+    package: Package,
+    version: String,
+    compare: fn(&str, &str) -> Ordering,
+) {
+    match versions.get_mut(&package) {
+        Some(newest) if compare(&version, newest) == Ordering::Greater => *newest = version,
+        Some(_) => {},
+        None => {
+            versions.insert(package, version);
+        },
+    }
+}
+
+/// Reads the value of a field of a stanza, e.g. "Version" in "Version: 5.2.15-2+b1"
+fn stanza_field<'a>(stanza: &'a str, field: &str) -> Option<&'a str> {
+    stanza.lines().find_map(|line| {
+        let value = line.strip_prefix(field)?.strip_prefix(':')?;
+        Some(value.trim())
+    })
+}
+
+/// Reads the packages, architectures and versions of a dpkg status file or apt list,
+/// which hold a stanza per package, separated by blank lines
+fn read_stanzas<'a>(
+    text: &'a str,
+    filter: impl Fn(&str) -> bool + 'a,
+) -> impl Iterator<Item = (Package, String)> + 'a {
+    text.split("\n\n").filter(move |stanza| filter(stanza)).filter_map(|stanza| {
+        let package = stanza_field(stanza, "Package")?.to_string();
+        let architecture = stanza_field(stanza, "Architecture")?.to_string();
+        let version = stanza_field(stanza, "Version")?.to_string();
+        Some(((package, architecture), version))
+    })
+}
+
+/// Repositories such as backports are marked as NotAutomatic in their Release file,
+/// so that apt doesn't upgrade to their packages unless asked to. The Release file of
+/// a list is the one of the same suite, e.g. ..._dists_bookworm-backports_InRelease
+/// for ..._dists_bookworm-backports_main_binary-amd64_Packages.
+fn is_not_automatic(list: &str, releases: &[(String, String)]) -> bool {
+    releases
+        .iter()
+        .filter(|(prefix, _)| list.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .is_some_and(|(_, release)| release.lines().any(|line| line == "NotAutomatic: yes"))
+}
+
+/// Compares the installed packages with the lists apt downloaded when last updated,
+/// which may be compressed when apt is configured to
+fn count_apt() -> Option<usize> {
+    let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    let mut installed = HashMap::new();
+    // Held packages are still listed as upgradable, e.g. "hold ok installed"
+    let is_installed = |stanza: &str| {
+        stanza_field(stanza, "Status").is_some_and(|status| status.ends_with(" ok installed"))
+    };
+    for (package, version) in read_stanzas(&status, is_installed) {
+        insert_newest(&mut installed, package, version, compare_deb);
+    }
+
+    let mut names: Vec<String> = fs::read_dir(APT_LISTS)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+
+    let releases: Vec<(String, String)> = names
+        .iter()
+        .filter_map(|name| {
+            let prefix = name
+                .strip_suffix("_InRelease")
+                .or_else(|| name.strip_suffix("_Release"))?;
+            let release = fs::read_to_string(Path::new(APT_LISTS).join(name)).ok()?;
+            Some((format!("{}_", prefix), release))
+        })
+        .collect();
+
+    let mut available = HashMap::new();
+    for name in names.iter().filter(|name| !is_not_automatic(name, &releases)) {
+        let data = match name.rsplit_once("_Packages") {
+            Some((_, "")) | Some((_, ".lz4")) | Some((_, ".gz")) => {
+                fs::read(Path::new(APT_LISTS).join(name)).ok()
+            },
+            _ => continue,
+        };
+        let data = match data {
+            Some(data) if name.ends_with(".lz4") => lz4::decompress(&data),
+            Some(data) if name.ends_with(".gz") => gunzip(&data),
+            data => data,
+        };
+
+        let text = String::from_utf8_lossy(data.as_deref().unwrap_or_default()).into_owned();
+        for (package, version) in read_stanzas(&text, |_| true) {
+            if installed.contains_key(&package) {
+                insert_newest(&mut available, package, version, compare_deb);
+            }
+        }
+    }
+
+    Some(count_newer(&installed, &available, compare_deb))
+}
+
+/// Reads the name, architecture and version of a package from its RPM header, which
+/// is made of an index of tags, each pointing at its value in the data that follows
+fn read_rpm_header(header: &[u8]) -> Option<(Package, String)> {
+    let entries = read_be_u32(header, 0)? as usize;
+    let data_start = 8 + entries * 16;
+    let data = header.get(data_start..data_start + read_be_u32(header, 4)? as usize)?;
+
+    let mut tags = HashMap::new();
+    for entry in 0..entries {
+        let entry = 8 + entry * 16;
+        tags.insert(read_be_u32(header, entry)?, read_be_u32(header, entry + 8)? as usize);
+    }
+
+    let string = |tag| {
+        let value = data.get(*tags.get(&tag)?..)?;
+        let value = &value[..value.iter().position(|&byte| byte == 0)?];
+        Some(String::from_utf8_lossy(value).into_owned())
+    };
+    let epoch = match tags.get(&RPMTAG_EPOCH) {
+        Some(&offset) => read_be_u32(data, offset)?,
+        None => 0,
+    };
+
+    let version = format!("{}:{}-{}", epoch, string(RPMTAG_VERSION)?, string(RPMTAG_RELEASE)?);
+    Some(((string(RPMTAG_NAME)?, string(RPMTAG_ARCH)?), version))
+}
+
+/// Reads an attribute of an XML tag, e.g. "ver" in `<version epoch="0" ver="1.2" rel="3"/>`
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+/// Reads the text within an XML element, e.g. "bash" in `<name>bash</name>`
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let len = xml[start..].find('<')?;
+    Some(&xml[start..start + len])
+}
+
+/// Compares the packages in the RPM database with the metadata dnf downloaded
+/// when it last refreshed the repositories, in their primary.xml
+fn count_dnf() -> Option<usize> {
+    let database = ["/var/lib/rpm/rpmdb.sqlite", "/usr/lib/sysimage/rpm/rpmdb.sqlite"]
+        .iter()
+        .find_map(|path| Database::open(path.as_ref()))?;
+
+    let mut installed = HashMap::new();
+    // Rows are (hnum, blob), the blob being the header of a package
+    database.visit_table("Packages", &mut |row| {
+        if let Some(Value::Bytes(header)) = row.get(1) {
+            if let Some((package, version)) = read_rpm_header(header) {
+                insert_newest(&mut installed, package, version, compare_rpm);
+            }
+        }
+    });
+
+    let primaries: Vec<_> = DNF_CACHES
+        .iter()
+        .filter_map(|cache| fs::read_dir(cache).ok())
+        .flat_map(|repos| repos.flatten())
+        .filter_map(|repo| fs::read_dir(repo.path().join("repodata")).ok())
+        .flat_map(|metadata| metadata.flatten())
+        .map(|metadata| metadata.path())
+        .filter(|path| path.to_string_lossy().ends_with("primary.xml.gz"))
+        .collect();
+    if primaries.is_empty() {
+        return None;
+    }
+
+    let mut available = HashMap::new();
+    for primary in primaries {
+        let xml = match fs::read(primary).ok().and_then(|data| gunzip(&data)) {
+            Some(xml) => String::from_utf8_lossy(&xml).into_owned(),
+            None => continue,
+        };
+
+        for package in xml.split("<package ").skip(1) {
+            let version = match package.split_once("<version ") {
+                Some((_, tag)) => tag,
+                None => continue,
+            };
+            let (name, arch) = match (element(package, "name"), element(package, "arch")) {
+                (Some(name), Some(arch)) => (name.to_string(), arch.to_string()),
+                _ => continue,
+            };
+            let version = match (attribute(version, "ver"), attribute(version, "rel")) {
+                (Some(ver), Some(rel)) => {
+                    format!("{}:{}-{}", attribute(version, "epoch").unwrap_or("0"), ver, rel)
+                },
+                _ => continue,
+            };
+
+            let package = (name, arch);
+            if installed.contains_key(&package) {
+                insert_newest(&mut available, package, version, compare_rpm);
+            }
+        }
+    }
+
+    Some(count_newer(&installed, &available, compare_rpm))
+}
+
+/// Counts the installed packages of which a newer version is available
+fn count_newer(
+    installed: &HashMap<Package, String>,
+    available: &HashMap<Package, String>,
+    compare: fn(&str, &str) -> Ordering,
+) -> usize {
+    let names: HashSet<&str> = available
+        .iter()
+        .filter(|(package, version)| {
+            installed
+                .get(*package)
+                .is_some_and(|installed| compare(version, installed) == Ordering::Greater)
+        })
+        .map(|((name, _), _)| name.as_str())
+        .collect();
+
+    names.len()
+}
+
+/// Number of packages with pending updates, as found in the metadata each package manager
+/// last downloaded, without connecting to the repositories. e.g. "12 (pacman)"
+pub fn get_updates() -> Option<String> {
+    let managers: [(&str, Counter); 3] = [
+        ("pacman", pacman::count_updates),
+        ("apt", count_apt),
+        ("dnf", count_dnf),
+    ];
+
+    let counts: Vec<String> = managers
+        .iter()
+        .filter_map(|(name, count)| Some((name, count()?)))
+        .map(|(name, count)| format!("{} ({})", count, name))
+        .collect();
+
+    if counts.is_empty() {
+        None
+    } else {
+        Some(counts.join(", "))
+    }
+}
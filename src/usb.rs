@@ -0,0 +1,71 @@
+use std::{fs, path::Path};
+
+/// bDeviceClass of USB hubs, including the root hubs of the host controllers
+const HUB_CLASS: &str = "09";
+
+fn read_attribute(device: &Path, name: &str) -> Option<String> {
+    let value = fs::read_to_string(device.join(name)).ok()?;
+    let value = value.trim();
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Names a device by its manufacturer and product strings, e.g. "Logitech USB Receiver",
+/// or by its vendor and product IDs when it doesn't report them, e.g. "046d:c52b"
+fn device_name(device: &Path) -> Option<String> {
+    let manufacturer = read_attribute(device, "manufacturer");
+
+    match (manufacturer, read_attribute(device, "product")) {
+        (Some(manufacturer), Some(product)) if !product.starts_with(&manufacturer) => {
+            Some(format!("{} {}", manufacturer, product))
+        },
+        (_, Some(product)) => Some(product),
+        (_, None) => Some(format!(
+            "{}:{}",
+            read_attribute(device, "idVendor")?,
+            read_attribute(device, "idProduct")?
+        )),
+    }
+}
+
+/// Names of the connected USB devices other than hubs, with identical ones counted,
+/// e.g. "2x Logitech USB Receiver"
+pub fn get_usb_devices() -> Vec<String> {
+    let entries = match fs::read_dir("/sys/bus/usb/devices/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let mut names: Vec<String> = entries
+        .flatten()
+        // Interfaces, e.g. "1-2:1.0", share the directory with the devices they belong to
+        .filter(|entry| !entry.file_name().to_string_lossy().contains(':'))
+        .map(|entry| entry.path())
+        .filter(|device| read_attribute(device, "bDeviceClass").as_deref() != Some(HUB_CLASS))
+        .filter_map(|device| device_name(&device))
+        .collect();
+    names.sort();
+
+    let mut devices: Vec<(String, usize)> = vec![];
+    for name in names {
+        match devices.last_mut() {
+            Some((last, count)) if *last == name => *count += 1,
+            _ => devices.push((name, 1)),
+        }
+    }
+
+    devices
+        .into_iter()
+        .map(|(name, count)| {
+            if count > 1 {
+                format!("{}x {}", count, name)
+            } else {
+                name
+            }
+        })
+        .collect()
+}
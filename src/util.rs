@@ -1,8 +1,12 @@
 use libc::{self, c_char};
 
 use std::{
+    convert::TryInto,
+    env,
     ffi::{CStr, OsStr},
+    fs,
     os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
     ptr,
 };
 
@@ -33,4 +37,119 @@ pub(crate) fn get_base(path: &str) -> String {
             .next()
             .unwrap()
             .to_string()
-}
\ No newline at end of file
+}
+
+// Extracts the selected option of a sysfs setting, which is the one between brackets.
+// Example: "always [madvise] never" -> "madvise"
+pub(crate) fn get_selected_option(options: &str) -> Option<&str> {
+    options.split('[').nth(1)?.split(']').next()
+}
+
+// Reads a sysfs or procfs file holding a single value, without the trailing newline.
+// Example: "/sys/class/power_supply/BAT0/status" -> "Charging"
+pub(crate) fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|text| text.trim().to_string())
+}
+
+// Reads a sysfs file holding a decimal number.
+// Example: "/sys/class/backlight/intel_backlight/brightness" -> 4800
+pub(crate) fn read_u64(path: &Path) -> Option<u64> {
+    read_trimmed(path)?.parse().ok()
+}
+
+// The value of an environment variable, unless it is unset or empty.
+// Example: "XCURSOR_THEME" -> "Adwaita"
+pub(crate) fn nonempty_var(var: &str) -> Option<String> {
+    env::var(var).ok().filter(|value| !value.is_empty())
+}
+
+// Whether an environment variable is set to something other than an empty string.
+// Example: "WAYLAND_DISPLAY" -> true
+pub(crate) fn is_set(var: &str) -> bool {
+    env::var_os(var).is_some_and(|value| !value.is_empty())
+}
+
+// Reads a little-endian number of 2 bytes at `offset`, as found in SMBIOS tables.
+// Example: read_le_u16(&[0x00, 0x34, 0x12], 1) -> 0x1234
+pub(crate) fn read_le_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+// Reads a little-endian number of 4 bytes at `offset`.
+// Example: read_le_u32(&[0x78, 0x56, 0x34, 0x12], 0) -> 0x12345678
+pub(crate) fn read_le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// Reads a big-endian number of 4 bytes at `offset`, as found in RPM headers and git files.
+// Example: read_be_u32(&[0x12, 0x34, 0x56, 0x78], 0) -> 0x12345678
+pub(crate) fn read_be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+// Lists the names of the running processes, as in /proc/<pid>/comm.
+// Example: ["systemd", "kthreadd", ...]
+pub(crate) fn get_process_names() -> Vec<String> {
+    let entries = match fs::read_dir("/proc/") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            name.to_str().is_some_and(|name| name.bytes().all(|byte| byte.is_ascii_digit()))
+        })
+        .filter_map(|entry| fs::read_to_string(entry.path().join("comm")).ok())
+        .map(|name| name.trim_end().to_string())
+        .collect()
+}
+
+// Directory holding the user's configuration files, as per the XDG Base Directory spec.
+// Example: "/home/user/.config"
+pub(crate) fn get_config_dir() -> Option<PathBuf> {
+    match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(PathBuf::from(env::var_os("HOME")?).join(".config")),
+    }
+}
+
+// Directory in which pulga keeps what's costly to compute, as per the XDG Base Directory spec.
+// Example: "/home/user/.cache/pulga"
+pub(crate) fn get_cache_dir() -> Option<PathBuf> {
+    let dir = match env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+    };
+    Some(dir.join("pulga"))
+}
+
+// Directory holding what programs remember from one run to the next, as per the
+// XDG Base Directory spec. Example: "/home/user/.local/state"
+pub(crate) fn get_state_dir() -> Option<PathBuf> {
+    match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => Some(PathBuf::from(env::var_os("HOME")?).join(".local").join("state")),
+    }
+}
+
+// Reads the value of a key within a section of an INI-like file, such as the GTK,
+// KDE and qt5ct settings. Example: read_ini(path, "Settings", "gtk-theme-name")
+pub(crate) fn read_ini(path: &Path, section: &str, key: &str) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+
+    let mut in_section = false;
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[') {
+            in_section = name.trim_end_matches(']') == section;
+        } else if let Some((name, value)) = line.split_once('=') {
+            let value = value.trim().trim_matches('"');
+            if in_section && name.trim() == key && !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+
+    None
+}
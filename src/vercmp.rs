@@ -0,0 +1,201 @@
+use std::cmp::Ordering;
+
+/// Splits off the epoch of a version, e.g. 1 in "1:2.0-3", which is 0 when missing
+fn split_epoch(version: &str) -> (u64, &str) {
+    match version.split_once(':') {
+        Some((epoch, rest)) if epoch.bytes().all(|byte| byte.is_ascii_digit()) => {
+            (epoch.parse().unwrap_or(0), rest)
+        },
+        _ => (0, version),
+    }
+}
+
+/// Compares two versions as rpm does, which is also what pacman follows, by their
+/// alternating numeric and alphabetic segments, e.g. "6.8.1.arch1" or "2.0~rc1"
+fn compare_segments(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    let is_separator = |byte: &u8| !byte.is_ascii_alphanumeric() && *byte != b'~' && *byte != b'^';
+
+    loop {
+        while a.first().is_some_and(is_separator) {
+            a = &a[1..];
+        }
+        while b.first().is_some_and(is_separator) {
+            b = &b[1..];
+        }
+
+        // A tilde sorts before anything, even the end of the version, as in "1.0~rc1" < "1.0"
+        match (a.first() == Some(&b'~'), b.first() == Some(&b'~')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            },
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {},
+        }
+
+        // A caret sorts after the end of the version, but before anything else,
+        // as in "1.0" < "1.0^git1" < "1.0.1"
+        match (a.first() == Some(&b'^'), b.first() == Some(&b'^')) {
+            (true, true) => {
+                a = &a[1..];
+                b = &b[1..];
+                continue;
+            },
+            (true, false) if b.is_empty() => return Ordering::Greater,
+            (true, false) => return Ordering::Less,
+            (false, true) if a.is_empty() => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {},
+        }
+
+        if a.is_empty() || b.is_empty() {
+            break;
+        }
+
+        let is_numeric = a[0].is_ascii_digit();
+        let segment_len = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .take_while(|byte| {
+                    if is_numeric {
+                        byte.is_ascii_digit()
+                    } else {
+                        byte.is_ascii_alphabetic()
+                    }
+                })
+                .count()
+        };
+        let (a_len, b_len) = (segment_len(a), segment_len(b));
+        let (a_segment, b_segment) = (&a[..a_len], &b[..b_len]);
+        a = &a[a_len..];
+        b = &b[b_len..];
+
+        // Segments of different kinds, in which case the numeric one is newer
+        if b_segment.is_empty() {
+            return if is_numeric { Ordering::Greater } else { Ordering::Less };
+        }
+
+        let ordering = if is_numeric {
+            let trim_zeros = |segment: &[u8]| {
+                let zeros = segment.iter().take_while(|&&byte| byte == b'0').count();
+                segment[zeros..].to_vec()
+            };
+            let (a_segment, b_segment) = (trim_zeros(a_segment), trim_zeros(b_segment));
+            a_segment.len().cmp(&b_segment.len()).then_with(|| a_segment.cmp(&b_segment))
+        } else {
+            a_segment.cmp(b_segment)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    // Whichever version has segments left is the newer one
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
+
+/// Compares rpm and pacman versions, made of an optional epoch, the version and an
+/// optional release, e.g. "1:2.0-3"
+pub fn compare_rpm(a: &str, b: &str) -> Ordering {
+    let split = |version| {
+        let (epoch, rest) = split_epoch(version);
+        match rest.rsplit_once('-') {
+            Some((version, release)) => (epoch, version, Some(release)),
+            None => (epoch, rest, None),
+        }
+    };
+    let ((a_epoch, a_version, a_release), (b_epoch, b_version, b_release)) = (split(a), split(b));
+
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_segments(a_version, b_version))
+        .then_with(|| match (a_release, b_release) {
+            (Some(a_release), Some(b_release)) => compare_segments(a_release, b_release),
+            _ => Ordering::Equal,
+        })
+}
+
+/// Weight of a character of a Debian version, in which letters sort before
+/// other symbols and a tilde before anything, even the end of the version
+fn deb_order(byte: Option<&u8>) -> i32 {
+    match byte {
+        None => 0,
+        Some(b'~') => -1,
+        Some(byte) if byte.is_ascii_digit() => 0,
+        Some(byte) if byte.is_ascii_alphabetic() => *byte as i32,
+        Some(byte) => *byte as i32 + 256,
+    }
+}
+
+/// Compares the upstream versions or revisions of Debian versions, as dpkg does,
+/// by their alternating non-digit and digit parts
+fn compare_deb_parts(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.as_bytes(), b.as_bytes());
+    let is_digit = |bytes: &[u8]| bytes.first().is_some_and(u8::is_ascii_digit);
+
+    while !a.is_empty() || !b.is_empty() {
+        while (!a.is_empty() && !is_digit(a)) || (!b.is_empty() && !is_digit(b)) {
+            let (a_order, b_order) = (deb_order(a.first()), deb_order(b.first()));
+            if a_order != b_order {
+                return a_order.cmp(&b_order);
+            }
+            a = a.get(1..).unwrap_or_default();
+            b = b.get(1..).unwrap_or_default();
+        }
+
+        while a.first() == Some(&b'0') {
+            a = &a[1..];
+        }
+        while b.first() == Some(&b'0') {
+            b = &b[1..];
+        }
+
+        // The longer number is the bigger one, otherwise the first digit that differs decides
+        let mut first_difference = Ordering::Equal;
+        while is_digit(a) && is_digit(b) {
+            if first_difference == Ordering::Equal {
+                first_difference = a[0].cmp(&b[0]);
+            }
+            a = &a[1..];
+            b = &b[1..];
+        }
+        if is_digit(a) {
+            return Ordering::Greater;
+        }
+        if is_digit(b) {
+            return Ordering::Less;
+        }
+        if first_difference != Ordering::Equal {
+            return first_difference;
+        }
+    }
+
+    Ordering::Equal
+}
+
+/// Compares Debian versions, made of an optional epoch, the upstream version and an
+/// optional revision, e.g. "1:2.36-9+deb12u4"
+pub fn compare_deb(a: &str, b: &str) -> Ordering {
+    let split = |version| {
+        let (epoch, rest) = split_epoch(version);
+        let (version, revision) = rest.rsplit_once('-').unwrap_or((rest, ""));
+        (epoch, version, revision)
+    };
+    let ((a_epoch, a_version, a_revision), (b_epoch, b_version, b_revision)) = (split(a), split(b));
+
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_deb_parts(a_version, b_version))
+        .then_with(|| compare_deb_parts(a_revision, b_revision))
+}
@@ -0,0 +1,92 @@
+use crate::dmi;
+
+use std::fs;
+
+/// Names hypervisors by the vendor signature they report through CPUID leaf 0x40000000
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn hypervisor_from_signature(signature: &[u8]) -> Option<&'static str> {
+    let name = match signature {
+        b"KVMKVMKVM\0\0\0" => "KVM",
+        b"Microsoft Hv" => "Hyper-V",
+        b"VMwareVMware" => "VMware",
+        b"XenVMMXenVMM" => "Xen",
+        b"TCGTCGTCGTCG" => "QEMU",
+        b"VBoxVBoxVBox" => "VirtualBox",
+        b"bhyve bhyve " => "bhyve",
+        b" lrpepyh  vr" => "Parallels",
+        b"ACRNACRNACRN" => "ACRN",
+        b"QNXQVMBSQG\0\0" => "QNX",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Checks the hypervisor bit of CPUID, which hypervisors set for their guests
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn hypervisor_from_cpuid() -> Option<&'static str> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    let features = unsafe { __cpuid(1) };
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+
+    let leaf = unsafe { __cpuid(0x4000_0000) };
+    let mut signature = [0_u8; 12];
+    signature[..4].copy_from_slice(&leaf.ebx.to_le_bytes());
+    signature[4..8].copy_from_slice(&leaf.ecx.to_le_bytes());
+    signature[8..].copy_from_slice(&leaf.edx.to_le_bytes());
+
+    Some(hypervisor_from_signature(&signature).unwrap_or("Unknown hypervisor"))
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn hypervisor_from_cpuid() -> Option<&'static str> {
+    None
+}
+
+/// Guesses the hypervisor or cloud from the DMI vendor and product names
+fn hypervisor_from_dmi() -> Option<&'static str> {
+    let vendor = dmi::read_id("sys_vendor").unwrap_or_default();
+    let product = dmi::read_id("product_name").unwrap_or_default();
+
+    let name = match (vendor.as_str(), product.as_str()) {
+        ("QEMU", _) => "QEMU",
+        (_, "KVM") => "KVM",
+        ("VMware, Inc.", _) => "VMware",
+        ("innotek GmbH", _) | (_, "VirtualBox") => "VirtualBox",
+        ("Microsoft Corporation", "Virtual Machine") => "Hyper-V",
+        ("Xen", _) => "Xen",
+        ("Parallels Software International Inc.", _) => "Parallels",
+        ("BHYVE", _) => "bhyve",
+        ("Amazon EC2", _) => "Amazon EC2",
+        ("Google", "Google Compute Engine") => "Google Compute Engine",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Name of the hypervisor the system runs under, e.g. "KVM (Amazon EC2)", if any
+pub fn get_hypervisor() -> Option<String> {
+    // Xen guests without CPUID passthrough (e.g. PV ones) and ARM guests
+    let sys_hypervisor = fs::read_to_string("/sys/hypervisor/type")
+        .ok()
+        .map(|kind| kind.trim().to_string())
+        .filter(|kind| !kind.is_empty());
+
+    let cpuid = hypervisor_from_cpuid().map(ToString::to_string).or(sys_hypervisor);
+
+    match (cpuid, hypervisor_from_dmi()) {
+        (Some(cpuid), Some(dmi)) if !cpuid.eq_ignore_ascii_case(dmi) => {
+            Some(format!("{} ({})", cpuid, dmi))
+        },
+        (Some(cpuid), _) => Some(cpuid),
+        (None, dmi) => dmi.map(ToString::to_string),
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{
+    netlink::{find_attribute, parse_attributes, Socket},
+    network,
+};
+
+use std::{convert::TryInto, fs, path::Path};
+
+// Command and attributes of WireGuard, as in <linux/wireguard.h>
+const WG_CMD_GET_DEVICE: u8 = 0;
+const WGDEVICE_A_IFNAME: u16 = 2;
+const WGDEVICE_A_PEERS: u16 = 8;
+const WGPEER_A_LAST_HANDSHAKE_TIME: u16 = 6;
+
+// Flags of a TUN/TAP device, as in <linux/if_tun.h>
+const IFF_TAP: u32 = 0x0002;
+
+/// An interface which tunnels the traffic through a VPN
+pub struct Vpn {
+    pub kind:           &'static str,
+    pub interface:      String,
+    // Unix timestamp of the latest handshake with a peer, for WireGuard
+    pub last_handshake: Option<i64>,
+}
+
+/// Asks WireGuard when the interface last shook hands with any of its peers, which only
+/// root may do, as the reply holds the keys
+fn get_last_handshake(interface: &str) -> Option<i64> {
+    let mut socket = Socket::open()?;
+    let family = socket.resolve_family("wireguard")?;
+
+    let mut name = interface.as_bytes().to_vec();
+    name.push(0);
+    let replies = socket.request(family, WG_CMD_GET_DEVICE, true, &[(WGDEVICE_A_IFNAME, &name)])?;
+
+    // Devices with many peers are split across several messages, and peers which never
+    // shook hands have a time of 0
+    replies
+        .iter()
+        .filter_map(|reply| find_attribute(reply, WGDEVICE_A_PEERS))
+        .flat_map(parse_attributes)
+        .filter_map(|(_, peer)| {
+            let time = find_attribute(peer, WGPEER_A_LAST_HANDSHAKE_TIME)?;
+            Some(i64::from_ne_bytes(time.get(..8)?.try_into().ok()?))
+        })
+        .filter(|&seconds| seconds > 0)
+        .max()
+}
+
+/// Tells which VPN an interface belongs to, if any, from its name and what the kernel
+/// says about it, e.g. "DEVTYPE=wireguard" in its uevent
+fn get_kind(interface: &str) -> Option<&'static str> {
+    let sysfs = Path::new("/sys/class/net").join(interface);
+    let uevent = fs::read_to_string(sysfs.join("uevent")).unwrap_or_default();
+    // Userspace implementations, such as wireguard-go and tailscaled, use TUN devices
+    let tun_flags = fs::read_to_string(sysfs.join("tun_flags"))
+        .ok()
+        .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok());
+
+    if uevent.lines().any(|line| line == "DEVTYPE=wireguard") {
+        return Some("WireGuard");
+    }
+
+    match tun_flags {
+        Some(_) if interface.starts_with("wg") => Some("WireGuard"),
+        Some(_) if interface.starts_with("tailscale") => Some("Tailscale"),
+        Some(_) if interface.starts_with("zt") => Some("ZeroTier"),
+        // Such as the ones of OpenVPN, which may tunnel either IP packets or Ethernet frames
+        Some(flags) if flags & IFF_TAP != 0 => Some("TAP"),
+        Some(_) => Some("TUN"),
+        None => None,
+    }
+}
+
+/// The VPN tunnels among the active interfaces
+pub fn get_vpns() -> Vec<Vpn> {
+    network::get_interfaces()
+        .into_iter()
+        .filter_map(|interface| {
+            let kind = get_kind(&interface.name)?;
+            let last_handshake = match kind {
+                "WireGuard" => get_last_handshake(&interface.name),
+                _ => None,
+            };
+
+            Some(Vpn { kind, interface: interface.name, last_handshake })
+        })
+        .collect()
+}
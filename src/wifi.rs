@@ -0,0 +1,81 @@
+use crate::{
+    netlink::{find_attribute, Socket},
+    network,
+};
+
+use libc::if_nametoindex;
+
+use std::{ffi::CString, fs};
+
+// Commands and attributes of nl80211, as in <linux/nl80211.h>
+const NL80211_CMD_GET_INTERFACE: u8 = 5;
+const NL80211_CMD_GET_STATION: u8 = 17;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_STA_INFO: u16 = 21;
+const NL80211_ATTR_SSID: u16 = 52;
+const NL80211_STA_INFO_SIGNAL: u16 = 7;
+
+/// The SSID the interface is connected to and the signal of the access point, in dBm,
+/// as nl80211 reports them
+fn query_nl80211(interface: &str) -> Option<(Option<String>, Option<i8>)> {
+    let name = CString::new(interface).ok()?;
+    let index = unsafe { if_nametoindex(name.as_ptr()) };
+    if index == 0 {
+        return None;
+    }
+
+    let mut socket = Socket::open()?;
+    let family = socket.resolve_family("nl80211")?;
+    let index = index.to_ne_bytes();
+    let attributes = [(NL80211_ATTR_IFINDEX, &index[..])];
+
+    let replies = socket.request(family, NL80211_CMD_GET_INTERFACE, false, &attributes)?;
+    let ssid = find_attribute(replies.first()?, NL80211_ATTR_SSID)
+        .map(|ssid| String::from_utf8_lossy(ssid).into_owned());
+
+    // While connected to a network, the only station of the interface is its access point
+    let stations = socket.request(family, NL80211_CMD_GET_STATION, true, &attributes)?;
+    let signal = stations.first().and_then(|station| {
+        let info = find_attribute(station, NL80211_ATTR_STA_INFO)?;
+        let signal = find_attribute(info, NL80211_STA_INFO_SIGNAL)?;
+        Some(*signal.first()? as i8)
+    });
+
+    Some((ssid, signal))
+}
+
+/// Reads the signal level from /proc/net/wireless, where each interface has a line such as
+/// " wlan0: 0000   70.  -40.  -256        0      0      0      0     0        0"
+fn read_proc_wireless(interface: &str) -> Option<i8> {
+    let wireless = fs::read_to_string("/proc/net/wireless").ok()?;
+    let line = wireless
+        .lines()
+        .find(|line| line.trim_start().starts_with(&format!("{}:", interface)))?;
+
+    let level = line.split_whitespace().nth(3)?.trim_end_matches('.');
+    level.parse().ok().filter(|level: &i8| *level < 0)
+}
+
+/// The SSID and signal strength of the first active wireless interface,
+/// e.g. "HomeNetwork (-52 dBm, 96%)"
+pub fn get_wifi() -> Option<String> {
+    let interface = network::get_interfaces()
+        .into_iter()
+        .find(|interface| network::is_wireless(&interface.name))?;
+
+    let (ssid, signal) = match query_nl80211(&interface.name) {
+        Some(reply) => reply,
+        None => (None, read_proc_wireless(&interface.name)),
+    };
+
+    // The quality as NetworkManager estimates it, from -100 dBm at worst to -50 dBm at best
+    let signal =
+        signal.map(|dbm| format!("{} dBm, {}%", dbm, ((i32::from(dbm) + 100) * 2).clamp(0, 100)));
+
+    match (ssid, signal) {
+        (Some(ssid), Some(signal)) => Some(format!("{} ({})", ssid, signal)),
+        (Some(ssid), None) => Some(ssid),
+        (None, Some(signal)) => Some(signal),
+        (None, None) => None,
+    }
+}
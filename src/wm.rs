@@ -0,0 +1,64 @@
+use crate::{compositor::get_wayland_compositor, util::get_process_names};
+
+use std::env;
+
+/// X11 window managers, by the name of their process
+const X11_WINDOW_MANAGERS: &[(&str, &str)] = &[
+    ("bspwm", "bspwm"),
+    ("awesome", "awesome"),
+    // The binary built by xmonad is named after the platform, e.g. "xmonad-x86_64-linux"
+    ("xmonad", "xmonad"),
+    ("i3", "i3"),
+    ("dwm", "dwm"),
+    ("qtile", "Qtile"),
+    ("herbstluftwm", "herbstluftwm"),
+    ("spectrwm", "spectrwm"),
+    ("leftwm", "LeftWM"),
+    ("openbox", "Openbox"),
+    ("fluxbox", "Fluxbox"),
+    ("blackbox", "Blackbox"),
+    ("icewm", "IceWM"),
+    ("jwm", "JWM"),
+    ("fvwm", "FVWM"),
+    ("wmaker", "Window Maker"),
+    ("enlightenment", "Enlightenment"),
+    ("cwm", "cwm"),
+    ("ratpoison", "ratpoison"),
+    ("stumpwm", "StumpWM"),
+    ("kwin_x11", "KWin"),
+    ("xfwm4", "Xfwm4"),
+    ("marco", "Marco"),
+    ("metacity", "Metacity"),
+    ("mutter", "Mutter"),
+    ("compiz", "Compiz"),
+];
+
+/// Guesses the window manager from the running processes, for when it can't be queried
+fn get_wm_from_processes() -> Option<String> {
+    let processes = get_process_names();
+
+    // Prefixes are matched since names such as "fvwm3" or "icewm-session" carry suffixes
+    X11_WINDOW_MANAGERS
+        .iter()
+        .find(|(name, _)| processes.iter().any(|process| process.starts_with(name)))
+        .map(|(_, pretty_name)| pretty_name.to_string())
+}
+
+/// Name of the window manager, e.g. "bspwm", or of the Wayland compositor, which manages
+/// the windows itself
+pub fn get_window_manager() -> Option<String> {
+    if let Some(compositor) = get_wayland_compositor() {
+        return Some(compositor.to_string());
+    }
+
+    env::var_os("DISPLAY")?;
+
+    #[cfg(feature = "use_xlib")]
+    {
+        if let Some(name) = unsafe { crate::wmx11::get_wm_name() } {
+            return Some(name);
+        }
+    }
+
+    get_wm_from_processes()
+}
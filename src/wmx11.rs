@@ -0,0 +1,97 @@
+use x11::xlib::{
+    Atom, Display, Window, XCloseDisplay, XDefaultRootWindow, XFree, XGetWindowProperty,
+    XInternAtom, XOpenDisplay, XA_WINDOW,
+};
+
+use std::{
+    ffi::CString,
+    mem,
+    os::raw::{c_int, c_uchar, c_ulong},
+    ptr, slice,
+};
+
+/// Reads a property of a window, given the name and type of the property
+unsafe fn get_property(
+    display: *mut Display,
+    window: Window,
+    name: &str,
+    kind: Atom,
+) -> Option<Vec<u8>> {
+    let name = CString::new(name).ok()?;
+    let property = XInternAtom(display, name.as_ptr(), 1);
+    if property == 0 {
+        return None;
+    }
+
+    let mut actual_type: Atom = 0;
+    let mut actual_format: c_int = 0;
+    let mut items: c_ulong = 0;
+    let mut bytes_after: c_ulong = 0;
+    let mut value: *mut c_uchar = ptr::null_mut();
+
+    let status = XGetWindowProperty(
+        display,
+        window,
+        property,
+        0,
+        1024,
+        0,
+        kind,
+        &mut actual_type,
+        &mut actual_format,
+        &mut items,
+        &mut bytes_after,
+        &mut value,
+    );
+
+    if value.is_null() {
+        return None;
+    }
+
+    // Items of format 32 are stored as longs by Xlib, whatever their size on the wire
+    let item_size = match actual_format {
+        8 => 1,
+        16 => 2,
+        32 => mem::size_of::<c_ulong>(),
+        _ => 0,
+    };
+    let bytes = if status == 0 && actual_type == kind {
+        Some(slice::from_raw_parts(value, items as usize * item_size).to_vec())
+    } else {
+        None
+    };
+    XFree(value as *mut _);
+
+    bytes
+}
+
+/// Reads the name of the window manager from the child window it announces
+/// through _NET_SUPPORTING_WM_CHECK, as required by the EWMH spec
+pub unsafe fn get_wm_name() -> Option<String> {
+    let display: *mut Display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return None;
+    }
+
+    let name = (|| {
+        let root = XDefaultRootWindow(display);
+        let check = get_property(display, root, "_NET_SUPPORTING_WM_CHECK", XA_WINDOW)?;
+        let check = check.get(..mem::size_of::<Window>())?;
+        let check = ptr::read_unaligned(check.as_ptr() as *const Window);
+
+        let utf8_string = CString::new("UTF8_STRING").ok()?;
+        let utf8_string = XInternAtom(display, utf8_string.as_ptr(), 0);
+        let name = get_property(display, check, "_NET_WM_NAME", utf8_string)?;
+
+        let name = String::from_utf8_lossy(&name).trim_end_matches('\0').trim().to_string();
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    })();
+
+    XCloseDisplay(display);
+
+    name
+}
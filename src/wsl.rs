@@ -0,0 +1,145 @@
+use crate::util::{read_le_u16, read_le_u32};
+
+use std::{
+    env,
+    fs::{self, File},
+    os::unix::fs::FileExt,
+    path::PathBuf,
+};
+
+/// Signature of VS_FIXEDFILEINFO, the fixed part of the version resource of PE files
+const FIXED_FILE_INFO_SIGNATURE: [u8; 4] = 0xFEEF_04BD_u32.to_le_bytes();
+/// How much of a PE file is read to find its section table, which follows the DOS stub
+const PE_HEADERS_SIZE: usize = 4096;
+/// Size past which the resources of kernel32.dll are taken for something else
+const MAX_RESOURCES_SIZE: usize = 16 * 1024 * 1024;
+
+/// Version of the Windows Subsystem for Linux the system runs under, if any
+pub fn get_wsl_version() -> Option<u8> {
+    // e.g. "4.4.0-19041-Microsoft" on WSL1 or "5.15.90.1-microsoft-standard-WSL2" on WSL2
+    let release = fs::read_to_string("/proc/sys/kernel/osrelease").ok()?;
+
+    if release.contains("WSL2") || release.contains("microsoft-standard") {
+        Some(2)
+    } else if release.contains("Microsoft") {
+        Some(1)
+    } else if env::var_os("WSL_INTEROP").is_some() || env::var_os("WSL_DISTRO_NAME").is_some() {
+        // Only WSL2 can boot a custom kernel, which may not mention Microsoft at all
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// The Windows drives are mounted under /mnt/ unless /etc/wsl.conf says otherwise, e.g.
+/// ```ini
+/// [automount]
+/// root = /windows/
+/// ```
+fn drives_root() -> PathBuf {
+    let wsl_conf = fs::read_to_string("/etc/wsl.conf").unwrap_or_default();
+
+    let mut in_automount = false;
+    let mut root = None;
+
+    for line in wsl_conf.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_automount = line == "[automount]";
+        } else if let Some((key, value)) = line.split_once('=') {
+            if in_automount && key.trim() == "root" {
+                root = Some(value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+
+    PathBuf::from(root.unwrap_or_else(|| "/mnt/".to_string()))
+}
+
+/// Finds the offset and the size of the resources of a PE file in its section table,
+/// so that the rest of it is never read through the slow drvfs mount
+fn find_resources(file: &File) -> Option<(u64, usize)> {
+    let mut headers = [0_u8; PE_HEADERS_SIZE];
+    let len = file.read_at(&mut headers, 0).ok()?;
+    let headers = &headers[..len];
+
+    // The DOS header points at the PE signature, which the COFF header follows
+    let pe = read_le_u32(headers, 0x3C)? as usize;
+    if headers.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let sections = read_le_u16(headers, pe + 6)? as usize;
+    let optional_header_size = read_le_u16(headers, pe + 20)? as usize;
+
+    // Entries of the section table are 40 bytes long, starting with the name of the section
+    let table = pe + 24 + optional_header_size;
+    (0..sections).map(|section| table + section * 40).find_map(|entry| {
+        if headers.get(entry..entry + 8)? != b".rsrc\0\0\0" {
+            return None;
+        }
+        let size = read_le_u32(headers, entry + 16)? as usize;
+        let offset = read_le_u32(headers, entry + 20)?;
+        Some((u64::from(offset), size))
+    })
+}
+
+/// Reads the product version of kernel32.dll, which follows the Windows build,
+/// e.g. "10.0.22621.2428"
+fn read_windows_version() -> Option<String> {
+    let dll = drives_root().join("c").join("Windows").join("System32").join("kernel32.dll");
+    let file = File::open(dll).ok()?;
+
+    let (offset, size) = find_resources(&file)?;
+    if size > MAX_RESOURCES_SIZE {
+        return None;
+    }
+    let mut bytes = vec![0_u8; size];
+    file.read_exact_at(&mut bytes, offset).ok()?;
+
+    let offset = bytes
+        .windows(FIXED_FILE_INFO_SIGNATURE.len())
+        .position(|window| window == FIXED_FILE_INFO_SIGNATURE)?;
+
+    // The signature is followed by the structure version, then the file version
+    // and the product version, each as two 32-bit halves
+    let product_ms = read_le_u32(&bytes, offset + 16)?;
+    let product_ls = read_le_u32(&bytes, offset + 20)?;
+
+    Some(format!(
+        "{}.{}.{}.{}",
+        product_ms >> 16,
+        product_ms & 0xFFFF,
+        product_ls >> 16,
+        product_ls & 0xFFFF
+    ))
+}
+
+/// Describes the Windows host, e.g. "Windows 11 (10.0.22621.2428)"
+pub fn get_windows_host() -> Option<String> {
+    let version = read_windows_version().or_else(|| {
+        // WSL1 reports the Windows build in its release, e.g. "4.4.0-19041-Microsoft"
+        let release = fs::read_to_string("/proc/sys/kernel/osrelease").ok()?;
+        let build = release.split('-').nth(1)?;
+        build.parse::<u32>().ok()?;
+        Some(format!("10.0.{}", build))
+    })?;
+
+    // Windows 11 still calls itself 10.0, but starts at build 22000
+    let build: u32 = version.split('.').nth(2)?.parse().ok()?;
+    let name = if build >= 22000 { "Windows 11" } else { "Windows 10" };
+
+    Some(format!("{} ({})", name, version))
+}
+
+/// e.g. "WSL2 (Ubuntu) on Windows 11 (10.0.22621.2428)"
+pub fn get_wsl_info() -> Option<String> {
+    let mut info = format!("WSL{}", get_wsl_version()?);
+
+    if let Ok(distro) = env::var("WSL_DISTRO_NAME") {
+        info.push_str(&format!(" ({})", distro));
+    }
+    if let Some(host) = get_windows_host() {
+        info.push_str(&format!(" on {}", host));
+    }
+
+    Some(info)
+}
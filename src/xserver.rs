@@ -0,0 +1,91 @@
+use crate::util::get_process_names;
+
+use std::{env, fs, path::PathBuf};
+
+/// X.Org packs its versions in the release number as MMmmppPPP, e.g.
+/// 12101004 for 1.21.1.4, which it calls 21.1.4 since 1.21
+#[cfg(feature = "use_xlib")]
+fn format_release(release: i32) -> String {
+    let major = release / 10_000_000;
+    let minor = release / 100_000 % 100;
+    let patch = release / 1000 % 100;
+    let patch2 = release % 1000;
+
+    if major == 1 && minor >= 21 {
+        format!("{}.{}.{}", minor, patch, patch2)
+    } else {
+        format!("{}.{}.{}", major, minor, patch)
+    }
+}
+
+/// Formats a version from the Xorg log the same way, e.g. "1.21.1.4" -> "21.1.4"
+fn format_log_version(version: &str) -> String {
+    let minor = version
+        .strip_prefix("1.")
+        .and_then(|rest| rest.split('.').next()?.parse::<u32>().ok());
+
+    match minor {
+        Some(minor) if minor >= 21 => version[2..].to_string(),
+        _ => version.to_string(),
+    }
+}
+
+/// Reads the version the server logged when starting, e.g. "X.Org X Server 1.21.1.4"
+fn read_xorg_log_version(display: &str) -> Option<String> {
+    // e.g. ":0" or ":1.0"
+    let number = display.split(':').nth(1)?.split('.').next()?;
+    let log_name = format!("Xorg.{}.log", number);
+
+    let mut logs = vec![PathBuf::from("/var/log/").join(&log_name)];
+    if let Some(home) = env::var_os("HOME") {
+        // Where rootless Xorg logs to
+        logs.insert(0, PathBuf::from(home).join(".local/share/xorg").join(&log_name));
+    }
+
+    logs.iter().find_map(|log| {
+        let log = fs::read_to_string(log).ok()?;
+        let version = log.lines().find_map(|line| line.split("X.Org X Server ").nth(1))?;
+        Some(format_log_version(version.trim()))
+    })
+}
+
+/// Guesses the server from the running processes, for when it can't be queried
+fn get_server_from_processes(display: &str) -> Option<String> {
+    let processes = get_process_names();
+    let is_running = |name: &str| processes.iter().any(|process| process == name);
+
+    if is_running("Xwayland") {
+        Some("Xwayland".to_string())
+    } else if is_running("Xorg") || is_running("X") {
+        match read_xorg_log_version(display) {
+            Some(version) => Some(format!("Xorg {}", version)),
+            None => Some("Xorg".to_string()),
+        }
+    } else {
+        None
+    }
+}
+
+/// Name and version of the X server, e.g. "Xorg 21.1.4" or "Xwayland 23.2.4"
+pub fn get_x_server() -> Option<String> {
+    let display = env::var("DISPLAY").ok().filter(|display| !display.is_empty())?;
+
+    #[cfg(feature = "use_xlib")]
+    {
+        if let Some((vendor, release, is_xwayland)) =
+            unsafe { crate::xserverx11::get_server_release() }
+        {
+            let name = if is_xwayland {
+                "Xwayland".to_string()
+            } else if vendor.contains("X.Org") {
+                "Xorg".to_string()
+            } else {
+                vendor
+            };
+
+            return Some(format!("{} {}", name, format_release(release)));
+        }
+    }
+
+    get_server_from_processes(&display)
+}
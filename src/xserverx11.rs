@@ -0,0 +1,30 @@
+use x11::xlib::{Display, XCloseDisplay, XOpenDisplay, XQueryExtension, XServerVendor, XVendorRelease};
+
+use std::{ffi::CString, os::raw::c_int, ptr};
+
+/// Queries the X server for its vendor, e.g. "The X.Org Foundation", its release number,
+/// e.g. 12101004, and whether it's Xwayland, which has the XWAYLAND extension
+pub unsafe fn get_server_release() -> Option<(String, i32, bool)> {
+    let display: *mut Display = XOpenDisplay(ptr::null());
+    if display.is_null() {
+        return None;
+    }
+
+    let vendor_ptr = XServerVendor(display);
+    let vendor = if vendor_ptr.is_null() {
+        String::new()
+    } else {
+        crate::util::char_ptr_to_string(vendor_ptr)
+    };
+    let release = XVendorRelease(display);
+
+    let extension = CString::new("XWAYLAND").ok();
+    let (mut opcode, mut event, mut error): (c_int, c_int, c_int) = (0, 0, 0);
+    let is_xwayland = extension.is_some_and(|extension| {
+        XQueryExtension(display, extension.as_ptr(), &mut opcode, &mut event, &mut error) != 0
+    });
+
+    XCloseDisplay(display);
+
+    Some((vendor, release, is_xwayland))
+}
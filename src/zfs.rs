@@ -0,0 +1,83 @@
+use crate::disk::{self, Mount};
+
+use std::{fs, path::Path};
+
+#[derive(Debug)]
+pub struct ZfsPool {
+    pub name:  String,
+    // e.g. "ONLINE" or "DEGRADED"
+    pub state: Option<String>,
+    // Space used by the pool's mounted datasets, in bytes
+    pub used:  u64,
+    // `used` plus the space still available to the datasets, in bytes
+    pub total: u64,
+}
+
+/// Name of the pool a ZFS mount belongs to, e.g. "rpool" for "rpool/ROOT/ubuntu"
+fn pool_name(mount: &Mount) -> &str {
+    mount.source.split('/').next().unwrap_or(&mount.source)
+}
+
+/// Lists the imported ZFS pools.
+///
+/// statvfs reports each dataset as if it were its own filesystem, so the
+/// space of a pool is computed by adding what its mounted datasets use to the
+/// space they share. Snapshots and unmounted datasets aren't accounted for.
+pub fn get_pools() -> Vec<ZfsPool> {
+    let entries = match fs::read_dir("/proc/spl/kstat/zfs/") {
+        Ok(entries) => entries,
+        // The ZFS module isn't loaded
+        Err(_) => return vec![],
+    };
+
+    let zfs_mounts: Vec<Mount> = disk::get_mounts()
+        .into_iter()
+        .filter(|mount| mount.fs_type == "zfs")
+        .collect();
+
+    let mut pools: Vec<ZfsPool> = entries
+        .filter_map(Result::ok)
+        // Besides a directory for each pool, there are files with module-wide statistics
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            let mut used = 0;
+            let mut available = 0;
+            for mount in zfs_mounts.iter().filter(|mount| pool_name(mount) == name) {
+                if let Some((dataset_used, dataset_total)) = disk::get_usage(&mount.mount_point) {
+                    used += dataset_used;
+                    available = dataset_total - dataset_used;
+                }
+            }
+
+            ZfsPool {
+                state: read_state(&entry.path()),
+                used,
+                total: used + available,
+                name,
+            }
+        })
+        .collect();
+
+    pools.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    pools
+}
+
+/// OpenZFS 0.8+ exposes the health of each pool in /proc/spl/kstat/zfs/{pool}/state
+fn read_state(pool_dir: &Path) -> Option<String> {
+    fs::read_to_string(pool_dir.join("state"))
+        .ok()
+        .map(|state| state.trim().to_string())
+}
+
+/// Gets the pool holding the given mount, if it's a ZFS dataset
+pub fn get_pool_of(mount: &Mount) -> Option<ZfsPool> {
+    if mount.fs_type != "zfs" {
+        return None;
+    }
+
+    get_pools()
+        .into_iter()
+        .find(|pool| pool.name == pool_name(mount))
+}